@@ -0,0 +1,110 @@
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::RuntimeDir;
+
+/// One line of `activity.log`: a state-changing subcommand run against an
+/// environment. Used to reconstruct what happened to a challenge
+/// afterwards — when it was built, what was run inside it, when the flag
+/// was captured — so keep `action`/`args` close to the CLI invocation
+/// itself rather than some internal name.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Event {
+    pub timestamp: u64,
+    pub action: String,
+    pub args: Vec<String>,
+    pub exit_code: Option<i32>,
+}
+
+fn activity_log_path(runtime: &RuntimeDir) -> PathBuf {
+    runtime.root().join("activity.log")
+}
+
+/// Appends a structured event to `runtime`'s `activity.log` (JSON lines).
+/// Never fails the caller's primary operation: an error here degrades to
+/// a stderr warning instead of propagating, since losing a log line is
+/// far less bad than losing the build/up/etc. it was trying to record.
+pub fn log_event(runtime: &RuntimeDir, action: &str, args: &[String], exit_code: Option<i32>) {
+    let event = Event {
+        timestamp: now_unix(),
+        action: action.to_string(),
+        args: args.to_vec(),
+        exit_code,
+    };
+    let line = match serde_json::to_string(&event) {
+        Ok(line) => line,
+        Err(e) => {
+            eprintln!("warning: failed to serialize activity event: {e}");
+            return;
+        }
+    };
+    if let Err(e) = append_line(&activity_log_path(runtime), &line) {
+        eprintln!("warning: failed to write activity log: {e}");
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+/// Reads back every event at or after `since` (a unix timestamp), in the
+/// order they were logged. Unparseable lines are skipped rather than
+/// failing the whole read — a half-written line from a crash shouldn't
+/// hide everything logged before it.
+pub fn read_events(runtime: &RuntimeDir, since: Option<u64>) -> Vec<Event> {
+    let Ok(contents) = std::fs::read_to_string(activity_log_path(runtime)) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Event>(line).ok())
+        .filter(|event| since.is_none_or(|s| event.timestamp >= s))
+        .collect()
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logged_events_round_trip_through_read_events() {
+        let runtime = RuntimeDir::new(&format!("activity-test-{}", std::process::id()));
+        runtime.ensure_exists().unwrap();
+
+        log_event(&runtime, "build", &["--tag".to_string(), "chall".to_string()], Some(0));
+        log_event(&runtime, "up", &[], None);
+
+        let events = read_events(&runtime, None);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].action, "build");
+        assert_eq!(events[0].args, vec!["--tag", "chall"]);
+        assert_eq!(events[0].exit_code, Some(0));
+        assert_eq!(events[1].action, "up");
+        assert_eq!(events[1].exit_code, None);
+
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+
+    #[test]
+    fn since_filters_out_older_events() {
+        let runtime = RuntimeDir::new(&format!("activity-test-since-{}", std::process::id()));
+        runtime.ensure_exists().unwrap();
+
+        log_event(&runtime, "init", &[], None);
+        let events = read_events(&runtime, Some(u64::MAX));
+        assert!(events.is_empty());
+
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use crate::config::ToolConfig;
+
+/// ELF's `e_ident[EI_CLASS]` byte (offset 4): `1` is `ELFCLASS32`, `2` is
+/// `ELFCLASS64`. Reading just the header is enough to tell a 32-bit
+/// challenge binary apart from a 64-bit one without a full ELF parser.
+const EI_CLASS_OFFSET: usize = 4;
+const ELFCLASS32: u8 = 1;
+
+/// True if `path` is an ELF binary built for a 32-bit architecture.
+/// `Ok(false)` (not an error) for anything that isn't a valid 32/64-bit
+/// ELF, e.g. a script or a non-challenge file `init` happened to scan.
+pub fn is_32bit_elf(path: &Path) -> std::io::Result<bool> {
+    let data = std::fs::read(path)?;
+    Ok(data.len() > EI_CLASS_OFFSET
+        && data.starts_with(b"\x7fELF")
+        && data[EI_CLASS_OFFSET] == ELFCLASS32)
+}
+
+/// Shallow (non-recursive) scan of `dir` for a 32-bit ELF, for `init`'s
+/// `i386: true` suggestion. Only looks at `dir`'s immediate files, the
+/// same scope `programs_dir` detection elsewhere in this tool stays
+/// within, rather than walking the whole challenge directory tree.
+pub fn contains_32bit_elf(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .any(|entry| is_32bit_elf(&entry.path()).unwrap_or(false))
+}
+
+/// The `dpkg --add-architecture i386` + multiarch package install tool
+/// [`crate::config::Config`]'s `i386` option prepends ahead of every
+/// other tool, so the architecture is registered before any other tool's
+/// `apt-get update`/`install` layer runs against it.
+pub fn i386_tool() -> ToolConfig {
+    ToolConfig {
+        name: "i386-multiarch".to_string(),
+        script: vec![
+            "RUN dpkg --add-architecture i386".to_string(),
+            "RUN apt-get update && apt-get install -y libc6:i386 libstdc++6:i386 gcc-multilib \
+             libc6-dbg:i386"
+                .to_string(),
+        ],
+        build_only: false,
+        append: false,
+        artifacts: Vec::new(),
+        verify: vec!["dpkg --print-foreign-architectures | grep -q i386".to_string()],
+        secrets: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_elf(path: &Path, class: u8) {
+        let mut data = vec![0u8; 16];
+        data[0..4].copy_from_slice(b"\x7fELF");
+        data[EI_CLASS_OFFSET] = class;
+        std::fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn recognizes_a_32bit_elf() {
+        let path = std::env::temp_dir().join(format!("pwnenv-arch-test-32-{}", std::process::id()));
+        write_elf(&path, ELFCLASS32);
+        assert!(is_32bit_elf(&path).unwrap());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn does_not_flag_a_64bit_elf() {
+        let path = std::env::temp_dir().join(format!("pwnenv-arch-test-64-{}", std::process::id()));
+        write_elf(&path, 2);
+        assert!(!is_32bit_elf(&path).unwrap());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn non_elf_file_is_not_flagged() {
+        let path = std::env::temp_dir().join(format!("pwnenv-arch-test-notelf-{}", std::process::id()));
+        std::fs::write(&path, b"not an elf").unwrap();
+        assert!(!is_32bit_elf(&path).unwrap());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn scan_finds_a_32bit_elf_among_other_files() {
+        let dir = std::env::temp_dir().join(format!("pwnenv-arch-scan-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("README.md"), b"hello").unwrap();
+        write_elf(&dir.join("chall"), ELFCLASS32);
+        assert!(contains_32bit_elf(&dir));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn scan_finds_nothing_in_an_empty_dir() {
+        let dir = std::env::temp_dir().join(format!("pwnenv-arch-scan-empty-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(!contains_32bit_elf(&dir));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
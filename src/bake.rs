@@ -0,0 +1,139 @@
+use std::path::Path;
+
+use walkdir::WalkDir;
+
+use crate::error::{PwnenvError, Result};
+
+/// Above this size, baking a path gets a printed warning instead of
+/// silently bloating a cached image layer — `init` re-copies `bake`
+/// into the build context every run, so a multi-GB IDA database baked
+/// by mistake costs far more than the usual bind-mount alternative.
+const SIZE_WARNING_THRESHOLD: u64 = 512 * 1024 * 1024;
+
+/// Copies `config.bake`'s paths (each relative to `host_dir`, the
+/// challenge directory) into `dest` (`RuntimeDir::bake_dir`), preserving
+/// their relative paths so [`crate::docker::dockerfile::render_dockerfile`]'s
+/// `COPY bake/<path> /workspace/<path>` lines land on the right files.
+/// Errors if a path doesn't exist; only warns if one exceeds
+/// [`SIZE_WARNING_THRESHOLD`], since a big-but-intentional bake (the
+/// whole point of this option) shouldn't be blocked outright.
+pub fn copy_bake(bake: &[String], host_dir: &Path, dest: &Path) -> Result<()> {
+    std::fs::remove_dir_all(dest).ok();
+    if bake.is_empty() {
+        return Ok(());
+    }
+
+    for relative in bake {
+        let source = host_dir.join(relative);
+        if !source.exists() {
+            return Err(PwnenvError::BakePathMissing(source));
+        }
+
+        let size = path_size(&source)?;
+        if size > SIZE_WARNING_THRESHOLD {
+            eprintln!(
+                "warning: bake path '{relative}' is {} MiB; it's copied into the build \
+                 context on every `init` and baked into a cached image layer. If it \
+                 changes often, `workspace_dir` or an extra `--mount` will serve you \
+                 better than re-baking it each time.",
+                size / (1024 * 1024),
+            );
+        }
+
+        let target = dest.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        copy_recursive(&source, &target)?;
+    }
+    Ok(())
+}
+
+fn path_size(path: &Path) -> Result<u64> {
+    if path.is_file() {
+        return Ok(std::fs::metadata(path)?.len());
+    }
+    let mut total = 0;
+    for entry in WalkDir::new(path) {
+        let entry = entry.map_err(std::io::Error::from)?;
+        if entry.file_type().is_file() {
+            total += entry.metadata().map_err(std::io::Error::from)?.len();
+        }
+    }
+    Ok(total)
+}
+
+fn copy_recursive(source: &Path, target: &Path) -> Result<()> {
+    if source.is_file() {
+        std::fs::copy(source, target)?;
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(target)?;
+    for entry in WalkDir::new(source).min_depth(1) {
+        let entry = entry.map_err(std::io::Error::from)?;
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        let dest_path = target.join(relative);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&dest_path)?;
+        } else if entry.file_type().is_file() {
+            std::fs::copy(entry.path(), &dest_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (std::path::PathBuf, std::path::PathBuf) {
+        let base = std::env::temp_dir().join(format!("pwnenv-bake-test-{}", std::process::id()));
+        std::fs::remove_dir_all(&base).ok();
+        let host_dir = base.join("host");
+        let dest = base.join("dest");
+        std::fs::create_dir_all(&host_dir).unwrap();
+        (host_dir, dest)
+    }
+
+    #[test]
+    fn a_single_file_is_copied_preserving_its_relative_path() {
+        let (host_dir, dest) = setup();
+        std::fs::write(host_dir.join("rootfs.img"), b"image contents").unwrap();
+
+        copy_bake(&["rootfs.img".to_string()], &host_dir, &dest).unwrap();
+
+        assert_eq!(std::fs::read(dest.join("rootfs.img")).unwrap(), b"image contents");
+    }
+
+    #[test]
+    fn a_directory_is_copied_recursively() {
+        let (host_dir, dest) = setup();
+        std::fs::create_dir_all(host_dir.join("idb/sub")).unwrap();
+        std::fs::write(host_dir.join("idb/challenge.i64"), b"idb contents").unwrap();
+        std::fs::write(host_dir.join("idb/sub/notes.txt"), b"notes").unwrap();
+
+        copy_bake(&["idb".to_string()], &host_dir, &dest).unwrap();
+
+        assert_eq!(std::fs::read(dest.join("idb/challenge.i64")).unwrap(), b"idb contents");
+        assert_eq!(std::fs::read(dest.join("idb/sub/notes.txt")).unwrap(), b"notes");
+    }
+
+    #[test]
+    fn missing_bake_path_is_an_error() {
+        let (host_dir, dest) = setup();
+        let err = copy_bake(&["does-not-exist".to_string()], &host_dir, &dest).unwrap_err();
+        assert!(matches!(err, PwnenvError::BakePathMissing(_)));
+    }
+
+    #[test]
+    fn empty_bake_list_clears_any_stale_dest_without_erroring() {
+        let (host_dir, dest) = setup();
+        std::fs::create_dir_all(&dest).unwrap();
+        std::fs::write(dest.join("stale"), b"old").unwrap();
+
+        copy_bake(&[], &host_dir, &dest).unwrap();
+
+        assert!(!dest.exists());
+    }
+}
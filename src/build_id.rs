@@ -0,0 +1,87 @@
+use std::path::Path;
+
+/// Heuristic `NT_GNU_BUILD_ID` extraction, in the same spirit as
+/// [`crate::arch::is_32bit_elf`]/[`crate::libc_detect::detect_glibc_version`]:
+/// a raw byte scan for the note's `"GNU\0"` name marker, rather than
+/// walking the section/program header table properly. Good enough to
+/// label a copied-out libc/loader with the build ID that ties it to the
+/// debug symbols a distro package would ship, without pulling in a full
+/// ELF parser.
+const NOTE_NAME: &[u8] = b"GNU\0";
+const NT_GNU_BUILD_ID: u32 = 3;
+
+/// Lowercase hex build ID for the first `NT_GNU_BUILD_ID` note found in
+/// `path`, or `None` if it isn't an ELF with one (e.g. stripped of notes
+/// entirely, which is rare but not impossible).
+pub fn extract_build_id(path: &Path) -> std::io::Result<Option<String>> {
+    let data = std::fs::read(path)?;
+    Ok(find_build_id(&data))
+}
+
+fn find_build_id(data: &[u8]) -> Option<String> {
+    let mut start = 0;
+    while let Some(pos) = find_subslice(&data[start..], NOTE_NAME) {
+        let idx = start + pos;
+        if idx >= 12 {
+            let namesz = u32::from_le_bytes(data[idx - 12..idx - 8].try_into().ok()?);
+            let descsz = u32::from_le_bytes(data[idx - 8..idx - 4].try_into().ok()?);
+            let note_type = u32::from_le_bytes(data[idx - 4..idx].try_into().ok()?);
+            if namesz == 4 && note_type == NT_GNU_BUILD_ID {
+                let desc_start = idx + NOTE_NAME.len();
+                let desc_end = desc_start + descsz as usize;
+                if desc_end <= data.len() {
+                    return Some(hex_encode(&data[desc_start..desc_end]));
+                }
+            }
+        }
+        start = idx + 1;
+    }
+    None
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note_bytes(build_id: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(&(build_id.len() as u32).to_le_bytes());
+        data.extend_from_slice(&NT_GNU_BUILD_ID.to_le_bytes());
+        data.extend_from_slice(NOTE_NAME);
+        data.extend_from_slice(build_id);
+        data
+    }
+
+    #[test]
+    fn extracts_a_build_id_note() {
+        let mut data = b"junk before".to_vec();
+        data.extend(note_bytes(&[0xde, 0xad, 0xbe, 0xef]));
+        data.extend_from_slice(b"junk after");
+        assert_eq!(find_build_id(&data), Some("deadbeef".to_string()));
+    }
+
+    #[test]
+    fn ignores_a_gnu_name_marker_from_an_unrelated_note_type() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes()); // NT_GNU_ABI_TAG, not build-id
+        data.extend_from_slice(NOTE_NAME);
+        data.extend_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(find_build_id(&data), None);
+    }
+
+    #[test]
+    fn data_with_no_note_at_all_is_none() {
+        assert_eq!(find_build_id(b"not an elf, no notes here"), None);
+    }
+}
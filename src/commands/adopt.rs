@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::activity;
+use crate::config::Config;
+use crate::docker::dockerfile::render_dockerfile;
+use crate::docker::render_compose;
+use crate::error::{PwnenvError, Result};
+use crate::labels::{self, Labels};
+use crate::mounts;
+use crate::runtime::{setup_minimum_requirements, RuntimeDir};
+
+/// Re-registers `env_name` against `invoked_from` after state loss (a
+/// wiped `~/.local/share/pwnenv`, a fresh machine, a teammate's exported
+/// compose file) so `enter`/`status`/`kill` work again without a full
+/// `init` + `build`. Tries, in order:
+///
+/// 1. `container_id` (or, if unset, a container labeled
+///    `dev.pwnenv.env_name=<env_name>`, see [`labels::env_name_filter`]):
+///    reads nothing back from it to put in the runtime dir (there's
+///    nothing in a container to reconstruct mounts/Dockerfile from), but
+///    confirms its `dev.pwnenv.host_dir` label agrees with `invoked_from`
+///    before registering.
+/// 2. An existing runtime dir for `env_name` (nothing to recover, just
+///    re-registers `invoked_from` against it).
+/// 3. From scratch: regenerates `docker-compose.yml`/`Dockerfile` from
+///    `config` without building or starting anything, same as `init`
+///    would, so at least those files exist for `enter`/`status`/a manual
+///    `pwnenv compose`.
+///
+/// Rejects adopting `env_name` against a directory other than the one
+/// it's already registered to (see [`RuntimeDir::host_dir`]) — silently
+/// repointing it would leave whichever environment was there first
+/// orphaned without a word.
+pub fn adopt(env_name: &str, config: &Config, invoked_from: &Path, container_id: Option<&str>) -> Result<()> {
+    crate::host_path::validate(invoked_from)?;
+
+    let runtime_existed = RuntimeDir::new(env_name).root().exists();
+    let runtime = setup_minimum_requirements(env_name)?;
+
+    if let Some(existing) = runtime.host_dir() {
+        if existing != invoked_from {
+            return Err(PwnenvError::AdoptConflict {
+                name: env_name.to_string(),
+                existing,
+                attempted: invoked_from.to_path_buf(),
+            });
+        }
+    }
+
+    if let Some(container_host_dir) = adopt_from_container(env_name, container_id)? {
+        if container_host_dir != invoked_from {
+            eprintln!(
+                "warning: the container's dev.pwnenv.host_dir label ({}) does not match the \
+                 directory adopt was run from ({}); registering the latter.",
+                container_host_dir.display(),
+                invoked_from.display()
+            );
+        }
+        runtime.set_host_dir(invoked_from)?;
+        activity::log_event(&runtime, "adopt", &["container".to_string()], Some(0));
+        println!("{env_name}: adopted from a running container.");
+        return Ok(());
+    }
+
+    runtime.set_host_dir(invoked_from)?;
+
+    if runtime_existed {
+        activity::log_event(&runtime, "adopt", &["runtime-dir".to_string()], Some(0));
+        println!("{env_name}: re-registered against the existing runtime dir at {}.", runtime.root().display());
+        return Ok(());
+    }
+
+    let resolved_mounts = mounts::resolve(&[], &config.mounts, invoked_from, false)?;
+    runtime.set_mounts(&resolved_mounts)?;
+
+    let image_tag = crate::runtime::resolve_image_tag(None, &runtime, config);
+    let dockerfile = render_dockerfile(config, config.programs_dir.is_some(), None);
+    std::fs::write(runtime.root().join("Dockerfile"), &dockerfile)?;
+
+    let labels = Labels::new(env_name, config, invoked_from);
+    let compose = render_compose(config, &image_tag, env_name, invoked_from, None, &resolved_mounts, &runtime.extra_ports(), &labels)?;
+    std::fs::write(runtime.root().join("docker-compose.yml"), &compose)?;
+
+    activity::log_event(&runtime, "adopt", &["from-scratch".to_string()], Some(0));
+    println!(
+        "{env_name}: adopted from scratch; regenerated Dockerfile/docker-compose.yml without \
+         building. Run `pwnenv build` when you're ready."
+    );
+    Ok(())
+}
+
+/// Looks up the container to adopt from — `container_id` if given,
+/// otherwise the first container (running or not) labeled
+/// `dev.pwnenv.env_name=<env_name>` — and returns its recorded
+/// `dev.pwnenv.host_dir` label, decoded back to a path. `None` (not an
+/// error) when no such container exists, since "the container's gone
+/// too" is exactly the case `adopt`'s other two sources exist for.
+fn adopt_from_container(env_name: &str, container_id: Option<&str>) -> Result<Option<PathBuf>> {
+    let container = match container_id {
+        Some(id) => Some(id.to_string()),
+        None => find_container_by_label(env_name)?,
+    };
+    let Some(container) = container else {
+        return Ok(None);
+    };
+
+    let output = Command::new("docker")
+        .args(["inspect", "-f", "{{json .Config.Labels}}", &container])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let recorded: HashMap<String, String> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    Ok(recorded.get(labels::HOST_DIR).map(PathBuf::from))
+}
+
+fn find_container_by_label(env_name: &str) -> Result<Option<String>> {
+    let output = Command::new("docker")
+        .args(["ps", "-a", "-q", "--filter", &labels::env_name_filter(env_name)])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty()))
+}
@@ -0,0 +1,114 @@
+use std::process::Command;
+
+use crate::activity;
+use crate::error::{PwnenvError, Result};
+use crate::recordings::shell_quote;
+use crate::runtime::{container_name, RuntimeDir};
+
+/// Container path of the Ghidra headless post-script (baked into the
+/// image by the `"ghidra"` tool preset, see `tool_presets::lookup`) that
+/// dumps every function's decompiled C into the directory it's given.
+const GHIDRA_EXPORT_SCRIPT: &str = "/opt/pwnenv/ghidra-scripts/ExportDecompiledC.py";
+
+/// Runs a headless analysis pass over `binary` inside the environment's
+/// container, using whichever reversing tool was asked for. For `ghidra`,
+/// decompiled C for every function is exported to `<binary>.decomp/` next
+/// to the binary; a prior successful run there (a `ghidra.log` inside it)
+/// is reused unless `force`.
+pub fn analyze(env_name: &str, tool: &str, binary: &str, force: bool) -> Result<()> {
+    let container = container_name(env_name);
+    let decomp_dir = format!("{binary}.decomp");
+    let log_path = format!("{decomp_dir}/ghidra.log");
+
+    if tool == "ghidra" && !force && log_exists(&container, &log_path)? {
+        println!(
+            "{binary}: {decomp_dir} already has a completed analysis; skipping (pass --force to redo)"
+        );
+        return Ok(());
+    }
+
+    let script = script_for(tool, binary, &decomp_dir, &log_path)?;
+
+    let status = Command::new("docker")
+        .args(["exec", "-it", &container, "/bin/sh", "-c", &script])
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+
+    let runtime = RuntimeDir::new(env_name);
+    activity::log_event(&runtime, "analyze", &[tool.to_string(), binary.to_string()], status.code());
+
+    if !status.success() {
+        let message = if tool == "ghidra" {
+            format!("headless ghidra analysis exited with {status}; see {log_path} inside the container for details")
+        } else {
+            format!("headless {tool} analysis exited with {status}")
+        };
+        return Err(PwnenvError::Docker(message));
+    }
+    Ok(())
+}
+
+/// Builds the `/bin/sh -c` script for `tool` over `binary`, with every
+/// untrusted argument passed through [`shell_quote`] before interpolation
+/// so a `binary` containing shell metacharacters can't break out of it.
+fn script_for(tool: &str, binary: &str, decomp_dir: &str, log_path: &str) -> Result<String> {
+    let quoted_binary = shell_quote(binary);
+    match tool {
+        "ghidra" => Ok(format!(
+            "mkdir -p {decomp} && /opt/ghidra/support/analyzeHeadless /tmp pwnenv-project \
+             -import {quoted_binary} -deleteProject -analysisTimeoutPerFile 300 \
+             -postScript {GHIDRA_EXPORT_SCRIPT} {decomp} -log {log}",
+            decomp = shell_quote(decomp_dir),
+            log = shell_quote(log_path),
+        )),
+        "radare2" | "r2" => Ok(format!("r2 -q -c 'aaa; afl' {quoted_binary}")),
+        "rizin" | "rz" => Ok(format!("rizin -q -c 'aaa; afl' {quoted_binary}")),
+        other => Err(PwnenvError::Docker(format!(
+            "unknown analysis tool '{other}'; expected 'ghidra', 'radare2', or 'rizin'"
+        ))),
+    }
+}
+
+/// Whether `log_path` already exists inside `container`, used to decide
+/// whether a prior `ghidra` run's export can be reused.
+fn log_exists(container: &str, log_path: &str) -> Result<bool> {
+    let status = Command::new("docker")
+        .args(["exec", container, "/bin/sh", "-c", &format!("test -f {}", shell_quote(log_path))])
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    Ok(status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_tool_is_rejected() {
+        let err = script_for("objdump", "chall", "chall.decomp", "chall.decomp/ghidra.log").unwrap_err();
+        assert!(err.to_string().contains("unknown analysis tool"));
+    }
+
+    #[test]
+    fn binary_argument_is_shell_quoted_not_spliced_raw() {
+        // A `binary` crafted to break out of the script string (e.g.
+        // `pwnenv analyze myenv radare2 "chall'; rm -rf / #"`) must come
+        // back through shell_quote, not spliced in as-is.
+        let binary = "chall'; rm -rf / #";
+        let script = script_for("radare2", binary, "chall.decomp", "chall.decomp/ghidra.log").unwrap();
+        assert_eq!(script, format!("r2 -q -c 'aaa; afl' {}", shell_quote(binary)));
+    }
+
+    #[test]
+    fn ghidra_script_points_at_the_export_script_and_log() {
+        let script = script_for("ghidra", "chall", "chall.decomp", "chall.decomp/ghidra.log").unwrap();
+        assert!(script.contains(GHIDRA_EXPORT_SCRIPT));
+        assert!(script.contains("-log 'chall.decomp/ghidra.log'"));
+    }
+
+    #[test]
+    fn rizin_is_accepted_as_an_alias() {
+        assert!(script_for("rizin", "chall", "chall.decomp", "chall.decomp/ghidra.log").is_ok());
+        assert!(script_for("rz", "chall", "chall.decomp", "chall.decomp/ghidra.log").is_ok());
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::activity;
+use crate::config::{validate_image_tag, Config};
+use crate::docker::dockerfile::{render_dockerfile, CacheBust, FAILED_MARKER_DIR};
+use crate::docker::lint::{lint_tools, Severity};
+use crate::error::{PwnenvError, Result};
+use crate::labels::Labels;
+use crate::runtime::RuntimeDir;
+use crate::trace;
+
+/// The outcome of a build: which tools (if any) failed to install. Only
+/// populated when `fail_fast` is disabled; under `fail_fast` a failed tool
+/// aborts the build before a report can be produced.
+#[derive(Debug, Default)]
+pub struct BuildReport {
+    pub failed_tools: Vec<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build_image(
+    env_name: &str,
+    cli_tag: Option<&str>,
+    config: &Config,
+    assume_yes: bool,
+    host_dir: &Path,
+    trace_dir: Option<&Path>,
+    cli_build_args: &[String],
+    only: Option<&str>,
+) -> Result<BuildReport> {
+    if let Some(tag) = cli_tag {
+        validate_image_tag(tag)?;
+    }
+    let runtime = crate::runtime::setup_minimum_requirements(env_name)?;
+    let mut config = config.clone();
+    config.build_args.extend(parse_build_args(cli_build_args)?);
+    if let Some(plugin) = runtime.gdb_plugin_override() {
+        config.gdb_plugin = Some(plugin);
+        config.apply_gdb_plugin();
+    }
+    let config = &config;
+    let image_tag = crate::runtime::resolve_image_tag(cli_tag, &runtime, config);
+    let image_tag = image_tag.as_str();
+
+    if let Some(image_ref) = runtime.image_override() {
+        println!("{env_name}: using prebuilt image '{image_ref}'; skipping build.");
+        activity::log_event(&runtime, "build", &[image_tag.to_string()], Some(0));
+        return Ok(BuildReport::default());
+    }
+
+    if runtime.offline() {
+        if !image_exists(image_tag) {
+            return Err(PwnenvError::Docker(format!(
+                "{env_name} was init'ed with --offline, but no local image matches '{image_tag}'; \
+                 build it elsewhere and `docker save`/`docker load` it in, or drop --offline"
+            )));
+        }
+        println!("{env_name}: --offline; using existing local image '{image_tag}'.");
+        activity::log_event(&runtime, "build", &[image_tag.to_string()], Some(0));
+        return Ok(BuildReport::default());
+    }
+
+    let _lock = crate::lock::BuildLock::acquire(&runtime)?;
+
+    if image_exists(image_tag)
+        && !crate::prompt::confirm(&format!("image '{image_tag}' already exists; rebuild it?"), assume_yes)
+    {
+        return Err(PwnenvError::Docker("build cancelled".to_string()));
+    }
+
+    lint_or_abort(config, runtime.root())?;
+    crate::docker::compose::validate_dns(&config.dns)?;
+
+    if let Some(only) = only {
+        if !config.tools.iter().any(|tool| tool.name == only) {
+            return Err(PwnenvError::Docker(format!(
+                "--only '{only}' doesn't match any tool in pwnenv.yaml"
+            )));
+        }
+    }
+    let nonce = cache_bust_nonce();
+    let cache_bust = only.map(|tool| CacheBust { tool, nonce: &nonce });
+
+    let (copy_enabled, _) = runtime.programs_delivery();
+    let include_programs = copy_enabled && config.programs_dir.is_some();
+    let dockerfile = render_dockerfile(config, include_programs, cache_bust);
+    std::fs::write(runtime.root().join("Dockerfile"), &dockerfile)?;
+
+    if let Some(trace_dir) = trace_dir {
+        trace::write_artifact(trace_dir, "Dockerfile", &dockerfile)?;
+        trace::write_resolved_config(trace_dir, config)?;
+    }
+
+    let labels = Labels::new(env_name, config, host_dir);
+    let build_log = std::fs::File::create(runtime.build_log())?;
+    let mut build_command = Command::new("docker");
+    build_command
+        .args(["build", "-t", image_tag, "-f"])
+        .arg(runtime.root().join("Dockerfile"))
+        .args(config.dns.iter().flat_map(|server| ["--dns".to_string(), server.clone()]))
+        .args(labels.as_pairs().into_iter().flat_map(|(key, value)| ["--label".to_string(), format!("{key}={value}")]))
+        .args(config.build_args.iter().flat_map(|(key, value)| ["--build-arg".to_string(), format!("{key}={value}")]))
+        .args(
+            config
+                .secrets
+                .iter()
+                .flat_map(|(key, path)| ["--secret".to_string(), format!("id={key},src={}", path.display())]),
+        )
+        .arg(runtime.root());
+    if !config.secrets.is_empty() {
+        // `--mount=type=secret`/`--secret` both require BuildKit; set it
+        // here rather than requiring every caller to export it themselves.
+        build_command.env("DOCKER_BUILDKIT", "1");
+    }
+    let status = build_command
+        .stdout(build_log.try_clone()?)
+        .stderr(build_log)
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+
+    for warning in unconsumed_build_arg_warnings(&runtime.build_log())? {
+        eprintln!("warning: {warning}");
+    }
+
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker build exited with {status}; see {}",
+            runtime.build_log().display()
+        )));
+    }
+
+    if config.fail_fast {
+        activity::log_event(&runtime, "build", &[image_tag.to_string()], Some(0));
+        return Ok(BuildReport::default());
+    }
+
+    let failed_tools = collect_failed_tools(image_tag, &runtime)?;
+    activity::log_event(
+        &runtime,
+        "build",
+        &[image_tag.to_string()],
+        Some(if failed_tools.is_empty() { 0 } else { 1 }),
+    );
+    Ok(BuildReport { failed_tools })
+}
+
+/// Pre-pulls `config.base_image`, independent of any build. Handy for
+/// warming the local image cache before working offline. Not tied to any
+/// one environment, so nothing is logged to an `activity.log` here.
+pub fn pull_base(config: &Config) -> Result<()> {
+    let status = Command::new("docker")
+        .args(["pull", &config.base_image])
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker pull {} exited with {status}",
+            config.base_image
+        )));
+    }
+    Ok(())
+}
+
+/// Parses `build --build-arg KEY=VALUE` flags into a map, so they can be
+/// merged over `config.build_args` (the CLI wins on a key collision, since
+/// it's the more specific, one-off override).
+pub fn parse_build_args(pairs: &[String]) -> Result<BTreeMap<String, String>> {
+    pairs
+        .iter()
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| PwnenvError::InvalidBuildArg(pair.clone()))?;
+            if key.is_empty() {
+                return Err(PwnenvError::InvalidBuildArg(pair.clone()));
+            }
+            Ok((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// docker warns (rather than errors) when a declared `ARG` is never
+/// referenced by any `RUN`/`ENV` in the build, e.g. `[Warning] One or more
+/// build-args [FOO] were not consumed`. That warning lands in `build_log`
+/// alongside everything else `docker build` prints, so it'd otherwise go
+/// unnoticed; this pulls matching lines back out to print after the build.
+fn unconsumed_build_arg_warnings(build_log: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(build_log)?;
+    Ok(contents
+        .lines()
+        .filter(|line| line.contains("were not consumed") || line.contains("was not consumed"))
+        .map(|line| line.trim().to_string())
+        .collect())
+}
+
+/// A value that's different on every `build`, so `--only`'s
+/// [`CacheBust`] always misses docker's layer cache regardless of
+/// whether the targeted tool's script actually changed.
+fn cache_bust_nonce() -> String {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn image_exists(image_tag: &str) -> bool {
+    Command::new("docker")
+        .args(["image", "inspect", image_tag])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Runs [`lint_tools`] against the rendered tool scripts, printing any
+/// warnings and failing the build before `docker build` ever runs if there
+/// are errors.
+fn lint_or_abort(config: &Config, build_context: &std::path::Path) -> Result<()> {
+    let findings = lint_tools(config, build_context);
+    let mut has_errors = false;
+    for finding in &findings {
+        eprintln!("{finding}");
+        if finding.severity == Severity::Error {
+            has_errors = true;
+        }
+    }
+    if has_errors {
+        return Err(PwnenvError::Docker(
+            "Dockerfile sanity checks failed; see errors above".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Copies [`FAILED_MARKER_DIR`] out of the freshly built image into the
+/// environment's runtime dir, then returns the tool names it contains.
+fn collect_failed_tools(image_tag: &str, runtime: &RuntimeDir) -> Result<Vec<String>> {
+    let create = Command::new("docker")
+        .args(["create", image_tag])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !create.status.success() {
+        return Err(PwnenvError::Docker(
+            "docker create failed while collecting the failed-tool report".to_string(),
+        ));
+    }
+    let container_id = String::from_utf8_lossy(&create.stdout).trim().to_string();
+
+    let failed_dir = runtime.failed_dir();
+    std::fs::create_dir_all(&failed_dir)?;
+    let _ = Command::new("docker")
+        .args(["cp", &format!("{container_id}:{FAILED_MARKER_DIR}/."), &failed_dir.display().to_string()])
+        .status();
+    let _ = Command::new("docker").args(["rm", &container_id]).status();
+
+    let mut names: Vec<String> = std::fs::read_dir(&failed_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Prints a human-readable report after a `fail_fast = false` build,
+/// loudly stating that some tools may be silently missing from the image.
+pub fn print_report(report: &BuildReport, runtime: &RuntimeDir) {
+    if report.failed_tools.is_empty() {
+        println!("build complete; all tools installed successfully.");
+        return;
+    }
+    println!("build complete, but {} tool(s) FAILED to install:", report.failed_tools.len());
+    for tool in &report.failed_tools {
+        println!("  - {tool}");
+    }
+    println!(
+        "these tools are silently missing from the image. see {} for details.",
+        runtime.build_log().display()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_value_pairs() {
+        let args = parse_build_args(&["PWNDBG_REF=2024.02.14".to_string(), "MIRROR=https://internal".to_string()]).unwrap();
+        assert_eq!(args.get("PWNDBG_REF"), Some(&"2024.02.14".to_string()));
+        assert_eq!(args.get("MIRROR"), Some(&"https://internal".to_string()));
+    }
+
+    #[test]
+    fn value_may_itself_contain_an_equals_sign() {
+        let args = parse_build_args(&["QUERY=a=b".to_string()]).unwrap();
+        assert_eq!(args.get("QUERY"), Some(&"a=b".to_string()));
+    }
+
+    #[test]
+    fn missing_equals_sign_is_rejected() {
+        assert!(parse_build_args(&["PWNDBG_REF".to_string()]).is_err());
+    }
+
+    #[test]
+    fn empty_key_is_rejected() {
+        assert!(parse_build_args(&["=nokey".to_string()]).is_err());
+    }
+
+    #[test]
+    fn cli_args_override_config_args_on_collision() {
+        let mut config_args = BTreeMap::new();
+        config_args.insert("MIRROR".to_string(), "https://default".to_string());
+        config_args.extend(parse_build_args(&["MIRROR=https://override".to_string()]).unwrap());
+        assert_eq!(config_args.get("MIRROR"), Some(&"https://override".to_string()));
+    }
+
+    #[test]
+    fn surfaces_unconsumed_build_arg_warnings_from_the_log() {
+        let path = std::env::temp_dir().join(format!("pwnenv-build-log-test-{}.log", std::process::id()));
+        std::fs::write(
+            &path,
+            "Sending build context to Docker daemon\n[Warning] One or more build-args [FOO] were not consumed\n",
+        )
+        .unwrap();
+        let warnings = unconsumed_build_arg_warnings(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("FOO"));
+    }
+
+    #[test]
+    fn no_warning_lines_means_no_warnings() {
+        let path = std::env::temp_dir().join(format!("pwnenv-build-log-test-clean-{}.log", std::process::id()));
+        std::fs::write(&path, "Sending build context to Docker daemon\nSuccessfully built abc123\n").unwrap();
+        let warnings = unconsumed_build_arg_warnings(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(warnings.is_empty());
+    }
+}
@@ -0,0 +1,334 @@
+use std::io::Write as _;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::docker::lint::lint_tools;
+use crate::error::Result;
+
+/// Loads the config at `path` and runs the Dockerfile sanity checks
+/// against it, printing every finding. Returns an error if the config
+/// fails to parse or any finding is an error-level one.
+pub fn validate(path: &Path, build_context: &Path) -> Result<()> {
+    let config = Config::load(path)?;
+    let findings = lint_tools(&config, build_context);
+
+    if findings.is_empty() {
+        println!("{}: ok", path.display());
+        return Ok(());
+    }
+
+    let mut has_errors = false;
+    for finding in &findings {
+        println!("{finding}");
+        if finding.severity == crate::docker::lint::Severity::Error {
+            has_errors = true;
+        }
+    }
+
+    if has_errors {
+        return Err(crate::error::PwnenvError::Docker(
+            "config validate found error-level issues".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Prints the raw value of a top-level scalar field from `pwnenv.yaml`,
+/// bypassing the typed [`Config`] so it keeps working even for fields an
+/// older pwnenv binary doesn't know about.
+pub fn get(path: &Path, key: &str) -> Result<()> {
+    let value = load_raw(path)?;
+    let field = field(&value, key)?;
+    println!("{}", scalar_to_string(field));
+    Ok(())
+}
+
+/// Sets a top-level scalar field in `pwnenv.yaml` to `new_value`,
+/// inferring its type (bool, int, or string) and rewriting the file.
+pub fn set(path: &Path, key: &str, new_value: &str) -> Result<()> {
+    let mut value = load_raw(path)?;
+    let serde_yaml::Value::Mapping(mapping) = &mut value else {
+        return Err(crate::error::PwnenvError::Docker(
+            "pwnenv.yaml must be a mapping at the top level".to_string(),
+        ));
+    };
+    mapping.insert(
+        serde_yaml::Value::String(key.to_string()),
+        parse_scalar(new_value),
+    );
+
+    write_atomic(path, &serde_yaml::to_string(&value).map_err(to_parse_error(path))?)?;
+    Ok(())
+}
+
+/// Overwrites `pwnenv.yaml` with [`Config::default`]'s serialized form, for
+/// when it's been emptied or truncated (see [`crate::error::PwnenvError::ConfigEmpty`])
+/// and there's nothing in it worth salvaging. Requires confirmation —
+/// skippable with the global `--yes` flag — since it discards whatever's
+/// currently on disk.
+pub fn reset(path: &Path, assume_yes: bool) -> Result<()> {
+    if !crate::prompt::confirm(
+        &format!("{} will be overwritten with default settings. Continue?", path.display()),
+        assume_yes,
+    ) {
+        println!("aborted; {} left untouched", path.display());
+        return Ok(());
+    }
+    write_atomic(path, &serde_yaml::to_string(&Config::default()).map_err(to_parse_error(path))?)?;
+    println!("{}: reset to defaults", path.display());
+    Ok(())
+}
+
+/// Writes `contents` to `path` without ever leaving it empty or
+/// half-written: writes to a temp file in the same directory, fsyncs it,
+/// then renames it over `path` (atomic on the same filesystem). A crash or
+/// kill mid-write lands on the old contents or the new ones, never
+/// neither.
+pub(crate) fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("pwnenv.yaml");
+    let tmp_path = dir.join(format!(".{file_name}.tmp"));
+
+    let mut tmp_file = std::fs::File::create(&tmp_path)?;
+    tmp_file.write_all(contents.as_bytes())?;
+    tmp_file.sync_all()?;
+    drop(tmp_file);
+
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Stamps `pwnenv.yaml`'s `generated_by` with the running binary's version,
+/// so the next command's [`crate::version`] check stops warning about a
+/// stale config. Doesn't touch anything else — run `config diff` first if
+/// you want to see what a newer pwnenv would otherwise add.
+pub fn upgrade(path: &Path) -> Result<()> {
+    set(path, "generated_by", crate::version::CURRENT_VERSION)?;
+    println!(
+        "{}: generated_by set to {}",
+        path.display(),
+        crate::version::CURRENT_VERSION
+    );
+    Ok(())
+}
+
+/// Shows which top-level fields the typed [`Config`] schema would add,
+/// remove, or change relative to what's on disk, without writing
+/// anything. Fields are compared by their resolved (post-default) value,
+/// so an omitted field that merely takes its documented default doesn't
+/// show up as a change.
+pub fn diff(path: &Path) -> Result<()> {
+    let raw = load_raw(path)?;
+    let resolved = Config::load(path)?;
+    let resolved_value = serde_yaml::to_value(&resolved).map_err(to_parse_error(path))?;
+
+    let raw_mapping = match &raw {
+        serde_yaml::Value::Mapping(m) => m.clone(),
+        _ => serde_yaml::Mapping::new(),
+    };
+    let resolved_mapping = match &resolved_value {
+        serde_yaml::Value::Mapping(m) => m.clone(),
+        _ => serde_yaml::Mapping::new(),
+    };
+
+    let mut keys: Vec<String> = raw_mapping
+        .keys()
+        .chain(resolved_mapping.keys())
+        .filter_map(|k| k.as_str().map(str::to_string))
+        .collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut changed = false;
+    for key in keys {
+        let key_value = serde_yaml::Value::String(key.clone());
+        let before = raw_mapping.get(&key_value);
+        let after = resolved_mapping.get(&key_value);
+        match (before, after) {
+            (None, Some(after)) => {
+                changed = true;
+                println!("+ {key}: {}", scalar_to_string(after));
+            }
+            (Some(_), None) => {
+                changed = true;
+                println!("- {key}");
+            }
+            (Some(before), Some(after)) if before != after => {
+                changed = true;
+                println!("~ {key}: {} -> {}", scalar_to_string(before), scalar_to_string(after));
+            }
+            _ => {}
+        }
+    }
+
+    if !changed {
+        println!("{}: up to date", path.display());
+    }
+    Ok(())
+}
+
+/// Prints the fully-resolved effective [`Config`] — defaults filled in,
+/// `include`s merged, `preset`/`auto_detect_libc_from`/`gdb_plugin`
+/// applied, and any `--set key=value` overrides from this invocation —
+/// as either YAML (`format == "yaml"`, the default) or JSON
+/// (`format == "json"`). Unlike [`diff`], which only lists what changed
+/// relative to the raw file, this is the whole thing: what `build`/`up`
+/// actually sees once `Config::load` is done with it.
+pub fn show(path: &Path, format: &str) -> Result<()> {
+    let config = Config::load(path)?;
+    match format {
+        "yaml" => print!("{}", serde_yaml::to_string(&config).map_err(to_parse_error(path))?),
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&config)
+                .map_err(|e| crate::error::PwnenvError::Docker(format!("failed to serialize config: {e}")))?
+        ),
+        other => {
+            return Err(crate::error::PwnenvError::Docker(format!(
+                "unknown --format '{other}'; expected 'yaml' or 'json'"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn load_raw(path: &Path) -> Result<serde_yaml::Value> {
+    let raw = std::fs::read_to_string(path).map_err(|source| {
+        crate::error::PwnenvError::ConfigRead {
+            path: path.to_path_buf(),
+            source,
+        }
+    })?;
+    serde_yaml::from_str(&raw).map_err(to_parse_error(path))
+}
+
+fn to_parse_error(path: &Path) -> impl Fn(serde_yaml::Error) -> crate::error::PwnenvError + '_ {
+    move |source| crate::error::PwnenvError::ConfigParse {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+fn field<'a>(value: &'a serde_yaml::Value, key: &str) -> Result<&'a serde_yaml::Value> {
+    value
+        .get(key)
+        .ok_or_else(|| crate::error::PwnenvError::Docker(format!("no field named '{key}' in pwnenv.yaml")))
+}
+
+fn scalar_to_string(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+    }
+}
+
+/// Infers bool/int/string from a CLI-provided `config set` value.
+fn parse_scalar(raw: &str) -> serde_yaml::Value {
+    if let Ok(b) = raw.parse::<bool>() {
+        return serde_yaml::Value::Bool(b);
+    }
+    if let Ok(n) = raw.parse::<i64>() {
+        return serde_yaml::Value::Number(n.into());
+    }
+    serde_yaml::Value::String(raw.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PwnenvError;
+
+    fn unique_path(label: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("pwnenv-config-test-{label}-{}.yaml", std::process::id()))
+    }
+
+    #[test]
+    fn empty_config_is_a_clear_error_not_a_parse_error() {
+        let path = unique_path("empty-load");
+        std::fs::write(&path, "").unwrap();
+
+        let err = Config::load(&path).unwrap_err();
+        assert!(matches!(err, PwnenvError::ConfigEmpty(_)));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn truncated_whitespace_only_config_is_also_a_clear_error() {
+        let path = unique_path("whitespace-load");
+        std::fs::write(&path, "\n  \n").unwrap();
+
+        let err = Config::load(&path).unwrap_err();
+        assert!(matches!(err, PwnenvError::ConfigEmpty(_)));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn write_atomic_leaves_no_tmp_file_behind() {
+        let path = unique_path("atomic-write");
+        std::fs::write(&path, "base_image: ubuntu:22.04\n").unwrap();
+
+        set(&path, "privileged", "false").unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("privileged: false"));
+        assert!(!path.parent().unwrap().join(format!(
+            ".{}.tmp",
+            path.file_name().unwrap().to_str().unwrap()
+        )).exists());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn reset_without_confirmation_leaves_the_file_untouched() {
+        let path = unique_path("reset-declined");
+        std::fs::write(&path, "not valid: [").unwrap();
+
+        // assume_yes = false and no stdin input available under `cargo
+        // test` reads as EOF, which `prompt::confirm` treats as "no".
+        reset(&path, false).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "not valid: [");
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn show_rejects_an_unknown_format() {
+        let path = unique_path("show-bad-format");
+        std::fs::write(&path, "base_image: ubuntu:22.04\n").unwrap();
+
+        assert!(show(&path, "toml").is_err());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn show_yaml_includes_defaulted_fields() {
+        let path = unique_path("show-yaml");
+        std::fs::write(&path, "base_image: ubuntu:22.04\n").unwrap();
+
+        // Nothing to assert on stdout directly; this just confirms `show`
+        // succeeds against a minimal config that relies on defaults for
+        // everything else (e.g. `shell`, `restart_policy`).
+        assert!(show(&path, "yaml").is_ok());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn reset_with_assume_yes_regenerates_defaults() {
+        let path = unique_path("reset-confirmed");
+        std::fs::write(&path, "").unwrap();
+
+        reset(&path, true).unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.base_image, Config::default().base_image);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
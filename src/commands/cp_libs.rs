@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::build_id;
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+use crate::libc_detect::detect_glibc_version;
+use crate::runtime::container_name;
+
+/// One library/loader copied out by [`cp_libs`], with whatever version
+/// and build ID info could be extracted from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibEntry {
+    pub container_path: String,
+    pub file: String,
+    pub glibc_version: Option<String>,
+    pub build_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibsManifest {
+    pub libs: Vec<LibEntry>,
+}
+
+/// The container paths `cp-libs` pulls from: the 64-bit libc and its
+/// loader always, plus the i386 pair when `config.i386` is set (see
+/// [`crate::arch`]) — those won't exist in the image otherwise, so
+/// there's nothing to skip-if-missing about them. `required` marks the
+/// one file whose absence fails the whole command instead of just
+/// getting a warning; a challenge's loader path can vary by distro/arch
+/// in ways its libc's can't, so only the libc itself is load-bearing
+/// here.
+#[derive(Clone, Copy)]
+struct LibSpec {
+    container_path: &'static str,
+    required: bool,
+}
+
+const X86_64_LIBS: &[LibSpec] = &[
+    LibSpec { container_path: "/lib/x86_64-linux-gnu/libc.so.6", required: true },
+    LibSpec { container_path: "/lib64/ld-linux-x86-64.so.2", required: false },
+];
+
+const I386_LIBS: &[LibSpec] = &[
+    LibSpec { container_path: "/lib/i386-linux-gnu/libc.so.6", required: false },
+    LibSpec { container_path: "/lib/i386-linux-gnu/ld-linux.so.2", required: false },
+];
+
+/// Copies `env_name`'s loader/libc out of its container via `docker cp`
+/// (which works whether the container is running or merely exists —
+/// unlike `docker exec`, so this doesn't need [`crate::commands::snapshot`]'s
+/// running check) into `out`, creating it if needed, and writes a
+/// `libs-manifest.json` there with each file's glibc version (reusing
+/// [`detect_glibc_version`], the same byte-scan `auto_libc_detect` uses
+/// on a challenge binary) and build ID (see [`build_id`]).
+///
+/// A destination file that already exists and differs from the
+/// container's copy is left alone and reported as an error unless
+/// `force` is set — same "don't clobber without being told to" stance as
+/// [`crate::programs`]'s own copy logic.
+pub fn cp_libs(env_name: &str, config: &Config, out: &Path, force: bool) -> Result<()> {
+    let container = container_name(env_name);
+    if !container_exists(&container)? {
+        return Err(PwnenvError::Docker(format!(
+            "no container named '{container}' was found; run `pwnenv up` first"
+        )));
+    }
+    std::fs::create_dir_all(out)?;
+
+    let mut specs = X86_64_LIBS.to_vec();
+    if config.i386 {
+        specs.extend_from_slice(I386_LIBS);
+    }
+
+    let mut libs = Vec::new();
+    for spec in specs {
+        match copy_one(&container, spec.container_path, out, force)? {
+            Some(dest) => libs.push(describe(spec.container_path, &dest)),
+            None if spec.required => {
+                return Err(PwnenvError::Docker(format!(
+                    "'{}' was not found in '{container}'",
+                    spec.container_path
+                )));
+            }
+            None => eprintln!(
+                "warning: '{}' was not found in '{container}'; skipping",
+                spec.container_path
+            ),
+        }
+    }
+
+    let manifest = LibsManifest { libs };
+    let manifest_path = out.join("libs-manifest.json");
+    std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest).unwrap_or_default())?;
+
+    println!(
+        "cp-libs: wrote {} file(s) to {} ({})",
+        manifest.libs.len(),
+        out.display(),
+        manifest_path.display()
+    );
+    Ok(())
+}
+
+fn describe(container_path: &str, dest: &Path) -> LibEntry {
+    LibEntry {
+        container_path: container_path.to_string(),
+        file: dest.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+        glibc_version: detect_glibc_version(dest).ok().flatten(),
+        build_id: build_id::extract_build_id(dest).ok().flatten(),
+    }
+}
+
+/// `docker inspect` succeeds for a container regardless of running
+/// state, so this is the right existence check for a command that's
+/// meant to work whether the environment is up or down.
+fn container_exists(container: &str) -> Result<bool> {
+    let status = Command::new("docker")
+        .args(["inspect", container])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    Ok(status.success())
+}
+
+/// Copies `container_path` out of `container` into `out`, under its own
+/// basename, via a `docker cp` into a sibling `.pwnenv-tmp` file so a
+/// failed/partial copy never clobbers a good existing one. `Ok(None)`
+/// means `docker cp` failed (almost always: the path doesn't exist in
+/// this image), not that anything is wrong with `out` itself.
+fn copy_one(container: &str, container_path: &str, out: &Path, force: bool) -> Result<Option<PathBuf>> {
+    let name = Path::new(container_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| container_path.to_string());
+    let dest = out.join(&name);
+    let tmp = out.join(format!("{name}.pwnenv-tmp"));
+
+    let status = Command::new("docker")
+        .args(["cp", &format!("{container}:{container_path}"), &tmp.display().to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !status.success() {
+        std::fs::remove_file(&tmp).ok();
+        return Ok(None);
+    }
+
+    if dest.exists() {
+        let unchanged = std::fs::read(&dest)? == std::fs::read(&tmp)?;
+        if unchanged {
+            std::fs::remove_file(&tmp).ok();
+            return Ok(Some(dest));
+        }
+        if !force {
+            std::fs::remove_file(&tmp).ok();
+            return Err(PwnenvError::Docker(format!(
+                "{} already exists and differs from the container's copy; pass --force to overwrite",
+                dest.display()
+            )));
+        }
+    }
+
+    std::fs::rename(&tmp, &dest)?;
+    Ok(Some(dest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_on_a_plain_file_has_no_version_or_build_id() {
+        let path = std::env::temp_dir().join(format!("pwnenv-cp-libs-test-{}", std::process::id()));
+        std::fs::write(&path, b"not an elf at all").unwrap();
+        let entry = describe("/lib/x86_64-linux-gnu/libc.so.6", &path);
+        assert_eq!(entry.container_path, "/lib/x86_64-linux-gnu/libc.so.6");
+        assert_eq!(entry.glibc_version, None);
+        assert_eq!(entry.build_id, None);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn describe_picks_up_the_destination_file_name() {
+        let path = std::env::temp_dir().join("pwnenv-cp-libs-test-ld-linux-x86-64.so.2");
+        std::fs::write(&path, b"").unwrap();
+        let entry = describe("/lib64/ld-linux-x86-64.so.2", &path);
+        assert_eq!(entry.file, "pwnenv-cp-libs-test-ld-linux-x86-64.so.2");
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,41 @@
+use crate::error::Result;
+
+/// Renders an `xinetd` service file that spawns `binary` on `port`, the
+/// classic way CTF challenges are deployed standalone (outside pwnenv's
+/// own docker-based `up`).
+pub fn render_xinetd(service_name: &str, binary: &str, port: u16) -> String {
+    format!(
+        "service {service_name}\n\
+         {{\n\
+         \tdisable = no\n\
+         \tsocket_type = stream\n\
+         \tprotocol = tcp\n\
+         \twait = no\n\
+         \tuser = nobody\n\
+         \ttype = UNLISTED\n\
+         \tport = {port}\n\
+         \tbind = 0.0.0.0\n\
+         \tserver = {binary}\n\
+         \tper_source = 10\n\
+         \trlimit_cpu = 20\n\
+         }}\n"
+    )
+}
+
+pub fn write_xinetd(path: &std::path::Path, service_name: &str, binary: &str, port: u16) -> Result<()> {
+    std::fs::write(path, render_xinetd(service_name, binary, port))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_a_minimal_xinetd_stanza() {
+        let conf = render_xinetd("chall", "/chall/run.sh", 1337);
+        assert!(conf.contains("port = 1337"));
+        assert!(conf.contains("server = /chall/run.sh"));
+        assert!(conf.starts_with("service chall"));
+    }
+}
@@ -0,0 +1,178 @@
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::diff_env::{unified_diff, CategoryResult};
+use crate::docker::render_dockerfile;
+use crate::error::{PwnenvError, Result};
+use crate::manifest::{self, ManifestDiff};
+use crate::runtime::RuntimeDir;
+
+/// The full `diff-env` result: every category compared, in a fixed
+/// order, whether printed as text or as `--format json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffEnvReport {
+    pub env_a: String,
+    pub env_b: String,
+    pub categories: Vec<CategoryResult>,
+}
+
+/// Compares `env_a` and `env_b`: their stored `pwnenv.yaml` configs (by
+/// [`crate::labels::config_hash`], the same fingerprint `status
+/// --verbose` flags staleness with), their rendered Dockerfiles, their
+/// cached `docker-compose.yml`s, their configured `base_image`'s pulled
+/// digest, and — with `packages` (`diff-env --packages`) — their last
+/// saved package manifests (see [`crate::manifest`]).
+///
+/// Each environment is resolved from just its name via
+/// [`RuntimeDir::host_dir`], the same registration `adopt`/`status` rely
+/// on — neither needs to be the environment the command was run from.
+/// Categories that need a build/up that never happened (compose,
+/// packages) or an image that was never pulled (base image) report
+/// `unavailable` with a note instead of being skipped silently; config
+/// and Dockerfile are always comparable, since both are pure functions
+/// of `pwnenv.yaml`.
+pub fn diff_env(env_a: &str, env_b: &str, packages: bool, json: bool) -> Result<()> {
+    let (runtime_a, config_a) = load_env(env_a)?;
+    let (runtime_b, config_b) = load_env(env_b)?;
+
+    let mut categories = vec![
+        diff_config(&config_a, &config_b),
+        diff_dockerfile(&config_a, &config_b),
+        diff_compose(&runtime_a, &runtime_b, env_a, env_b),
+        diff_base_image(&config_a, &config_b),
+    ];
+    if packages {
+        categories.push(diff_packages(&runtime_a, &runtime_b, env_a, env_b));
+    }
+
+    let report = DiffEnvReport { env_a: env_a.to_string(), env_b: env_b.to_string(), categories };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+fn load_env(env_name: &str) -> Result<(RuntimeDir, Config)> {
+    let runtime = RuntimeDir::new(env_name);
+    let host_dir = runtime
+        .host_dir()
+        .ok_or_else(|| PwnenvError::UnknownEnvironment(env_name.to_string()))?;
+    let config = Config::load(&host_dir.join("pwnenv.yaml"))?;
+    Ok((runtime, config))
+}
+
+fn diff_config(a: &Config, b: &Config) -> CategoryResult {
+    if crate::labels::config_hash(a) == crate::labels::config_hash(b) {
+        return CategoryResult::same("config");
+    }
+    let yaml_a = serde_yaml::to_string(a).unwrap_or_default();
+    let yaml_b = serde_yaml::to_string(b).unwrap_or_default();
+    CategoryResult::different("config", unified_diff(&yaml_a, &yaml_b))
+}
+
+fn diff_dockerfile(a: &Config, b: &Config) -> CategoryResult {
+    let dockerfile_a = render_dockerfile(a, a.programs_dir.is_some(), None);
+    let dockerfile_b = render_dockerfile(b, b.programs_dir.is_some(), None);
+    CategoryResult::from_text("dockerfile", &dockerfile_a, &dockerfile_b)
+}
+
+fn diff_compose(runtime_a: &RuntimeDir, runtime_b: &RuntimeDir, env_a: &str, env_b: &str) -> CategoryResult {
+    let a = std::fs::read_to_string(runtime_a.root().join("docker-compose.yml"));
+    let b = std::fs::read_to_string(runtime_b.root().join("docker-compose.yml"));
+    match (a, b) {
+        (Ok(a), Ok(b)) => CategoryResult::from_text("compose", &a, &b),
+        (Err(_), _) => CategoryResult::unavailable(
+            "compose",
+            format!("'{env_a}' has no docker-compose.yml yet; run `build`/`up` first"),
+        ),
+        (_, Err(_)) => CategoryResult::unavailable(
+            "compose",
+            format!("'{env_b}' has no docker-compose.yml yet; run `build`/`up` first"),
+        ),
+    }
+}
+
+fn diff_base_image(a: &Config, b: &Config) -> CategoryResult {
+    match (image_digest(&a.base_image), image_digest(&b.base_image)) {
+        (Some(digest_a), Some(digest_b)) if digest_a == digest_b => CategoryResult::same("base_image"),
+        (Some(digest_a), Some(digest_b)) => CategoryResult::different(
+            "base_image",
+            format!("{} ({digest_a}) vs {} ({digest_b})", a.base_image, b.base_image),
+        ),
+        (None, _) => {
+            CategoryResult::unavailable("base_image", format!("'{}' is not pulled locally", a.base_image))
+        }
+        (_, None) => {
+            CategoryResult::unavailable("base_image", format!("'{}' is not pulled locally", b.base_image))
+        }
+    }
+}
+
+fn image_digest(image: &str) -> Option<String> {
+    let output = Command::new("docker").args(["image", "inspect", "-f", "{{.Id}}", image]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!id.is_empty()).then_some(id)
+}
+
+fn diff_packages(runtime_a: &RuntimeDir, runtime_b: &RuntimeDir, env_a: &str, env_b: &str) -> CategoryResult {
+    let a = manifest::load(&manifest::manifest_path(runtime_a));
+    let b = manifest::load(&manifest::manifest_path(runtime_b));
+    match (a, b) {
+        (Ok(a), Ok(b)) => {
+            let diff = manifest::diff(&a, &b);
+            if diff.added.is_empty() && diff.removed.is_empty() && diff.upgraded.is_empty() {
+                CategoryResult::same("packages")
+            } else {
+                CategoryResult::different("packages", format_package_diff(&diff))
+            }
+        }
+        (Err(_), _) => CategoryResult::unavailable(
+            "packages",
+            format!("'{env_a}' has no saved package manifest; run `pwnenv manifest` first"),
+        ),
+        (_, Err(_)) => CategoryResult::unavailable(
+            "packages",
+            format!("'{env_b}' has no saved package manifest; run `pwnenv manifest` first"),
+        ),
+    }
+}
+
+fn format_package_diff(diff: &ManifestDiff) -> String {
+    let mut lines = Vec::new();
+    for pkg in &diff.added {
+        lines.push(format!("+ {}/{} {}", pkg.source, pkg.name, pkg.version));
+    }
+    for pkg in &diff.removed {
+        lines.push(format!("- {}/{} {}", pkg.source, pkg.name, pkg.version));
+    }
+    for upgrade in &diff.upgraded {
+        lines.push(format!("~ {}/{} {} -> {}", upgrade.source, upgrade.name, upgrade.from, upgrade.to));
+    }
+    lines.join("\n")
+}
+
+fn print_report(report: &DiffEnvReport) {
+    println!("diff-env: {} vs {}", report.env_a, report.env_b);
+    for category in &report.categories {
+        if let Some(reason) = &category.unavailable {
+            println!("  {}: unavailable ({reason})", category.category);
+        } else if category.differs {
+            println!("  {}: differs", category.category);
+        } else {
+            println!("  {}: same", category.category);
+        }
+    }
+    for category in &report.categories {
+        if let Some(detail) = &category.detail {
+            println!("\n--- {} ---\n{detail}", category.category);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::path::Path;
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::docker::lint::{lint_tools, Severity};
+use crate::error::{PwnenvError, Result};
+use crate::mounts;
+
+/// One check's outcome. `Warning` never sets [`doctor`]'s exit code;
+/// `Error` does — that's the line CI gates on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl DoctorCheck {
+    fn ok(name: &str, detail: impl Into<String>) -> Self {
+        DoctorCheck { name: name.to_string(), status: CheckStatus::Ok, detail: detail.into() }
+    }
+
+    fn warning(name: &str, detail: impl Into<String>) -> Self {
+        DoctorCheck { name: name.to_string(), status: CheckStatus::Warning, detail: detail.into() }
+    }
+
+    fn error(name: &str, detail: impl Into<String>) -> Self {
+        DoctorCheck { name: name.to_string(), status: CheckStatus::Error, detail: detail.into() }
+    }
+}
+
+/// Runs a battery of environment-readiness checks (docker itself, not any
+/// particular running container) and prints the results, either as a
+/// `name: detail` line per check or, with `json`, as a JSON array — the
+/// array is printed even if some checks failed, so a CI step can always
+/// parse it regardless of the exit code. Returns whether any check came
+/// back `Error`-level; the caller turns that into the process exit code,
+/// same as [`super::probe::probe`]'s `bool` result does.
+pub fn doctor(json: bool) -> Result<bool> {
+    let mut checks = Vec::new();
+
+    let config = match Config::load(Path::new("pwnenv.yaml")) {
+        Ok(config) => {
+            checks.push(DoctorCheck::ok("pwnenv.yaml", "loaded and parsed"));
+            Some(config)
+        }
+        Err(e) => {
+            checks.push(DoctorCheck::error("pwnenv.yaml", e.to_string()));
+            None
+        }
+    };
+
+    checks.push(check_docker_cli());
+    checks.push(check_docker_daemon());
+    checks.push(check_compose(config.as_ref().and_then(|c| c.compose_command.as_deref())));
+
+    if let Some(config) = &config {
+        checks.push(check_dockerfile_lint(config));
+        checks.push(check_mounts(config));
+        checks.push(check_forwarded_port(config));
+    }
+
+    let critical = checks.iter().any(|c| c.status == CheckStatus::Error);
+
+    if json {
+        let out = serde_json::to_string_pretty(&checks)
+            .map_err(|e| PwnenvError::Docker(format!("failed to serialize doctor results: {e}")))?;
+        println!("{out}");
+    } else {
+        print_table(&checks);
+    }
+
+    Ok(critical)
+}
+
+fn print_table(checks: &[DoctorCheck]) {
+    for check in checks {
+        let mark = match check.status {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Warning => "WARN",
+            CheckStatus::Error => "FAIL",
+        };
+        println!("{mark:<4} {}: {}", check.name, check.detail);
+    }
+}
+
+fn check_docker_cli() -> DoctorCheck {
+    match Command::new("docker").arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            DoctorCheck::ok("docker-cli", String::from_utf8_lossy(&output.stdout).trim().to_string())
+        }
+        _ => DoctorCheck::error("docker-cli", "`docker --version` failed; is docker installed and on PATH?"),
+    }
+}
+
+fn check_docker_daemon() -> DoctorCheck {
+    match Command::new("docker").arg("info").output() {
+        Ok(output) if output.status.success() => DoctorCheck::ok("docker-daemon", "reachable"),
+        _ => DoctorCheck::error("docker-daemon", "`docker info` failed; is the daemon running and accessible?"),
+    }
+}
+
+fn check_compose(override_cmd: Option<&str>) -> DoctorCheck {
+    if let Some(cmd) = override_cmd {
+        return DoctorCheck::ok("compose", format!("using pwnenv.yaml's compose_command override `{cmd}`"));
+    }
+    let v2_available = Command::new("docker")
+        .args(["compose", "version"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if v2_available {
+        return DoctorCheck::ok("compose", "docker compose (v2 plugin) available");
+    }
+    let v1_available = Command::new("docker-compose")
+        .arg("--version")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if v1_available {
+        return DoctorCheck::warning("compose", "docker compose v2 plugin not found; falling back to docker-compose v1");
+    }
+    DoctorCheck::error("compose", "neither `docker compose` nor `docker-compose` is available")
+}
+
+fn check_dockerfile_lint(config: &Config) -> DoctorCheck {
+    let findings = lint_tools(config, Path::new("."));
+    if findings.is_empty() {
+        return DoctorCheck::ok("dockerfile-lint", "no issues found");
+    }
+    let errors = findings.iter().filter(|f| f.severity == Severity::Error).count();
+    let warnings = findings.len() - errors;
+    let detail = format!("{errors} error(s), {warnings} warning(s); see `pwnenv config validate` for details");
+    if errors > 0 {
+        DoctorCheck::error("dockerfile-lint", detail)
+    } else {
+        DoctorCheck::warning("dockerfile-lint", detail)
+    }
+}
+
+fn check_mounts(config: &Config) -> DoctorCheck {
+    if config.mounts.is_empty() {
+        return DoctorCheck::ok("mounts", "none configured");
+    }
+    let base_dir = match std::env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => return DoctorCheck::error("mounts", format!("could not read current directory: {e}")),
+    };
+    match mounts::resolve(&[], &config.mounts, &base_dir, false) {
+        Ok(resolved) => DoctorCheck::ok("mounts", format!("{} host path(s) exist", resolved.len())),
+        Err(e) => DoctorCheck::error("mounts", e.to_string()),
+    }
+}
+
+fn check_forwarded_port(config: &Config) -> DoctorCheck {
+    let Some(port) = config.forwarded_port else {
+        return DoctorCheck::ok("forwarded-port", "none configured");
+    };
+    match super::up::check_port_available(port) {
+        Ok(()) => DoctorCheck::ok("forwarded-port", format!("{port} is free")),
+        Err(e) => DoctorCheck::warning("forwarded-port", e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_mounts_configured_is_ok() {
+        let config = Config::default();
+        assert_eq!(check_mounts(&config).status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn no_forwarded_port_configured_is_ok() {
+        let config = Config::default();
+        assert_eq!(check_forwarded_port(&config).status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn clean_config_has_no_lint_findings() {
+        let config = Config::default();
+        assert_eq!(check_dockerfile_lint(&config).status, CheckStatus::Ok);
+    }
+}
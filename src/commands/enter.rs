@@ -0,0 +1,461 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::activity;
+use crate::config::Config;
+use crate::docker::compose::service_names;
+use crate::error::{PwnenvError, Result};
+use crate::recordings::{self, Recorder};
+use crate::runtime::{container_name_for_service, RuntimeDir};
+use crate::sessions;
+
+/// docker's exit code when the command given to `exec`/`run` can't be
+/// found in the image (distinct from the command itself exiting non-zero).
+const EXEC_NOT_FOUND: i32 = 127;
+
+/// `exec`s into `env_name`'s running container. With no `profile`, runs
+/// `config.shell`; with `profile`, runs the command registered for it
+/// under `config.profiles` (e.g. `enter --as debug` might run `gdb -q
+/// /chall`). If the configured command isn't found in the image (e.g.
+/// config still points at `fish` but the container was built without
+/// it), falls back to `/bin/sh` with a warning instead of failing
+/// outright.
+///
+/// With no `profile`, `config.shell` is checked with [`resolve_shell`]
+/// before any of that: a quick `docker exec ... test -x` so a stale
+/// image (built before `pwnenv.yaml`'s `shell` changed) gets a clear
+/// diagnostic — which other shells are actually there, and that
+/// `pwnenv build` would fix it — instead of just docker's opaque `exec
+/// failed`. A profile's command skips this probe (see `resolve_shell`'s
+/// own doc comment) and still falls back through the `EXEC_NOT_FOUND`
+/// path below if it turns out missing.
+///
+/// `service` (`enter --service`) picks which compose service to exec
+/// into, for when an environment ever has more than one; it defaults to
+/// `env_name` itself, the only service [`crate::docker::render_compose`]
+/// renders today. Whatever it resolves to is checked against the
+/// environment's actual `docker-compose.yml` via [`service_names`]
+/// first, so a typo'd or not-yet-existing service gets a clear error
+/// instead of docker's opaque "no such container".
+///
+/// `record` (`enter --record`) wraps the same session in whichever
+/// recorder [`recordings::detect`] finds in the container (asciinema,
+/// else `script(1)`, see [`crate::recordings`]), and copies the result
+/// into [`recordings::recordings_dir`] once the session ends. Neither
+/// recorder changes the pty docker allocates, so gdb/tmux behave the
+/// same recorded as not. With neither binary present, `enter` degrades
+/// to an unrecorded session with a warning instead of failing outright
+/// — same "never let a nice-to-have block the actual shell" approach as
+/// the `EXEC_NOT_FOUND` fallback above.
+///
+/// For as long as this session runs, it's registered in `env_name`'s
+/// [`sessions`] file via [`sessions::Guard`] — pid, tty, start time —
+/// so `status`/`ps` can show how many sessions are attached and `kill`
+/// can warn before tearing down a container others might still be using.
+/// The guard is a plain local: it's released on every return path out of
+/// this function, early `?`-propagated errors included, the same as
+/// [`crate::lock::BuildLock`] releases a build lock.
+pub fn enter(
+    env_name: &str,
+    config: &Config,
+    service: Option<&str>,
+    profile: Option<&str>,
+    no_tty: bool,
+    no_interactive: bool,
+    record: bool,
+) -> Result<()> {
+    let service = service.unwrap_or(env_name);
+    let runtime = RuntimeDir::new(env_name);
+    validate_service(&runtime, service)?;
+    let container = container_name_for_service(env_name, service);
+    let _session = sessions::Guard::register(&runtime)?;
+    let args: Vec<String> = profile.map(|p| vec![p.to_string()]).unwrap_or_default();
+
+    let command = match profile {
+        Some(profile) => config
+            .profiles
+            .get(profile)
+            .ok_or_else(|| PwnenvError::Docker(format!("no profile named '{profile}' in pwnenv.yaml")))?
+            .as_str(),
+        None => config.shell.as_str(),
+    };
+    // `login_shell` only makes sense for the configured shell itself, not
+    // for a profile's own command (e.g. `gdb -q /chall` has no notion of
+    // a login shell).
+    let login_shell = profile.is_none() && config.login_shell;
+
+    // Only the configured shell itself is worth a pre-flight probe: a
+    // profile's command might be `gdb -q /chall`, which `test -x` can't
+    // meaningfully answer for, and the generic `EXEC_NOT_FOUND` fallback
+    // below still catches it if it's missing.
+    let resolved_command = if profile.is_none() {
+        resolve_shell(&container, command)?
+    } else {
+        command.to_string()
+    };
+    let command = resolved_command.as_str();
+
+    if record {
+        match recordings::detect(&container) {
+            Some(recorder) => {
+                return record_session(&container, &runtime, env_name, command, login_shell, recorder, &args);
+            }
+            None => {
+                eprintln!(
+                    "warning: neither `asciinema` nor `script` was found in the container; \
+                     continuing without recording (add `include_tools: [recording]` for asciinema)"
+                );
+            }
+        }
+    }
+
+    // Interactive sessions log their start/end, never the keystrokes in
+    // between — there's nothing to capture those anyway, since the shell
+    // runs attached to the user's own tty via `docker exec -it`.
+    activity::log_event(&runtime, "enter:start", &args, None);
+    let status = exec_shell(&container, command, login_shell, no_tty, no_interactive)?;
+    if status.code() == Some(EXEC_NOT_FOUND) {
+        eprintln!("warning: '{command}' was not found in the container; falling back to /bin/sh");
+        let fallback_status = exec_shell(&container, "/bin/sh", false, no_tty, no_interactive)?;
+        activity::log_event(&runtime, "enter:end", &args, fallback_status.code());
+        return Ok(());
+    }
+
+    activity::log_event(&runtime, "enter:end", &args, status.code());
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker exec into '{container}' exited with {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks `service` is actually declared in `env_name`'s rendered
+/// `docker-compose.yml` before `enter` commits to a container name built
+/// from it, so a typo (or a service that simply doesn't exist yet,
+/// since today's compose rendering only ever produces one) gets
+/// [`PwnenvError::UnknownService`] listing what's actually there instead
+/// of docker's opaque "no such container" once `docker exec` runs.
+fn validate_service(runtime: &RuntimeDir, service: &str) -> Result<()> {
+    let compose_path = runtime.root().join("docker-compose.yml");
+    let compose_yaml = std::fs::read_to_string(&compose_path).map_err(|_| {
+        PwnenvError::Docker(format!(
+            "no docker-compose.yml found at {}; run `pwnenv up` first",
+            compose_path.display()
+        ))
+    })?;
+    let available = service_names(&compose_yaml)?;
+    if !available.iter().any(|s| s == service) {
+        return Err(PwnenvError::UnknownService {
+            service: service.to_string(),
+            available,
+        });
+    }
+    Ok(())
+}
+
+/// Shells tried, in order, when the configured one is missing and
+/// `/etc/shells` can't be read either (e.g. the image never installed
+/// one). `/bin/sh` is assumed to always exist, per `exec_shell`'s own
+/// `EXEC_NOT_FOUND` fallback, so it isn't worth probing for — it's the
+/// guaranteed last resort if every other probe fails too.
+const COMMON_SHELL_PROBES: &[&str] = &["/bin/bash", "/bin/zsh", "/bin/dash", "/bin/ash", "/bin/sh"];
+
+/// Checks `command` exists and is executable in `container` before
+/// `enter` commits to it, so a stale image (built before `pwnenv.yaml`'s
+/// `shell` changed) gets a clear diagnostic instead of docker's opaque
+/// `exec failed` error. A profile command is never passed in here (see
+/// [`enter`]), so `command` is always a bare shell path.
+///
+/// On success, `command` itself is returned unchanged — this is the
+/// common case, and it costs exactly one extra `docker exec ... test -x`
+/// round-trip, not a second one to list shells too.
+fn resolve_shell(container: &str, command: &str) -> Result<String> {
+    if probe_shell(container, command)? {
+        return Ok(command.to_string());
+    }
+
+    let available = list_available_shells(container);
+    let fallback = choose_fallback(&available, command);
+    eprintln!("{}", missing_shell_message(command, &available, fallback.as_deref()));
+    Ok(fallback.unwrap_or_else(|| "/bin/sh".to_string()))
+}
+
+/// Runs `docker exec <container> test -x <shell>`, the same check `sh`
+/// itself uses to decide whether a path is an executable file.
+fn probe_shell(container: &str, shell: &str) -> Result<bool> {
+    let status = Command::new("docker")
+        .args(["exec", container, "test", "-x", shell])
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    Ok(status.success())
+}
+
+/// Lists shells actually present in `container`: first tries parsing
+/// `/etc/shells` (the standard registry most base images ship with
+/// populated), falling back to probing [`COMMON_SHELL_PROBES`]
+/// individually if that file is missing, empty, or unreadable.
+fn list_available_shells(container: &str) -> Vec<String> {
+    let etc_shells = Command::new("docker")
+        .args(["exec", container, "cat", "/etc/shells"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_etc_shells(&String::from_utf8_lossy(&output.stdout)));
+
+    match etc_shells {
+        Some(shells) if !shells.is_empty() => shells,
+        _ => COMMON_SHELL_PROBES
+            .iter()
+            .filter(|shell| probe_shell(container, shell).unwrap_or(false))
+            .map(|shell| shell.to_string())
+            .collect(),
+    }
+}
+
+/// Parses `/etc/shells`' format: one path per line, blank lines and
+/// `#`-comments ignored.
+fn parse_etc_shells(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Picks the closest available substitute for `preferred`: an exact
+/// basename match first (e.g. a `/usr/bin/zsh` on `$PATH` instead of the
+/// `/bin/zsh` that was configured), then [`COMMON_SHELL_PROBES`]' order
+/// among what's actually available, then whatever `available` happens to
+/// list first. `None` only when nothing was found at all, in which case
+/// [`resolve_shell`] falls back to the universal `/bin/sh` assumption.
+fn choose_fallback(available: &[String], preferred: &str) -> Option<String> {
+    let preferred_name = preferred.rsplit('/').next().unwrap_or(preferred);
+    if let Some(exact) = available
+        .iter()
+        .find(|shell| shell.rsplit('/').next().unwrap_or(shell) == preferred_name)
+    {
+        return Some(exact.clone());
+    }
+    for candidate in COMMON_SHELL_PROBES {
+        if let Some(found) = available.iter().find(|shell| shell.as_str() == *candidate) {
+            return Some(found.clone());
+        }
+    }
+    available.first().cloned()
+}
+
+/// The diagnostic `resolve_shell` prints in place of docker's opaque
+/// `exec failed` when the configured shell turns out to be missing.
+fn missing_shell_message(missing: &str, available: &[String], fallback: Option<&str>) -> String {
+    let available_list = if available.is_empty() {
+        "none detected".to_string()
+    } else {
+        available.join(", ")
+    };
+    let fallback_desc = fallback.unwrap_or("/bin/sh");
+    format!(
+        "warning: configured shell '{missing}' was not found in the container \
+         (available: {available_list}); falling back to '{fallback_desc}' for this session. \
+         Run `pwnenv build` to get '{missing}' installed."
+    )
+}
+
+/// The `record: true` path of [`enter`]: builds the same command a plain
+/// `enter` would exec, hands it to [`recordings::record`] instead of
+/// [`exec_shell`] directly, and prints where the finished recording
+/// landed once the session ends.
+fn record_session(
+    container: &str,
+    runtime: &RuntimeDir,
+    env_name: &str,
+    command: &str,
+    login_shell: bool,
+    recorder: Recorder,
+    args: &[String],
+) -> Result<()> {
+    let full_command = if login_shell && !command.contains(char::is_whitespace) {
+        format!("{command} -l")
+    } else {
+        command.to_string()
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    activity::log_event(runtime, "enter:record-start", args, None);
+    let (status, id) = recordings::record(container, env_name, &full_command, recorder, timestamp)?;
+    activity::log_event(runtime, "enter:record-end", args, status.code());
+
+    println!("recording saved: {id} (pwnenv recordings play {id})");
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker exec into '{container}' exited with {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Runs `command` in `container` over `docker exec`. A bare command
+/// (no whitespace, e.g. `/usr/bin/fish`) execs directly as the container's
+/// argv; one with arguments (e.g. a profile's `gdb -q /chall`) goes
+/// through `/bin/sh -c` to get shell-style word splitting. `login_shell`
+/// only applies to the bare-command path, appending `-l` so the shell
+/// reads its profile/rc files as a real login shell would.
+///
+/// Allocates a pty and keeps stdin open by default (`-it`), matching
+/// every `enter` before these flags existed. `no_tty`/`no_interactive`
+/// (`enter --no-tty`/`--no-interactive`) drop `-t`/`-i` respectively —
+/// for a scripted `enter` piping output somewhere, a tty in the middle
+/// garbles it with control sequences docker's pty layer inserts.
+fn exec_shell(
+    container: &str,
+    command: &str,
+    login_shell: bool,
+    no_tty: bool,
+    no_interactive: bool,
+) -> Result<std::process::ExitStatus> {
+    let has_args = command.contains(char::is_whitespace);
+    let mut docker = Command::new("docker");
+    docker.arg("exec");
+    let flags = exec_flags(no_tty, no_interactive);
+    if !flags.is_empty() {
+        docker.arg(flags);
+    }
+    docker.arg(container);
+    if has_args {
+        docker.args(["/bin/sh", "-c", command]);
+    } else {
+        docker.arg(command);
+        if login_shell {
+            docker.arg("-l");
+        }
+    }
+    docker.status().map_err(|e| PwnenvError::Docker(e.to_string()))
+}
+
+/// The `-it`/`-t`/`-i`/`` combination `exec_shell` passes to `docker
+/// exec`, after `no_tty`/`no_interactive` drop whichever of `t`/`i`
+/// they ask for. Empty when both are set, meaning no flag is passed at
+/// all (not even a bare `-`).
+fn exec_flags(no_tty: bool, no_interactive: bool) -> String {
+    let mut flags = String::new();
+    if !no_interactive {
+        flags.push('i');
+    }
+    if !no_tty {
+        flags.push('t');
+    }
+    if flags.is_empty() {
+        String::new()
+    } else {
+        format!("-{flags}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_interactive_tty() {
+        assert_eq!(exec_flags(false, false), "-it");
+    }
+
+    #[test]
+    fn no_tty_drops_the_t_flag() {
+        assert_eq!(exec_flags(true, false), "-i");
+    }
+
+    #[test]
+    fn no_interactive_drops_the_i_flag() {
+        assert_eq!(exec_flags(false, true), "-t");
+    }
+
+    #[test]
+    fn both_dropped_leaves_no_flag_at_all() {
+        assert_eq!(exec_flags(true, true), "");
+    }
+
+    #[test]
+    fn parse_etc_shells_skips_comments_and_blank_lines() {
+        let contents = "# /etc/shells\n/bin/sh\n\n/bin/bash\n# extra\n/bin/zsh\n";
+        assert_eq!(
+            parse_etc_shells(contents),
+            vec!["/bin/sh".to_string(), "/bin/bash".to_string(), "/bin/zsh".to_string()]
+        );
+    }
+
+    #[test]
+    fn choose_fallback_prefers_an_exact_basename_match() {
+        let available = vec!["/usr/bin/zsh".to_string(), "/bin/bash".to_string()];
+        assert_eq!(choose_fallback(&available, "/bin/zsh"), Some("/usr/bin/zsh".to_string()));
+    }
+
+    #[test]
+    fn choose_fallback_falls_back_to_common_shell_order() {
+        let available = vec!["/bin/dash".to_string(), "/bin/bash".to_string()];
+        assert_eq!(choose_fallback(&available, "/usr/bin/fish"), Some("/bin/bash".to_string()));
+    }
+
+    #[test]
+    fn choose_fallback_with_nothing_recognized_takes_the_first_available() {
+        let available = vec!["/usr/bin/fish".to_string()];
+        assert_eq!(choose_fallback(&available, "/bin/zsh"), Some("/usr/bin/fish".to_string()));
+    }
+
+    #[test]
+    fn choose_fallback_with_nothing_available_is_none() {
+        assert_eq!(choose_fallback(&[], "/bin/zsh"), None);
+    }
+
+    #[test]
+    fn missing_shell_message_names_the_missing_shell_and_the_fallback() {
+        let available = vec!["/bin/bash".to_string(), "/bin/dash".to_string()];
+        let message = missing_shell_message("/usr/bin/zsh", &available, Some("/bin/bash"));
+        assert!(message.contains("/usr/bin/zsh"));
+        assert!(message.contains("/bin/bash, /bin/dash"));
+        assert!(message.contains("pwnenv build"));
+    }
+
+    #[test]
+    fn missing_shell_message_with_no_shells_detected_says_so() {
+        let message = missing_shell_message("/bin/zsh", &[], None);
+        assert!(message.contains("none detected"));
+        assert!(message.contains("/bin/sh"));
+    }
+
+    #[test]
+    fn validate_service_accepts_the_default_service() {
+        std::env::set_var("PWNENV_CONFIG_DIR", std::env::temp_dir().join("pwnenv-enter-test-default-service"));
+        let runtime = RuntimeDir::new("chall");
+        runtime.ensure_exists().unwrap();
+        std::fs::write(runtime.root().join("docker-compose.yml"), "services:\n  chall:\n    image: chall:latest\n").unwrap();
+        assert!(validate_service(&runtime, "chall").is_ok());
+        std::fs::remove_dir_all(runtime.root()).ok();
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+    }
+
+    #[test]
+    fn validate_service_rejects_an_unknown_service() {
+        std::env::set_var("PWNENV_CONFIG_DIR", std::env::temp_dir().join("pwnenv-enter-test-unknown-service"));
+        let runtime = RuntimeDir::new("chall");
+        runtime.ensure_exists().unwrap();
+        std::fs::write(runtime.root().join("docker-compose.yml"), "services:\n  chall:\n    image: chall:latest\n").unwrap();
+        assert!(validate_service(&runtime, "db").is_err());
+        std::fs::remove_dir_all(runtime.root()).ok();
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+    }
+
+    #[test]
+    fn validate_service_without_a_compose_file_is_an_error() {
+        std::env::set_var("PWNENV_CONFIG_DIR", std::env::temp_dir().join("pwnenv-enter-test-no-compose-file"));
+        let runtime = RuntimeDir::new("chall");
+        runtime.ensure_exists().unwrap();
+        assert!(validate_service(&runtime, "chall").is_err());
+        std::fs::remove_dir_all(runtime.root()).ok();
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+    }
+}
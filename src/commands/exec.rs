@@ -0,0 +1,200 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+use crate::runtime::{container_name, RuntimeDir};
+
+use super::{list_profiles, up};
+
+/// One environment's `exec --all` outcome. `None` means it was skipped
+/// (not running, and `--start` wasn't given) rather than having actually
+/// run and failed.
+struct Outcome {
+    env_name: String,
+    exit_code: Option<i32>,
+}
+
+/// Runs `command` inside every selected environment's container —
+/// `--all` (every environment with a runtime dir, see
+/// [`list_profiles::list_environments`]) or one or more repeatable
+/// `--name`s — prefixing every line of output with `[env_name]` so
+/// interleaved output under `--parallel` stays readable. An environment
+/// that isn't currently running is skipped with a notice unless `start`
+/// (`exec --start`) is set, in which case it's brought up first via
+/// [`up::up`], using whatever host directory [`RuntimeDir::host_dir`]
+/// has on record for it — the same resolution
+/// [`crate::commands::diff_env::load_env`] uses to work from outside the
+/// environment's own challenge directory.
+///
+/// Runs sequentially by default; `parallel` (`exec --parallel N`) runs
+/// up to `N` containers at once, chunked the same way
+/// [`crate::commands::kill::kill_all`] fans out across environments.
+/// Every target is attempted regardless of earlier failures, and the
+/// overall result is `Err` if any of them exited non-zero (or failed to
+/// start), after printing a per-environment summary — same "finish
+/// everything, then report" shape as `kill --all`.
+pub fn exec_all(names: &[String], all: bool, command: &[String], parallel: usize, start: bool) -> Result<()> {
+    if command.is_empty() {
+        return Err(PwnenvError::Docker(
+            "exec requires a command to run, e.g. `pwnenv exec --all -- pip install -U pwntools`".to_string(),
+        ));
+    }
+    let targets = resolve_targets(names, all)?;
+    if targets.is_empty() {
+        println!("no pwnenv environments found.");
+        return Ok(());
+    }
+
+    let rows = list_profiles::collect_rows()?;
+    let joined = command.join(" ");
+    let parallel = parallel.max(1);
+
+    let outcomes: Mutex<Vec<Outcome>> = Mutex::new(Vec::new());
+    for chunk in targets.chunks(parallel) {
+        std::thread::scope(|scope| {
+            for env_name in chunk {
+                scope.spawn(|| {
+                    let outcome = run_one(env_name, &rows, &joined, start);
+                    outcomes.lock().unwrap().push(outcome);
+                });
+            }
+        });
+    }
+
+    let outcomes = outcomes.into_inner().unwrap();
+    let mut failures = 0;
+    println!("exec summary:");
+    for outcome in &outcomes {
+        match outcome.exit_code {
+            Some(0) => println!("  {}: ok", outcome.env_name),
+            Some(code) => {
+                failures += 1;
+                println!("  {}: exited {code}", outcome.env_name);
+            }
+            None => println!("  {}: skipped (not running; pass --start to bring it up)", outcome.env_name),
+        }
+    }
+
+    if failures > 0 {
+        return Err(PwnenvError::Docker(format!("{failures} of {} environment(s) failed", outcomes.len())));
+    }
+    Ok(())
+}
+
+fn resolve_targets(names: &[String], all: bool) -> Result<Vec<String>> {
+    if all {
+        return list_profiles::list_environments();
+    }
+    if names.is_empty() {
+        return Err(PwnenvError::Docker(
+            "exec requires --all or at least one --name".to_string(),
+        ));
+    }
+    Ok(names.to_vec())
+}
+
+fn run_one(env_name: &str, rows: &[list_profiles::ProfileRow], command: &str, start: bool) -> Outcome {
+    let running = rows.iter().any(|row| row.env_name == env_name && row.running);
+
+    if !running {
+        if !start {
+            return Outcome { env_name: env_name.to_string(), exit_code: None };
+        }
+        if let Err(e) = bring_up(env_name) {
+            eprintln!("[{env_name}] failed to start: {e}");
+            return Outcome { env_name: env_name.to_string(), exit_code: Some(1) };
+        }
+    }
+
+    let container = container_name(env_name);
+    match exec_in_container(env_name, &container, command) {
+        Ok(code) => Outcome { env_name: env_name.to_string(), exit_code: Some(code) },
+        Err(e) => {
+            eprintln!("[{env_name}] {e}");
+            Outcome { env_name: env_name.to_string(), exit_code: Some(1) }
+        }
+    }
+}
+
+/// Brings `env_name` up using whatever `pwnenv.yaml` its registered host
+/// directory still has, for `exec --start` against an environment that
+/// was `init`ed but never (or no longer) running.
+fn bring_up(env_name: &str) -> Result<()> {
+    let runtime = RuntimeDir::new(env_name);
+    let host_dir = runtime
+        .host_dir()
+        .ok_or_else(|| PwnenvError::UnknownEnvironment(env_name.to_string()))?;
+    let config = Config::load(&host_dir.join("pwnenv.yaml"))?;
+    up::up(env_name, None, &config, &runtime, &host_dir, None)
+}
+
+/// Runs `command` via `docker exec <container> /bin/sh -c <command>`,
+/// streaming stdout/stderr line-by-line with a `[env_name]` prefix as
+/// they arrive rather than buffering the whole thing, so a long-running
+/// command's progress is visible immediately even with several
+/// environments running under `--parallel`.
+fn exec_in_container(env_name: &str, container: &str, command: &str) -> Result<i32> {
+    let mut child = Command::new("docker")
+        .args(["exec", container, "/bin/sh", "-c", command])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
+
+    let out_env = env_name.to_string();
+    let out_thread = std::thread::spawn(move || stream_lines(stdout, &out_env, false));
+    let err_env = env_name.to_string();
+    let err_thread = std::thread::spawn(move || stream_lines(stderr, &err_env, true));
+
+    out_thread.join().ok();
+    err_thread.join().ok();
+
+    let status = child.wait().map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+fn stream_lines(reader: impl Read, env_name: &str, is_stderr: bool) {
+    for line in BufReader::new(reader).lines().map_while(std::result::Result::ok) {
+        if is_stderr {
+            eprintln!("[{env_name}] {line}");
+        } else {
+            println!("[{env_name}] {line}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_with_no_environments_returns_targets_empty_not_an_error() {
+        std::env::set_var("PWNENV_CONFIG_DIR", std::env::temp_dir().join("pwnenv-exec-test-empty"));
+        let targets = resolve_targets(&[], true).unwrap();
+        assert!(targets.is_empty());
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+    }
+
+    #[test]
+    fn no_all_and_no_names_is_an_error() {
+        assert!(resolve_targets(&[], false).is_err());
+    }
+
+    #[test]
+    fn explicit_names_are_used_verbatim() {
+        let names = vec!["chall-a".to_string(), "chall-b".to_string()];
+        assert_eq!(resolve_targets(&names, false).unwrap(), names);
+    }
+
+    #[test]
+    fn run_one_skips_a_non_running_environment_without_start() {
+        let rows = vec![];
+        let outcome = run_one("chall", &rows, "true", false);
+        assert_eq!(outcome.exit_code, None);
+    }
+}
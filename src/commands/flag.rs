@@ -0,0 +1,16 @@
+use crate::activity;
+use crate::error::Result;
+use crate::runtime::RuntimeDir;
+
+/// Writes a local, non-secret flag file into the environment's runtime
+/// dir (mounted at `/flag` inside the container), so an exploit written
+/// against the real deployment can be sanity-checked locally without
+/// ever touching the actual flag.
+pub fn write_local_flag(runtime: &RuntimeDir, contents: Option<&str>) -> Result<()> {
+    let flag = contents
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("pwnenv{{local_test_flag_{}}}", std::process::id()));
+    std::fs::write(runtime.root().join("flag"), flag)?;
+    activity::log_event(runtime, "local-flag", &[], Some(0));
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use std::process::Command;
+
+use crate::activity;
+use crate::error::{PwnenvError, Result};
+use crate::recordings::shell_quote;
+use crate::runtime::{container_name, RuntimeDir};
+
+/// Where the `"glibc"` tool preset (see [`crate::tool_presets::lookup`])
+/// clones `bminor/glibc` to inside the container.
+const SRC_DIR: &str = "/opt/src/glibc";
+
+/// Checks out `version` (a release tag, e.g. `"2.31"`) in the container's
+/// already-cloned `bminor/glibc` checkout at [`SRC_DIR`] and builds it
+/// into `/opt/glibc-<version>`, streaming `configure`/`make`'s output
+/// straight to the terminal the same way `enter` streams a shell.
+///
+/// Resumable: if `/opt/glibc-<version>` already exists from a prior run
+/// (including one killed mid-build), `configure`/`make` are skipped
+/// entirely and the function goes straight to printing the patchelf/gdb
+/// commands below — re-running `make install` over a directory `make`
+/// itself already finished isn't needed, and re-running `configure`
+/// would throw away whatever of the build completed before it was
+/// interrupted.
+pub fn build(env_name: &str, version: &str, patch: Option<&str>) -> Result<()> {
+    let container = container_name(env_name);
+    let tag = format!("glibc-{version}");
+    let install_dir = format!("/opt/glibc-{version}");
+    let build_dir = format!("/tmp/glibc-build-{version}");
+
+    let checkout = format!(
+        "cd {src} && git fetch --tags && git checkout {tag}",
+        src = shell_quote(SRC_DIR),
+        tag = shell_quote(&tag),
+    );
+    run_in_container(&container, &checkout)?;
+
+    if !dir_exists(&container, &install_dir)? {
+        let build = format!(
+            "mkdir -p {build_dir} && cd {build_dir} && {src}/configure --prefix={install} \
+             CFLAGS=\"-g -Og\" && make -j$(nproc) && make install",
+            build_dir = shell_quote(&build_dir),
+            src = SRC_DIR,
+            install = shell_quote(&install_dir),
+        );
+        run_in_container(&container, &build)?;
+    } else {
+        println!("{install_dir} already exists inside {container}; skipping configure/make.");
+    }
+
+    let ld_so = format!(
+        "{install_dir}/lib/{}",
+        if install_dir.contains("32") { "ld-linux.so.2" } else { "ld-linux-x86-64.so.2" }
+    );
+    let patchelf_command =
+        format!("patchelf --set-interpreter {ld_so} --set-rpath {install_dir}/lib <binary>");
+
+    if let Some(binary) = patch {
+        let run = format!(
+            "patchelf --set-interpreter {ld_so} --set-rpath {install}/lib {binary}",
+            ld_so = shell_quote(&ld_so),
+            install = shell_quote(&install_dir),
+            binary = shell_quote(binary),
+        );
+        run_in_container(&container, &run)?;
+        println!("{binary}: patched to use glibc {version} from {install_dir}.");
+    } else {
+        println!("to run a binary against this build: {patchelf_command}");
+    }
+    println!("to source its symbols in gdb: directory {SRC_DIR}");
+
+    let runtime = RuntimeDir::new(env_name);
+    activity::log_event(&runtime, "glibc-build", &[version.to_string()], Some(0));
+    Ok(())
+}
+
+fn run_in_container(container: &str, script: &str) -> Result<()> {
+    let status = Command::new("docker")
+        .args(["exec", "-it", container, "/bin/sh", "-c", script])
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!("glibc build step exited with {status}")));
+    }
+    Ok(())
+}
+
+fn dir_exists(container: &str, dir: &str) -> Result<bool> {
+    let status = Command::new("docker")
+        .args(["exec", container, "/bin/sh", "-c", &format!("test -d {}", shell_quote(dir))])
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    Ok(status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_argument_is_shell_quoted_into_the_checkout_tag() {
+        // Mirrors `commands::analyze`'s handling of untrusted CLI args:
+        // `version` lands in a `git checkout` tag, so it's quoted the
+        // same way `binary` is there rather than spliced in raw.
+        let tag = shell_quote("glibc-2.31");
+        assert_eq!(tag, "'glibc-2.31'");
+    }
+}
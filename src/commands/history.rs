@@ -0,0 +1,88 @@
+use crate::activity::{self, Event};
+use crate::error::{PwnenvError, Result};
+use crate::runtime::RuntimeDir;
+
+/// Pretty-prints `env_name`'s `activity.log`, optionally restricted to
+/// events at or after `since` (a unix timestamp, as recorded by
+/// [`crate::activity::log_event`]).
+pub fn history(env_name: &str, since: Option<u64>) -> Result<()> {
+    let runtime = RuntimeDir::new(env_name);
+    let events = activity::read_events(&runtime, since);
+
+    if events.is_empty() {
+        println!("{env_name}: no activity recorded.");
+        return Ok(());
+    }
+
+    for event in &events {
+        println!("{}", format_event(event));
+    }
+    Ok(())
+}
+
+fn format_event(event: &Event) -> String {
+    let args = if event.args.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", event.args.join(" "))
+    };
+    match event.exit_code {
+        Some(code) => format!("{}  {}{args} (exit {code})", event.timestamp, event.action),
+        None => format!("{}  {}{args}", event.timestamp, event.action),
+    }
+}
+
+/// Parses `history --since`: either a raw unix timestamp, or a relative
+/// duration like `2h`/`3d` counted back from now.
+pub fn parse_since(raw: &str) -> Result<u64> {
+    if let Ok(timestamp) = raw.parse::<u64>() {
+        return Ok(timestamp);
+    }
+
+    let (amount, unit) = raw.split_at(raw.len().saturating_sub(1));
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| PwnenvError::Docker(format!("invalid --since '{raw}'; expected a unix timestamp or e.g. '2h', '3d'")))?;
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => {
+            return Err(PwnenvError::Docker(format!(
+                "invalid --since '{raw}'; expected a unix timestamp or e.g. '2h', '3d'"
+            )))
+        }
+    };
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok(now.saturating_sub(amount * seconds_per_unit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_timestamp_is_passed_through() {
+        assert_eq!(parse_since("1700000000").unwrap(), 1700000000);
+    }
+
+    #[test]
+    fn relative_duration_is_subtracted_from_now() {
+        let since = parse_since("1h").unwrap();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert!(since <= now - 3599 && since >= now - 3601);
+    }
+
+    #[test]
+    fn unknown_unit_is_rejected() {
+        assert!(parse_since("2x").is_err());
+    }
+}
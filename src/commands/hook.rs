@@ -0,0 +1,80 @@
+use crate::error::{PwnenvError, Result};
+
+/// Prints the `pwnenv hook <shell>` snippet for `shell` to stdout, for
+/// the caller to `eval`/`source` from their rc file. Every snippet: on
+/// each directory change, shells out to the hidden, docker-free `pwnenv
+/// __probe` (see [`crate::commands::probe`]) and exports `PWNENV_ENV`
+/// (the registered environment's name, or empty outside one) for the
+/// prompt to show; and defines a `pe` alias for `pwnenv enter`.
+/// `__probe` only reads a handful of small marker files, so this never
+/// adds a noticeable pause to `cd`.
+pub fn hook(shell: &str) -> Result<()> {
+    let snippet = match shell {
+        "bash" => BASH,
+        "zsh" => ZSH,
+        "fish" => FISH,
+        other => return Err(PwnenvError::UnknownShell(other.to_string())),
+    };
+    print!("{snippet}");
+    Ok(())
+}
+
+const BASH: &str = r#"# pwnenv shell hook. Add to ~/.bashrc:
+#   eval "$(pwnenv hook bash)"
+alias pe='pwnenv enter'
+__pwnenv_probe() {
+    PWNENV_ENV="$(pwnenv __probe "$PWD" 2>/dev/null)"
+    export PWNENV_ENV
+}
+case ";$PROMPT_COMMAND;" in
+    *";__pwnenv_probe;"*) ;;
+    *) PROMPT_COMMAND="__pwnenv_probe;${PROMPT_COMMAND}" ;;
+esac
+__pwnenv_probe
+"#;
+
+const ZSH: &str = r#"# pwnenv shell hook. Add to ~/.zshrc:
+#   eval "$(pwnenv hook zsh)"
+alias pe='pwnenv enter'
+__pwnenv_probe() {
+    PWNENV_ENV="$(pwnenv __probe "$PWD" 2>/dev/null)"
+    export PWNENV_ENV
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd __pwnenv_probe
+__pwnenv_probe
+"#;
+
+const FISH: &str = r#"# pwnenv shell hook. Add to ~/.config/fish/config.fish:
+#   pwnenv hook fish | source
+alias pe 'pwnenv enter'
+function __pwnenv_probe --on-variable PWD
+    set -gx PWNENV_ENV (pwnenv __probe $PWD 2>/dev/null)
+end
+__pwnenv_probe
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_shell_is_rejected() {
+        assert!(hook("powershell").is_err());
+    }
+
+    #[test]
+    fn every_known_shell_snippet_defines_the_pe_alias_and_calls_probe() {
+        for shell in ["bash", "zsh", "fish"] {
+            let snippet = match shell {
+                "bash" => BASH,
+                "zsh" => ZSH,
+                "fish" => FISH,
+                _ => unreachable!(),
+            };
+            assert!(snippet.contains("pe"), "{shell} snippet is missing the pe alias");
+            assert!(snippet.contains("__pwnenv_probe"), "{shell} snippet never calls the probe");
+            assert!(snippet.contains("PWNENV_ENV"), "{shell} snippet never sets PWNENV_ENV");
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::error::{PwnenvError, Result};
+use crate::labels;
+
+/// One `pwnenv`-built image: everything `docker image ls` knows about it,
+/// joined with which environments' containers currently run from it.
+#[derive(Debug, Serialize)]
+pub struct ImageRow {
+    pub tag: String,
+    pub image_id: String,
+    pub size: String,
+    pub created_at: String,
+    pub env_name: Option<String>,
+    pub config_hash: Option<String>,
+    /// Environments with a container (any state, per `docker ps -a`)
+    /// created from this image. Empty means `rm`/`prune` may remove it.
+    pub referenced_by: Vec<String>,
+}
+
+/// Every image docker labeled as a pwnenv build (see [`crate::labels`]),
+/// joined against `docker ps -a` to say which environments still
+/// reference each one.
+pub fn collect_rows() -> Result<Vec<ImageRow>> {
+    let images = list_labeled_images()?;
+    let references = containers_by_image()?;
+
+    let mut rows: Vec<ImageRow> = images
+        .into_iter()
+        .map(|entry| {
+            let referenced_by = references.get(&entry.image_id).cloned().unwrap_or_default();
+            ImageRow {
+                tag: entry.tag,
+                image_id: entry.image_id,
+                size: entry.size,
+                created_at: entry.created_at,
+                env_name: entry.env_name,
+                config_hash: entry.config_hash,
+                referenced_by,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| a.tag.cmp(&b.tag));
+    Ok(rows)
+}
+
+pub fn images(json: bool) -> Result<()> {
+    let rows = collect_rows()?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&rows)
+                .map_err(|e| PwnenvError::Docker(format!("failed to serialize images output: {e}")))?
+        );
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("no pwnenv images found.");
+        return Ok(());
+    }
+    for row in &rows {
+        let refs = if row.referenced_by.is_empty() {
+            "unreferenced".to_string()
+        } else {
+            row.referenced_by.join(", ")
+        };
+        println!(
+            "{:<24} {:<14} {:<10} {:<24} {}",
+            row.tag, row.image_id, row.size, row.created_at, refs
+        );
+    }
+    Ok(())
+}
+
+/// Removes the image matching `selector` (a tag or an image ID/hash
+/// prefix), after checking nothing references it. `force` removes it
+/// anyway, even if [`ImageRow::referenced_by`] isn't empty.
+pub fn images_rm(selector: &str, force: bool) -> Result<()> {
+    let rows = collect_rows()?;
+    let row = find_selected(&rows, selector)?;
+
+    if !force && !row.referenced_by.is_empty() {
+        return Err(PwnenvError::Docker(format!(
+            "image '{selector}' is still referenced by: {} (pass --force to remove it anyway)",
+            row.referenced_by.join(", ")
+        )));
+    }
+
+    remove_image(&row.image_id)?;
+    println!("removed {} ({}).", row.tag, row.image_id);
+    Ok(())
+}
+
+/// Removes every pwnenv-labeled image with no referencing container.
+pub fn images_prune() -> Result<()> {
+    let rows = collect_rows()?;
+    let unreferenced: Vec<&ImageRow> = rows.iter().filter(|row| row.referenced_by.is_empty()).collect();
+
+    if unreferenced.is_empty() {
+        println!("nothing to prune.");
+        return Ok(());
+    }
+
+    for row in &unreferenced {
+        remove_image(&row.image_id)?;
+        println!("removed {} ({}).", row.tag, row.image_id);
+    }
+    Ok(())
+}
+
+fn find_selected<'a>(rows: &'a [ImageRow], selector: &str) -> Result<&'a ImageRow> {
+    rows.iter()
+        .find(|row| {
+            row.tag == selector
+                || row.image_id == selector
+                || row.image_id.starts_with(selector)
+                || row.config_hash.as_deref() == Some(selector)
+        })
+        .ok_or_else(|| PwnenvError::Docker(format!("no pwnenv image matches '{selector}'")))
+}
+
+fn remove_image(image_id: &str) -> Result<()> {
+    let status = Command::new("docker")
+        .args(["rmi", image_id])
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!("docker rmi {image_id} exited with {status}")));
+    }
+    Ok(())
+}
+
+struct ImageEntry {
+    tag: String,
+    image_id: String,
+    size: String,
+    created_at: String,
+    env_name: Option<String>,
+    config_hash: Option<String>,
+}
+
+/// Runs `docker image ls --filter label=dev.pwnenv.env_name --format
+/// "{{json .}}"`, one JSON object per line (NDJSON). Unparseable lines
+/// (an older docker ignoring the format and falling back to its table)
+/// are skipped with a warning rather than failing the whole command.
+fn list_labeled_images() -> Result<Vec<ImageEntry>> {
+    let output = Command::new("docker")
+        .args(["image", "ls", "--filter", &format!("label={}", labels::ENV_NAME), "--format", "{{json .}}"])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !output.status.success() {
+        return Err(PwnenvError::Docker(format!("docker image ls exited with {}", output.status)));
+    }
+
+    let mut entries = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(raw) = serde_json::from_str::<RawImageEntry>(line) else {
+            eprintln!("pwnenv: skipping unparseable `docker image ls` line: {line}");
+            continue;
+        };
+        let parsed_labels = parse_labels(&raw.labels);
+        entries.push(ImageEntry {
+            tag: format!("{}:{}", raw.repository, raw.tag),
+            image_id: raw.id,
+            size: raw.size,
+            created_at: raw.created_at,
+            env_name: parsed_labels.get(labels::ENV_NAME).cloned(),
+            config_hash: parsed_labels.get(labels::CONFIG_HASH).cloned(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Every environment (by name) with a container, any state, created from
+/// each image ID — the "who references this" half of [`collect_rows`]'s
+/// join. Uses every container docker knows about, not just ones pwnenv
+/// labeled, since an image can outlive the runtime dir that built it.
+fn containers_by_image() -> Result<HashMap<String, Vec<String>>> {
+    let output = Command::new("docker")
+        .args(["ps", "-a", "--format", "{{json .}}"])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !output.status.success() {
+        return Err(PwnenvError::Docker(format!("docker ps exited with {}", output.status)));
+    }
+
+    let mut by_image: HashMap<String, Vec<String>> = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(raw) = serde_json::from_str::<RawContainerEntry>(line) else {
+            continue;
+        };
+        let referrer = parse_labels(&raw.labels)
+            .get(labels::ENV_NAME)
+            .cloned()
+            .unwrap_or(raw.names);
+        by_image.entry(raw.image).or_default().push(referrer);
+    }
+    Ok(by_image)
+}
+
+fn parse_labels(raw: &str) -> HashMap<String, String> {
+    raw.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RawImageEntry {
+    #[serde(rename = "Repository")]
+    repository: String,
+    #[serde(rename = "Tag")]
+    tag: String,
+    #[serde(rename = "ID")]
+    id: String,
+    #[serde(rename = "Size")]
+    size: String,
+    #[serde(rename = "CreatedAt")]
+    created_at: String,
+    #[serde(rename = "Labels")]
+    labels: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RawContainerEntry {
+    #[serde(rename = "Names")]
+    names: String,
+    #[serde(rename = "Image")]
+    image: String,
+    #[serde(rename = "Labels")]
+    labels: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(tag: &str, id: &str, hash: Option<&str>, referenced_by: &[&str]) -> ImageRow {
+        ImageRow {
+            tag: tag.to_string(),
+            image_id: id.to_string(),
+            size: "10MB".to_string(),
+            created_at: "2026-01-01".to_string(),
+            env_name: None,
+            config_hash: hash.map(str::to_string),
+            referenced_by: referenced_by.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn finds_by_exact_tag() {
+        let rows = vec![row("pwnenv-env:latest", "abc123", Some("deadbeef"), &[])];
+        assert_eq!(find_selected(&rows, "pwnenv-env:latest").unwrap().image_id, "abc123");
+    }
+
+    #[test]
+    fn finds_by_image_id_prefix() {
+        let rows = vec![row("pwnenv-env:latest", "abc123456", Some("deadbeef"), &[])];
+        assert_eq!(find_selected(&rows, "abc123").unwrap().tag, "pwnenv-env:latest");
+    }
+
+    #[test]
+    fn finds_by_config_hash() {
+        let rows = vec![row("pwnenv-env:latest", "abc123", Some("deadbeef"), &[])];
+        assert_eq!(find_selected(&rows, "deadbeef").unwrap().tag, "pwnenv-env:latest");
+    }
+
+    #[test]
+    fn unknown_selector_is_an_error() {
+        let rows = vec![row("pwnenv-env:latest", "abc123", Some("deadbeef"), &[])];
+        assert!(find_selected(&rows, "nope").is_err());
+    }
+
+    #[test]
+    fn parses_comma_separated_labels() {
+        let parsed = parse_labels("dev.pwnenv.env_name=chall,dev.pwnenv.config_hash=deadbeef");
+        assert_eq!(parsed.get("dev.pwnenv.env_name"), Some(&"chall".to_string()));
+        assert_eq!(parsed.get("dev.pwnenv.config_hash"), Some(&"deadbeef".to_string()));
+    }
+}
@@ -0,0 +1,361 @@
+use std::path::Path;
+
+use crate::activity;
+use crate::bake::copy_bake;
+use crate::config::{validate_image_tag, Config};
+use crate::error::{PwnenvError, Result};
+use crate::mounts;
+use crate::programs::{copy_programs, CopyFilter, PROGRAMS_CONTAINER_PATH};
+use crate::runtime::setup_minimum_requirements;
+
+/// Sets an environment's runtime dir up from `config`: migrates/creates
+/// the runtime dir, resolves extra bind mounts, copies `programs_dir`
+/// into it (if set), and stages `config.bake`'s paths for the Dockerfile
+/// to `COPY` in (see [`crate::bake`]).
+///
+/// `no_privileged` is the `init --no-privileged` quick flag: it records
+/// an override that later `build`/`up` runs pick up, without editing
+/// `pwnenv.yaml` itself. `cli_mounts` are `--mount host:container` pairs;
+/// combined with `config.mounts` and resolved against `invoked_from`
+/// (the cwd `init` was run from, before anything changes it). `from_image`
+/// is `init --from-image <ref>`: when set, `build` is skipped entirely and
+/// `up` runs the prebuilt image instead of building the environment's own
+/// Dockerfile. `follow_external_symlinks` is `init --follow-external-symlinks`:
+/// see [`crate::programs::CopyFilter`]. `no_copy`/`no_mount` are `init
+/// --no-copy`/`init --no-mount`: independently disable baking
+/// `programs_dir` into the image and bind-mounting it into the
+/// container, respectively; setting both is rejected when `programs_dir`
+/// is set, since the container would then have no access to it at all.
+/// `offline` is `init --offline`: later `build`/`up` runs refuse to touch
+/// the network, requiring a matching image already on disk instead.
+/// `force` is `init --force`: for scripted/CI use, it skips the mount
+/// overlap check (two mounts, or a mount and `programs_dir`, targeting
+/// the same container path) instead of erroring out. It's distinct from
+/// the global `--yes`: `--yes` only auto-answers yes/no prompts (there
+/// are none in `init` itself today), while `--force` disables a
+/// validation that would otherwise hard-stop the run. `gdb_plugin` is
+/// `init --gdb-plugin`: records a per-environment override of
+/// `pwnenv.yaml`'s `gdb_plugin`, picked up by `build` the same way
+/// `no_privileged`'s override is picked up by `up`.
+#[allow(clippy::too_many_arguments)]
+pub fn init(
+    env_name: &str,
+    config: &Config,
+    no_privileged: bool,
+    cli_mounts: &[String],
+    invoked_from: &Path,
+    force_copy: bool,
+    from_image: Option<&str>,
+    follow_external_symlinks: bool,
+    no_copy: bool,
+    no_mount: bool,
+    offline: bool,
+    image_tag: Option<&str>,
+    force: bool,
+    gdb_plugin: Option<&str>,
+) -> Result<()> {
+    crate::host_path::validate(invoked_from)?;
+    reject_if_inside_runtime_tree(invoked_from)?;
+
+    if no_copy && no_mount && config.programs_dir.is_some() {
+        return Err(PwnenvError::NoProgramsDelivery);
+    }
+
+    let runtime = setup_minimum_requirements(env_name)?;
+    runtime.set_host_dir(invoked_from)?;
+    runtime.set_programs_delivery(!no_copy, !no_mount)?;
+    runtime.set_offline(offline)?;
+
+    if no_privileged {
+        runtime.set_privileged_override(false)?;
+    }
+
+    if let Some(image_ref) = from_image {
+        validate_image_ref(image_ref)?;
+        runtime.set_image_override(image_ref)?;
+        println!("{env_name}: will use prebuilt image '{image_ref}' instead of building.");
+    }
+
+    if let Some(tag) = image_tag {
+        validate_image_tag(tag)?;
+        runtime.set_image_tag_override(tag)?;
+        println!("{env_name}: will tag the built image '{tag}'.");
+    }
+
+    if let Some(plugin) = gdb_plugin {
+        crate::gdb_plugins::validate_plugin(plugin)?;
+        runtime.set_gdb_plugin_override(plugin)?;
+        println!("{env_name}: will build gdb with the '{plugin}' plugin.");
+    }
+
+    let mut resolved_mounts = mounts::resolve(cli_mounts, &config.mounts, invoked_from, force)?;
+    if !no_mount && config.programs_dir.is_some() {
+        if let Some(collision) = resolved_mounts.iter().find(|m| m.container == PROGRAMS_CONTAINER_PATH) {
+            if !force {
+                return Err(PwnenvError::MountCollision {
+                    path: PROGRAMS_CONTAINER_PATH.to_string(),
+                    first: collision.host.display().to_string(),
+                    second: "programs_dir".to_string(),
+                });
+            }
+        }
+        resolved_mounts.push(mounts::Mount {
+            host: runtime.root().join("programs"),
+            container: PROGRAMS_CONTAINER_PATH.to_string(),
+        });
+    }
+    runtime.set_mounts(&resolved_mounts)?;
+    if !resolved_mounts.is_empty() {
+        println!("mounts: {}", resolved_mounts.len());
+    }
+
+    if let Some(programs_dir) = &config.programs_dir {
+        if no_copy {
+            println!("programs: skipped (--no-copy)");
+        } else {
+            let filter = CopyFilter {
+                respect_gitignore: config.respect_gitignore,
+                exclude: config.programs_exclude.clone(),
+                force_include: config.programs_force_include.clone(),
+                include: config.programs_include.clone(),
+                follow_external_symlinks,
+            };
+            let report = copy_programs(
+                std::path::Path::new(programs_dir),
+                &runtime.root().join("programs"),
+                &runtime.programs_manifest_path(),
+                force_copy,
+                &filter,
+            )?;
+            println!(
+                "programs: copied {}, skipped {} (unchanged), deleted {} (removed from source), \
+                 skipped {} (.gitignore), skipped {} (excluded), skipped {} (not in programs_include), \
+                 skipped {} (special files), skipped {} (external symlinks)",
+                report.copied,
+                report.skipped,
+                report.deleted,
+                report.skipped_gitignore,
+                report.skipped_excluded,
+                report.skipped_not_included,
+                report.skipped_special,
+                report.skipped_external_symlink,
+            );
+        }
+    }
+
+    copy_bake(&config.bake, Path::new("."), &runtime.bake_dir())?;
+    if !config.bake.is_empty() {
+        println!("bake: copied {} path(s) into the build context", config.bake.len());
+    }
+
+    if !config.i386 && crate::arch::contains_32bit_elf(invoked_from) {
+        println!(
+            "note: found a 32-bit ELF in this directory; add `i386: true` to pwnenv.yaml \
+             to install 32-bit multiarch libraries."
+        );
+    }
+
+    activity::log_event(
+        &runtime,
+        "init",
+        &init_args(
+            no_privileged,
+            force_copy,
+            from_image,
+            follow_external_symlinks,
+            no_copy,
+            no_mount,
+            offline,
+            image_tag,
+            force,
+        ),
+        Some(0),
+    );
+
+    if let Some(hook) = &config.post_init_hook {
+        run_post_init_hook(hook, invoked_from, env_name, config)?;
+    }
+
+    print_summary(env_name, config);
+    println!("{env_name}: initialized.");
+    Ok(())
+}
+
+/// Runs `config.post_init_hook` (resolved against `invoked_from` if
+/// relative) once `init` has otherwise finished, with
+/// `PWNENV_ENV_NAME`/`PWNENV_FORWARDED_PORT` set for it to act on. A
+/// non-zero exit (or a failure to even start it) fails `init` itself —
+/// this is a setup step the user opted into, not an optional nicety.
+fn run_post_init_hook(hook: &Path, invoked_from: &Path, env_name: &str, config: &Config) -> Result<()> {
+    let resolved = invoked_from.join(hook);
+    println!("post_init_hook: running {}", resolved.display());
+
+    let status = std::process::Command::new(&resolved)
+        .env("PWNENV_ENV_NAME", env_name)
+        .env(
+            "PWNENV_FORWARDED_PORT",
+            config.forwarded_port.map(|p| p.to_string()).unwrap_or_default(),
+        )
+        .status()
+        .map_err(|e| PwnenvError::Docker(format!("failed to run post_init_hook '{}': {e}", resolved.display())))?;
+
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "post_init_hook '{}' exited with {status}",
+            resolved.display()
+        )));
+    }
+    println!("post_init_hook: ok");
+    Ok(())
+}
+
+/// Refuses `init` when `invoked_from` is [`crate::runtime::state_dir`]
+/// itself or somewhere underneath it — it happens when exploring the
+/// generated runtime files and running `init` from in there by mistake.
+/// `programs_dir` defaults to the directory `init` was run from, so that
+/// copy step would walk and copy the entire state tree into a fresh
+/// subdirectory of itself; each re-run doubles it until the disk fills.
+/// Compares canonicalized paths so a symlink into the state tree is
+/// caught too, not just a literal path under it.
+fn reject_if_inside_runtime_tree(invoked_from: &Path) -> Result<()> {
+    let state_dir = crate::runtime::state_dir();
+    let canonical_invoked = invoked_from.canonicalize().unwrap_or_else(|_| invoked_from.to_path_buf());
+    let canonical_state_dir = state_dir.canonicalize().unwrap_or(state_dir);
+    if canonical_invoked == canonical_state_dir || canonical_invoked.starts_with(&canonical_state_dir) {
+        return Err(PwnenvError::InitInsideRuntimeTree(canonical_invoked));
+    }
+    Ok(())
+}
+
+/// Flattens `init`'s non-default flags into an `activity.log` args list.
+#[allow(clippy::too_many_arguments)]
+fn init_args(
+    no_privileged: bool,
+    force_copy: bool,
+    from_image: Option<&str>,
+    follow_external_symlinks: bool,
+    no_copy: bool,
+    no_mount: bool,
+    offline: bool,
+    image_tag: Option<&str>,
+    force: bool,
+) -> Vec<String> {
+    let mut args = Vec::new();
+    if no_privileged {
+        args.push("--no-privileged".to_string());
+    }
+    if force_copy {
+        args.push("--force-copy".to_string());
+    }
+    if let Some(image_ref) = from_image {
+        args.push(format!("--from-image={image_ref}"));
+    }
+    if follow_external_symlinks {
+        args.push("--follow-external-symlinks".to_string());
+    }
+    if no_copy {
+        args.push("--no-copy".to_string());
+    }
+    if no_mount {
+        args.push("--no-mount".to_string());
+    }
+    if offline {
+        args.push("--offline".to_string());
+    }
+    if let Some(tag) = image_tag {
+        args.push(format!("--image-tag={tag}"));
+    }
+    if force {
+        args.push("--force".to_string());
+    }
+    args
+}
+
+/// Prints a concise confirmation of what `init` just set up. `build`'s
+/// own tool count/time aren't known yet at `init` time (that's a
+/// separate step in this tool's workflow), so this covers what `init`
+/// itself decided: the base image, how many tools `build` will install,
+/// any forwarded port, the workspace mount, and the command to enter
+/// once the environment is built and up.
+fn print_summary(env_name: &str, config: &Config) {
+    println!();
+    println!("summary:");
+    println!("  base image:    {}", config.base_image);
+    println!("  tools:         {}", config.tools.len());
+    match config.forwarded_port {
+        Some(port) => println!("  forwarded port: {port}"),
+        None => println!("  forwarded port: none"),
+    }
+    match &config.workspace_dir {
+        Some(workspace_dir) => {
+            let suffix = if config.workspace_readonly { " (read-only)" } else { "" };
+            println!("  workspace:     {workspace_dir} -> /workspace{suffix}");
+        }
+        None => println!("  workspace:     none"),
+    }
+    if !config.bake.is_empty() {
+        println!("  baked paths:   {}", config.bake.len());
+    }
+    println!("  enter with:    pwnenv enter {env_name}");
+    println!();
+}
+
+/// Rejects obviously-malformed image refs (empty, or containing
+/// whitespace) before writing them into the runtime dir. Full validation
+/// of the registry/name/tag grammar is left to `docker` itself.
+fn validate_image_ref(image_ref: &str) -> Result<()> {
+    if image_ref.is_empty() || image_ref.chars().any(char::is_whitespace) {
+        return Err(PwnenvError::Docker(format!(
+            "invalid image reference '{image_ref}'"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invoked_from_inside_the_state_dir_is_rejected() {
+        let state_dir = std::env::temp_dir().join("pwnenv-init-test-inside-direct");
+        std::fs::create_dir_all(state_dir.join("some-env")).unwrap();
+        std::env::set_var("PWNENV_CONFIG_DIR", &state_dir);
+
+        assert!(reject_if_inside_runtime_tree(&state_dir.join("some-env")).is_err());
+
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::fs::remove_dir_all(&state_dir).ok();
+    }
+
+    #[test]
+    fn invoked_from_reached_via_a_symlink_into_the_state_dir_is_rejected() {
+        let base = std::env::temp_dir().join("pwnenv-init-test-inside-symlink");
+        std::fs::remove_dir_all(&base).ok();
+        let state_dir = base.join("actual-state");
+        std::fs::create_dir_all(&state_dir).unwrap();
+        let link = base.join("link-to-state");
+        std::os::unix::fs::symlink(&state_dir, &link).unwrap();
+        std::env::set_var("PWNENV_CONFIG_DIR", &state_dir);
+
+        assert!(reject_if_inside_runtime_tree(&link).is_err());
+
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn invoked_from_outside_the_state_dir_is_allowed() {
+        let state_dir = std::env::temp_dir().join("pwnenv-init-test-outside");
+        std::fs::create_dir_all(&state_dir).unwrap();
+        std::env::set_var("PWNENV_CONFIG_DIR", &state_dir);
+        let elsewhere = std::env::temp_dir().join("pwnenv-init-test-elsewhere");
+        std::fs::create_dir_all(&elsewhere).unwrap();
+
+        assert!(reject_if_inside_runtime_tree(&elsewhere).is_ok());
+
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::fs::remove_dir_all(&state_dir).ok();
+        std::fs::remove_dir_all(&elsewhere).ok();
+    }
+}
@@ -0,0 +1,145 @@
+use clap::CommandFactory;
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::Cli;
+
+/// Bump whenever the JSON shape below changes in a way a wrapper script
+/// might reasonably branch on, so scripts can feature-detect instead of
+/// guessing from pwnenv's own `--version`.
+pub const INTROSPECTION_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+struct Introspection {
+    introspection_version: u32,
+    config_schema_version: u32,
+    config_formats: Vec<&'static str>,
+    config_dir: String,
+    runtime_dir: String,
+    commands: Vec<CommandInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct CommandInfo {
+    name: String,
+    about: Option<String>,
+    flags: Vec<FlagInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    subcommands: Vec<CommandInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct FlagInfo {
+    long: Option<String>,
+    short: Option<char>,
+    takes_value: bool,
+}
+
+/// Emits a JSON document describing pwnenv's own CLI surface (subcommands
+/// and flags, walked off the live clap command tree rather than hand
+/// maintained), so wrapper scripts don't need to scrape `--help`.
+pub fn introspect() -> Result<()> {
+    let command = Cli::command();
+    let doc = Introspection {
+        introspection_version: INTROSPECTION_VERSION,
+        config_schema_version: crate::config::SCHEMA_VERSION,
+        config_formats: vec!["yaml"],
+        config_dir: std::env::current_dir()?.display().to_string(),
+        runtime_dir: crate::runtime::state_dir().display().to_string(),
+        commands: command_tree(&command),
+    };
+    println!("{}", serde_json::to_string_pretty(&doc).unwrap_or_default());
+    Ok(())
+}
+
+/// Walks `command`'s direct subcommands. Deliberately doesn't skip hidden
+/// ones (like `__introspect` itself) — "hidden" here just means "not shown
+/// in `--help`", and this document exists precisely for tooling that
+/// can't read `--help` in the first place.
+fn command_tree(command: &clap::Command) -> Vec<CommandInfo> {
+    let mut infos: Vec<CommandInfo> = command.get_subcommands().map(describe_command).collect();
+    infos.sort_by(|a, b| a.name.cmp(&b.name));
+    infos
+}
+
+fn describe_command(command: &clap::Command) -> CommandInfo {
+    let mut flags: Vec<FlagInfo> = command
+        .get_arguments()
+        .filter(|arg| arg.get_long().is_some() || arg.get_short().is_some())
+        .map(|arg| FlagInfo {
+            long: arg.get_long().map(str::to_string),
+            short: arg.get_short(),
+            takes_value: arg.get_num_args().is_some_and(|n| n.max_values() > 0),
+        })
+        .collect();
+    flags.sort_by(|a, b| a.long.cmp(&b.long));
+
+    CommandInfo {
+        name: command.get_name().to_string(),
+        about: command.get_about().map(|s| s.to_string()),
+        flags,
+        subcommands: command_tree(command),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A snapshot of pwnenv's top-level subcommand names. This is meant to
+    /// break loudly in review whenever a subcommand is added, renamed, or
+    /// removed — update the expected list deliberately, not by reflex.
+    #[test]
+    fn top_level_command_names_match_snapshot() {
+        let command = Cli::command();
+        let names: Vec<String> = command_tree(&command).into_iter().map(|c| c.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "__introspect",
+                "__probe",
+                "adopt",
+                "analyze",
+                "build",
+                "compose",
+                "config",
+                "cp-libs",
+                "deploy-xinetd",
+                "diff-env",
+                "docker",
+                "doctor",
+                "enter",
+                "exec",
+                "glibc",
+                "history",
+                "hook",
+                "images",
+                "init",
+                "kill",
+                "list-profiles",
+                "list-tools",
+                "local-flag",
+                "manifest",
+                "migrate-runtime",
+                "open-port",
+                "ps",
+                "pull-base",
+                "recordings",
+                "render",
+                "snapshot",
+                "status",
+                "template",
+                "tools",
+                "top",
+                "tui",
+                "up",
+                "verify",
+            ]
+        );
+    }
+
+    #[test]
+    fn introspection_version_is_stable() {
+        assert_eq!(INTROSPECTION_VERSION, 1);
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::activity;
+use crate::error::{PwnenvError, Result};
+use crate::runtime::RuntimeDir;
+use crate::sessions;
+
+use super::list_profiles;
+
+/// How many environments [`kill_all`] tears down concurrently. Each one
+/// is just a couple of `docker compose` subprocess calls, so there's
+/// nothing to gain from going wider than this.
+const MAX_CONCURRENT_KILLS: usize = 4;
+
+/// Stops and removes every environment under
+/// [`crate::runtime::state_dir`] (see [`list_profiles::list_environments`]),
+/// `MAX_CONCURRENT_KILLS` at a time. Asks for confirmation first (unless
+/// `assume_yes`), continues past individual failures instead of stopping
+/// at the first one, and prints a final success/failure summary.
+///
+/// Returns `Err` if any environment failed to kill, so `main` exits
+/// non-zero, but only after every environment has been attempted.
+pub fn kill_all(graceful: bool, timeout: u32, assume_yes: bool, force: bool) -> Result<()> {
+    let env_names = list_profiles::list_environments()?;
+    if env_names.is_empty() {
+        println!("no pwnenv environments found.");
+        return Ok(());
+    }
+
+    println!("about to kill {} environment(s): {}", env_names.len(), env_names.join(", "));
+    if !crate::prompt::confirm("kill all of them?", assume_yes) {
+        return Err(PwnenvError::Docker("aborted: --all kill not confirmed".to_string()));
+    }
+
+    let results: Mutex<Vec<(String, Result<()>)>> = Mutex::new(Vec::new());
+    for chunk in env_names.chunks(MAX_CONCURRENT_KILLS) {
+        std::thread::scope(|scope| {
+            for env_name in chunk {
+                scope.spawn(|| {
+                    let outcome = kill(env_name, graceful, timeout, force);
+                    results.lock().unwrap().push((env_name.clone(), outcome));
+                });
+            }
+        });
+    }
+
+    let results = results.into_inner().unwrap();
+    let failed: Vec<(String, PwnenvError)> = results
+        .into_iter()
+        .filter_map(|(env_name, outcome)| outcome.err().map(|e| (env_name, e)))
+        .collect();
+
+    if failed.is_empty() {
+        println!("all {} environment(s) killed.", env_names.len());
+        return Ok(());
+    }
+
+    println!("{} of {} environment(s) failed to kill:", failed.len(), env_names.len());
+    for (env_name, err) in &failed {
+        println!("  {env_name}: {err}");
+    }
+    Err(PwnenvError::Docker(format!("{} environment(s) failed to kill", failed.len())))
+}
+
+/// Stops and removes `env_name`'s container. By default this is an
+/// immediate `docker compose kill` (SIGKILL straight away), unchanged
+/// from before this flag existed. `graceful` runs `docker compose stop
+/// -t <timeout>` instead (SIGTERM, falling back to SIGKILL only once
+/// `timeout` seconds pass), so a well-behaved process gets a chance to
+/// flush state before it dies.
+///
+/// Refuses outright (no confirmation prompt, since this isn't a single
+/// "are you sure" but a "someone else is using this right now") if
+/// [`sessions::list_active`] shows any `enter` session still attached and
+/// `force` isn't set — a teammate sharing the same container shouldn't
+/// have their shell yanked out from under them by a `kill` they didn't
+/// run. `force` skips the check entirely.
+pub fn kill(env_name: &str, graceful: bool, timeout: u32, force: bool) -> Result<()> {
+    let runtime = RuntimeDir::new(env_name);
+
+    if !force {
+        let active = sessions::list_active(&runtime);
+        if !active.is_empty() {
+            return Err(PwnenvError::Docker(format!(
+                "{env_name}: {} other session(s) are attached (pwnenv enter); pass --force to kill anyway",
+                active.len()
+            )));
+        }
+    }
+
+    let compose_file = runtime.root().join("docker-compose.yml");
+
+    if graceful {
+        run_compose(&compose_file, &["stop", "-t", &timeout.to_string()])?;
+    } else {
+        run_compose(&compose_file, &["kill"])?;
+    }
+    run_compose(&compose_file, &["rm", "-f"])?;
+
+    activity::log_event(&runtime, "kill", &[format!("graceful={graceful}")], Some(0));
+    println!("{env_name}: killed.");
+    Ok(())
+}
+
+fn run_compose(compose_file: &Path, args: &[&str]) -> Result<()> {
+    let mut full_args = vec!["-f".to_string(), compose_file.display().to_string()];
+    full_args.extend(args.iter().map(|a| a.to_string()));
+    let status = crate::compose::resolve(None)
+        .command(&full_args)
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker compose {} exited with {status}",
+            args.join(" ")
+        )));
+    }
+    Ok(())
+}
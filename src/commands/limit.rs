@@ -0,0 +1,165 @@
+use crate::activity;
+use crate::error::{PwnenvError, Result};
+use crate::runtime::RuntimeDir;
+
+/// One currently-running environment, as far as the concurrency limit
+/// cares: its name and when it was last entered. `last_entered` is `0`
+/// for an environment that's never been entered, so it's evicted first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningEnvironment {
+    pub env_name: String,
+    pub last_entered: u64,
+}
+
+/// The timestamp of `env_name`'s last `enter` (see
+/// [`crate::commands::enter`]'s `enter:start` activity event), or `0` if
+/// it's never been entered.
+pub fn last_entered(env_name: &str) -> u64 {
+    let runtime = RuntimeDir::new(env_name);
+    activity::read_events(&runtime, None)
+        .into_iter()
+        .filter(|event| event.action == "enter:start")
+        .map(|event| event.timestamp)
+        .max()
+        .unwrap_or(0)
+}
+
+/// The running environment to stop to free a slot: the one least
+/// recently entered. `None` if `running` is empty.
+pub fn least_recently_entered(running: &[RunningEnvironment]) -> Option<&RunningEnvironment> {
+    running.iter().min_by_key(|e| e.last_entered)
+}
+
+/// Enforces `max_running_environments` before bringing `env_name` up.
+///
+/// `other_running` is every *other* environment docker currently reports
+/// running (see [`crate::commands::ps::collect_rows`], filtered to
+/// `state == "running"` and `env_name` excluded by the caller) — passed
+/// in rather than queried here so this stays testable without mocking
+/// `docker ps`. `force` (`up --force`) bypasses the check entirely.
+///
+/// If bringing `env_name` up would exceed `max`, prints the currently
+/// running environments and asks to stop the least recently entered one
+/// (skippable with the global `--yes` flag); declining aborts instead of
+/// starting `env_name`.
+pub fn enforce_limit(
+    env_name: &str,
+    max: Option<u32>,
+    other_running: &[String],
+    force: bool,
+    assume_yes: bool,
+) -> Result<()> {
+    let Some(max) = max else {
+        return Ok(());
+    };
+    if force {
+        return Ok(());
+    }
+    if (other_running.len() as u32) < max {
+        return Ok(());
+    }
+
+    let running: Vec<RunningEnvironment> = other_running
+        .iter()
+        .map(|name| RunningEnvironment {
+            env_name: name.clone(),
+            last_entered: last_entered(name),
+        })
+        .collect();
+    let Some(victim) = least_recently_entered(&running) else {
+        return Ok(());
+    };
+
+    println!(
+        "max_running_environments ({max}) reached; currently running: {}",
+        other_running.join(", ")
+    );
+    if !crate::prompt::confirm(
+        &format!(
+            "stop '{}' (least recently entered) to make room for '{env_name}'?",
+            victim.env_name
+        ),
+        assume_yes,
+    ) {
+        return Err(PwnenvError::Docker(format!(
+            "max_running_environments ({max}) reached; aborting instead of stopping '{}' \
+             (pass --force to bypass the limit)",
+            victim.env_name
+        )));
+    }
+
+    // The user already confirmed evicting `victim` above; a lingering
+    // `enter` session on it shouldn't silently defeat the limit they just
+    // agreed to enforce.
+    crate::commands::kill::kill(&victim.env_name, true, 10, true)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_entered_reflects_the_most_recent_enter_start_event() {
+        let env_name = format!("limit-test-{}", std::process::id());
+        let runtime = RuntimeDir::new(&env_name);
+        runtime.ensure_exists().unwrap();
+
+        activity::log_event(&runtime, "build", &[], Some(0));
+        activity::log_event(&runtime, "enter:start", &[], None);
+        activity::log_event(&runtime, "enter:end", &[], Some(0));
+
+        assert!(last_entered(&env_name) > 0);
+
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+
+    #[test]
+    fn last_entered_is_zero_for_an_environment_never_entered() {
+        let env_name = format!("limit-test-never-entered-{}", std::process::id());
+        assert_eq!(last_entered(&env_name), 0);
+    }
+
+    fn env(name: &str, last_entered: u64) -> RunningEnvironment {
+        RunningEnvironment { env_name: name.to_string(), last_entered }
+    }
+
+    #[test]
+    fn least_recently_entered_picks_the_oldest_timestamp() {
+        let running = vec![env("a", 200), env("b", 50), env("c", 100)];
+        assert_eq!(least_recently_entered(&running).unwrap().env_name, "b");
+    }
+
+    #[test]
+    fn never_entered_beats_everything_else_for_eviction() {
+        let running = vec![env("a", 200), env("b", 0), env("c", 100)];
+        assert_eq!(least_recently_entered(&running).unwrap().env_name, "b");
+    }
+
+    #[test]
+    fn empty_running_list_has_no_victim() {
+        assert!(least_recently_entered(&[]).is_none());
+    }
+
+    #[test]
+    fn no_limit_set_never_blocks() {
+        enforce_limit("chall", None, &["a".to_string(), "b".to_string()], false, false).unwrap();
+    }
+
+    #[test]
+    fn under_the_limit_never_blocks() {
+        enforce_limit("chall", Some(3), &["a".to_string()], false, false).unwrap();
+    }
+
+    #[test]
+    fn force_bypasses_the_limit_even_when_full() {
+        enforce_limit("chall", Some(1), &["a".to_string()], true, false).unwrap();
+    }
+
+    #[test]
+    fn at_the_limit_without_assume_yes_aborts() {
+        let err = enforce_limit("chall", Some(1), &["a".to_string()], false, false).unwrap_err();
+        assert!(err.to_string().contains("max_running_environments"));
+    }
+
+}
@@ -0,0 +1,119 @@
+//! `list-profiles`'s "profile" means an environment (a `pwnenv.yaml` +
+//! its runtime dir), not the unrelated `config.profiles` map of
+//! `enter --as` commands.
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+use crate::runtime;
+
+use super::ps;
+
+/// One environment pwnenv has ever `init`'ed, joined against docker for
+/// running status and (when running) its `pwnenv.yaml`'s forwarded port.
+#[derive(Debug, Serialize)]
+pub struct ProfileRow {
+    pub env_name: String,
+    pub running: bool,
+    pub state: String,
+    pub forwarded_port: Option<u16>,
+}
+
+/// Every environment with a runtime dir under
+/// [`crate::runtime::state_dir`], whether or not it's ever been `up`.
+/// Running status and uptime reuse [`ps::collect_rows`]'s docker-truth
+/// view; the forwarded port comes from re-reading `pwnenv.yaml` at the
+/// running container's `dev.pwnenv.host_dir`, since pwnenv's own runtime
+/// dir doesn't keep a copy of the config.
+pub fn collect_rows() -> Result<Vec<ProfileRow>> {
+    let env_names = list_environments()?;
+    let ps_rows = ps::collect_rows()?;
+
+    let mut rows: Vec<ProfileRow> = env_names
+        .into_iter()
+        .map(|env_name| {
+            let ps_row = ps_rows.iter().find(|row| row.env_name == env_name);
+            let running = ps_row.map(|row| row.state == "running").unwrap_or(false);
+            let state = ps_row.map(|row| row.state.clone()).unwrap_or_else(|| "not up".to_string());
+            let forwarded_port = ps_row.and_then(|row| forwarded_port_for(&row.host_dir));
+            ProfileRow { env_name, running, state, forwarded_port }
+        })
+        .collect();
+    rows.sort_by(|a, b| a.env_name.cmp(&b.env_name));
+    Ok(rows)
+}
+
+pub fn list_profiles(json: bool, no_color: bool) -> Result<()> {
+    let rows = collect_rows()?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&rows)
+                .map_err(|e| PwnenvError::Docker(format!("failed to serialize list-profiles output: {e}")))?
+        );
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("no pwnenv environments found.");
+        return Ok(());
+    }
+
+    let style = crate::output::Style::resolve(no_color);
+    let table_rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            vec![
+                row.env_name.clone(),
+                row.state.clone(),
+                row.forwarded_port.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    println!("{}", style.table(&["env", "state", "port"], &table_rows));
+    Ok(())
+}
+
+/// Every subdirectory of `state_dir()` (one per `init`'ed environment),
+/// skipping dotfiles like [`crate::version::notify`]'s daily-notice marker.
+/// `pub(crate)` so [`crate::commands::kill`]'s `--all` can enumerate the
+/// same set without duplicating the directory walk.
+pub(crate) fn list_environments() -> Result<Vec<String>> {
+    let Ok(entries) = std::fs::read_dir(runtime::state_dir()) else {
+        return Ok(Vec::new());
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+fn forwarded_port_for(host_dir: &str) -> Option<u16> {
+    if host_dir.is_empty() {
+        return None;
+    }
+    Config::load(&std::path::Path::new(host_dir).join("pwnenv.yaml"))
+        .ok()?
+        .forwarded_port
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_host_dir_has_no_port() {
+        assert_eq!(forwarded_port_for(""), None);
+    }
+
+    #[test]
+    fn unreadable_host_dir_has_no_port() {
+        assert_eq!(forwarded_port_for("/nonexistent/pwnenv-list-profiles-test"), None);
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+
+/// Output shape for `list-tools --format json`: a stable subset of
+/// [`crate::config::ToolConfig`]'s fields, sorted by name so two runs
+/// over the same config produce byte-identical output (useful for
+/// diffing environments across machines, or in CI).
+#[derive(Serialize)]
+struct ToolEntry<'a> {
+    name: &'a str,
+    build_only: bool,
+    artifacts: &'a [String],
+}
+
+pub fn list_tools(config: &Config, json: bool) -> Result<()> {
+    if json {
+        println!("{}", render_json(config)?);
+    } else {
+        for name in sorted_names(config) {
+            println!("{name}");
+        }
+    }
+    Ok(())
+}
+
+fn sorted_names(config: &Config) -> Vec<&str> {
+    let mut names: Vec<&str> = config.tools.iter().map(|t| t.name.as_str()).collect();
+    names.sort();
+    names
+}
+
+fn render_json(config: &Config) -> Result<String> {
+    let mut tools: Vec<&crate::config::ToolConfig> = config.tools.iter().collect();
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let entries: Vec<ToolEntry> = tools
+        .iter()
+        .map(|t| ToolEntry {
+            name: &t.name,
+            build_only: t.build_only,
+            artifacts: &t.artifacts,
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&entries)
+        .map_err(|e| PwnenvError::Docker(format!("failed to serialize tool list: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ToolConfig;
+
+    fn tool(name: &str) -> ToolConfig {
+        ToolConfig {
+            name: name.to_string(),
+            script: Vec::new(),
+            build_only: false,
+            append: false,
+            artifacts: Vec::new(),
+            verify: Vec::new(),
+            secrets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn json_output_is_byte_identical_regardless_of_tool_declaration_order() {
+        let config_a = Config {
+            tools: vec![tool("zzz"), tool("aaa"), tool("mmm")],
+            ..Config::default()
+        };
+        let config_b = Config {
+            tools: vec![tool("mmm"), tool("aaa"), tool("zzz")],
+            ..Config::default()
+        };
+
+        assert_eq!(render_json(&config_a).unwrap(), render_json(&config_b).unwrap());
+    }
+
+    #[test]
+    fn text_output_is_sorted_by_name() {
+        let config = Config {
+            tools: vec![tool("zzz"), tool("aaa")],
+            ..Config::default()
+        };
+        assert_eq!(sorted_names(&config), vec!["aaa", "zzz"]);
+    }
+}
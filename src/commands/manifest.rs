@@ -0,0 +1,47 @@
+use std::path::Path;
+
+use crate::error::{PwnenvError, Result};
+use crate::manifest;
+use crate::runtime::{container_name, RuntimeDir};
+
+/// Collects `env_name`'s package manifest from its running container
+/// (see [`crate::manifest::collect`]) and writes it to the runtime dir,
+/// printing it as JSON when `json`, otherwise a one-line-per-package
+/// summary.
+pub fn manifest(env_name: &str, json: bool) -> Result<()> {
+    let runtime = RuntimeDir::new(env_name);
+    let container = container_name(env_name);
+    let manifest = manifest::collect(&container);
+
+    if manifest.packages.is_empty() {
+        return Err(PwnenvError::Docker(format!(
+            "no packages found in '{env_name}'; is it up and does it have dpkg/pip/cargo/gem installed?"
+        )));
+    }
+
+    manifest::save(&runtime, &manifest)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&manifest).unwrap_or_default());
+    } else {
+        for pkg in &manifest.packages {
+            println!("{}/{} {}", pkg.source, pkg.name, pkg.version);
+        }
+        println!(
+            "{} package(s) written to {}",
+            manifest.packages.len(),
+            manifest::manifest_path(&runtime).display()
+        );
+    }
+    Ok(())
+}
+
+/// Compares two previously-saved manifests (e.g. `manifest.json` from
+/// before and after a rebuild) and prints what was added, removed, or
+/// upgraded.
+pub fn diff(a: &Path, b: &Path) -> Result<()> {
+    let before = manifest::load(a)?;
+    let after = manifest::load(b)?;
+    manifest::print_diff(&manifest::diff(&before, &after));
+    Ok(())
+}
@@ -0,0 +1,36 @@
+pub mod adopt;
+pub mod analyze;
+pub mod build;
+pub mod config;
+pub mod cp_libs;
+pub mod deploy;
+pub mod diff_env;
+pub mod doctor;
+pub mod enter;
+pub mod exec;
+pub mod flag;
+pub mod glibc;
+pub mod history;
+pub mod hook;
+pub mod images;
+pub mod init;
+pub mod introspect;
+pub mod kill;
+pub mod limit;
+pub mod list_profiles;
+pub mod list_tools;
+pub mod manifest;
+pub mod open_port;
+pub mod passthrough;
+pub mod probe;
+pub mod ps;
+pub mod recordings;
+pub mod render;
+pub mod snapshot;
+pub mod stats;
+pub mod status;
+pub mod template;
+pub mod tools_sync;
+pub mod tui;
+pub mod up;
+pub mod verify;
@@ -0,0 +1,62 @@
+use std::path::Path;
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+use crate::runtime::RuntimeDir;
+
+/// Parses `spec` (`host:container`) and adds it to `env_name`'s extra
+/// ports (see [`RuntimeDir::extra_ports`]), then re-runs [`super::up::up`]
+/// so compose recreates the service with the new mapping — docker has no
+/// way to add a port to a container already running, only to one being
+/// (re)created. A no-op, without touching anything, if the mapping is
+/// already open.
+pub fn open_port(
+    env_name: &str,
+    spec: &str,
+    config: &Config,
+    runtime: &RuntimeDir,
+    host_dir: &Path,
+    trace_dir: Option<&Path>,
+) -> Result<()> {
+    let port = parse_port_pair(spec)?;
+
+    let mut ports = runtime.extra_ports();
+    if ports.contains(&port) {
+        println!("{env_name}: {}:{} is already open.", port.0, port.1);
+        return Ok(());
+    }
+    ports.push(port);
+    runtime.set_extra_ports(&ports)?;
+
+    println!("{env_name}: opening {}:{}; this recreates the container.", port.0, port.1);
+    super::up::up(env_name, None, config, runtime, host_dir, trace_dir)
+}
+
+fn parse_port_pair(spec: &str) -> Result<(u16, u16)> {
+    let (host, container) = spec
+        .split_once(':')
+        .ok_or_else(|| PwnenvError::InvalidPortMapping(spec.to_string()))?;
+    let host: u16 = host.parse().map_err(|_| PwnenvError::InvalidPortMapping(spec.to_string()))?;
+    let container: u16 = container.parse().map_err(|_| PwnenvError::InvalidPortMapping(spec.to_string()))?;
+    Ok((host, container))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_valid_pair() {
+        assert_eq!(parse_port_pair("8080:80").unwrap(), (8080, 80));
+    }
+
+    #[test]
+    fn rejects_a_non_numeric_port() {
+        assert!(parse_port_pair("abc:80").is_err());
+    }
+
+    #[test]
+    fn rejects_a_spec_without_a_colon() {
+        assert!(parse_port_pair("8080").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+use crate::error::{PwnenvError, Result};
+use crate::runtime::{container_name, RuntimeDir};
+
+/// Runs `docker compose <args>` with the environment's project name,
+/// compose file, project directory, and env file (if one exists)
+/// already set — an escape hatch for compose flags pwnenv doesn't wrap
+/// yet, without losing the environment's context.
+pub fn compose(env_name: &str, host_dir: &Path, args: &[String], print: bool) -> Result<()> {
+    crate::host_path::validate(host_dir)?;
+    let runtime = RuntimeDir::new(env_name);
+    let compose_command = crate::config::Config::load(&host_dir.join("pwnenv.yaml"))
+        .map(|config| crate::compose::resolve(config.compose_command.as_deref()))
+        .unwrap_or_else(|_| crate::compose::resolve(None));
+
+    let mut full_args = vec![
+        "-p".to_string(),
+        env_name.to_string(),
+        "-f".to_string(),
+        runtime.root().join("docker-compose.yml").display().to_string(),
+        "--project-directory".to_string(),
+        host_dir.display().to_string(),
+    ];
+    let env_file = runtime.root().join(".env");
+    if env_file.exists() {
+        full_args.push("--env-file".to_string());
+        full_args.push(env_file.display().to_string());
+    }
+    full_args.extend(args.iter().cloned());
+    full_args.splice(0..0, compose_command.prefix.iter().cloned());
+
+    run_or_print(&compose_command.program, &full_args, print)
+}
+
+/// Runs `docker <args>`, substituting any literal `{container}` token in
+/// `args` with the environment's resolved container ID — an escape
+/// hatch for docker flags pwnenv doesn't wrap yet (`docker logs`,
+/// `docker cp`, a one-off `docker exec`, ...).
+pub fn docker(env_name: &str, args: &[String], print: bool) -> Result<()> {
+    let container_id = resolve_container_id(env_name)?;
+    let full_args: Vec<String> = args.iter().map(|arg| arg.replace("{container}", &container_id)).collect();
+
+    run_or_print("docker", &full_args, print)
+}
+
+fn resolve_container_id(env_name: &str) -> Result<String> {
+    let container = container_name(env_name);
+    let output = Command::new("docker")
+        .args(["inspect", "-f", "{{.Id}}", &container])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !output.status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "no container named '{container}' was found"
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `--print` shows the full command, quoted the way a shell would need
+/// it, without running it, so it can be copied into a terminal or a bug
+/// report as-is. Otherwise runs it and exits with its exit code
+/// unchanged — these are raw escape hatches, so the caller expects
+/// `docker`'s own exit code, not pwnenv's.
+fn run_or_print(program: &str, args: &[String], print: bool) -> Result<()> {
+    if print {
+        println!("{program} {}", args.iter().map(|a| quote(a)).collect::<Vec<_>>().join(" "));
+        return Ok(());
+    }
+
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    exit_with(status)
+}
+
+fn quote(arg: &str) -> String {
+    if arg.is_empty() || arg.chars().any(char::is_whitespace) {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    } else {
+        arg.to_string()
+    }
+}
+
+fn exit_with(status: ExitStatus) -> Result<()> {
+    std::process::exit(status.code().unwrap_or(1));
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use crate::error::Result;
+
+/// Hidden, fast lookup behind `pwnenv hook`'s shell snippets (see
+/// [`crate::commands::hook`]): does `dir` have a pwnenv environment
+/// registered to it? Never touches docker or loads `pwnenv.yaml` —
+/// just reads each environment's tiny `host-dir` marker (see
+/// [`crate::runtime::RuntimeDir::host_dir`]) under `state_dir()`, so a
+/// shell's directory-change hook can call this on every `cd` without a
+/// noticeable pause. Prints the matching environment's name and returns
+/// `true` on a hit; prints nothing and returns `false` otherwise — the
+/// hook shells out to this and keys off exit status/stdout, not error
+/// text, so this never returns `Err` for "not found".
+pub fn probe(dir: &Path) -> Result<bool> {
+    let Ok(entries) = std::fs::read_dir(crate::runtime::state_dir()) else {
+        return Ok(false);
+    };
+
+    for entry in entries.flatten() {
+        let root = entry.path();
+        let Ok(registered) = std::fs::read_to_string(root.join("host-dir")) else {
+            continue;
+        };
+        if Path::new(registered.trim()) != dir {
+            continue;
+        }
+        if let Some(env_name) = root.file_name().and_then(|n| n.to_str()) {
+            println!("{env_name}");
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_match_when_state_dir_is_empty() {
+        let state_dir = std::env::temp_dir().join("pwnenv-probe-test-empty");
+        std::fs::create_dir_all(&state_dir).unwrap();
+        std::env::set_var("PWNENV_CONFIG_DIR", &state_dir);
+        assert!(!probe(Path::new("/some/chall")).unwrap());
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::fs::remove_dir_all(&state_dir).ok();
+    }
+
+    #[test]
+    fn matches_a_registered_host_dir() {
+        let state_dir = std::env::temp_dir().join("pwnenv-probe-test-match");
+        std::fs::create_dir_all(state_dir.join("chall")).unwrap();
+        std::fs::write(state_dir.join("chall").join("host-dir"), "/home/user/chall").unwrap();
+        std::env::set_var("PWNENV_CONFIG_DIR", &state_dir);
+        assert!(probe(Path::new("/home/user/chall")).unwrap());
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::fs::remove_dir_all(&state_dir).ok();
+    }
+
+    #[test]
+    fn no_match_for_an_unregistered_directory() {
+        let state_dir = std::env::temp_dir().join("pwnenv-probe-test-miss");
+        std::fs::create_dir_all(state_dir.join("chall")).unwrap();
+        std::fs::write(state_dir.join("chall").join("host-dir"), "/home/user/chall").unwrap();
+        std::env::set_var("PWNENV_CONFIG_DIR", &state_dir);
+        assert!(!probe(Path::new("/home/user/somewhere-else")).unwrap());
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::fs::remove_dir_all(&state_dir).ok();
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::error::{PwnenvError, Result};
+use crate::labels;
+use crate::runtime::RuntimeDir;
+use crate::sessions;
+
+/// One pwnenv-labeled container, joined with its live resource usage.
+/// This is the docker-truth view: unlike a state-file-driven listing, a
+/// row here exists because docker says the container exists, whether or
+/// not pwnenv's own runtime dir still knows about it.
+#[derive(Debug, Serialize)]
+pub struct PsRow {
+    pub env_name: String,
+    pub host_dir: String,
+    pub container_name: String,
+    pub state: String,
+    pub uptime: String,
+    pub cpu: Option<String>,
+    pub memory: Option<String>,
+    /// True when the container carries pwnenv's labels but its runtime
+    /// dir (`~/.local/share/pwnenv/<env_name>`) no longer exists — e.g.
+    /// it survived a `rm -rf` of the runtime dir. Feeds `adopt`/`clean`.
+    pub orphaned: bool,
+    /// How many live `enter` sessions are attached (see [`crate::sessions`]).
+    /// `0` for an orphaned row, since there's no runtime dir left to read
+    /// `sessions.json` from.
+    pub sessions: usize,
+}
+
+/// Builds the rows [`ps`] prints, and that [`crate::commands::tui`]'s list
+/// pane refreshes on a timer: every pwnenv-labeled container docker knows
+/// about, joined with its live resource usage.
+pub fn collect_rows() -> Result<Vec<PsRow>> {
+    let containers = list_labeled_containers()?;
+    let stats = container_stats();
+
+    let mut rows: Vec<PsRow> = containers
+        .into_iter()
+        .map(|entry| {
+            let env_name = entry.labels.get(labels::ENV_NAME).cloned().unwrap_or_default();
+            let host_dir = entry.labels.get(labels::HOST_DIR).cloned().unwrap_or_default();
+            let usage = stats.get(&entry.name);
+            let runtime = RuntimeDir::new(&env_name);
+            let orphaned = !runtime.root().exists();
+            PsRow {
+                sessions: if orphaned { 0 } else { sessions::list_active(&runtime).len() },
+                orphaned,
+                env_name,
+                host_dir,
+                container_name: entry.name,
+                state: entry.state,
+                uptime: entry.running_for,
+                cpu: usage.map(|u| u.cpu.clone()),
+                memory: usage.map(|u| u.memory.clone()),
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| a.env_name.cmp(&b.env_name));
+    Ok(rows)
+}
+
+pub fn ps(json: bool, no_color: bool) -> Result<()> {
+    let rows = collect_rows()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows).map_err(|e| {
+            PwnenvError::Docker(format!("failed to serialize ps output: {e}"))
+        })?);
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("no pwnenv containers found.");
+        return Ok(());
+    }
+
+    let style = crate::output::Style::resolve(no_color);
+    let table_rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let host_dir = if row.orphaned { format!("{} (orphaned)", row.host_dir) } else { row.host_dir.clone() };
+            vec![
+                row.env_name.clone(),
+                row.state.clone(),
+                row.sessions.to_string(),
+                row.cpu.clone().unwrap_or_else(|| "-".to_string()),
+                row.memory.clone().unwrap_or_else(|| "-".to_string()),
+                host_dir,
+            ]
+        })
+        .collect();
+    println!("{}", style.table(&["env", "state", "sessions", "cpu", "memory", "host_dir"], &table_rows));
+    Ok(())
+}
+
+struct ContainerEntry {
+    name: String,
+    state: String,
+    running_for: String,
+    labels: HashMap<String, String>,
+}
+
+/// Runs `docker ps -a --filter label=dev.pwnenv.env_name --format
+/// "{{json .}}"`, which emits one JSON object per line (NDJSON) on every
+/// docker version we've seen. Lines that don't parse (an older docker
+/// ignoring `--format json` and falling back to its table) are skipped
+/// with a warning instead of failing the whole command.
+fn list_labeled_containers() -> Result<Vec<ContainerEntry>> {
+    let output = Command::new("docker")
+        .args(["ps", "-a", "--filter", "label=dev.pwnenv.env_name", "--format", "{{json .}}"])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+    if !output.status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker ps exited with {}",
+            output.status
+        )));
+    }
+
+    let mut entries = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(raw) = serde_json::from_str::<RawPsEntry>(line) else {
+            eprintln!("pwnenv: skipping unparseable `docker ps` line: {line}");
+            continue;
+        };
+        entries.push(ContainerEntry {
+            name: raw.names,
+            state: raw.state,
+            running_for: raw.running_for,
+            labels: parse_labels(&raw.labels),
+        });
+    }
+    Ok(entries)
+}
+
+struct ContainerUsage {
+    cpu: String,
+    memory: String,
+}
+
+/// Runs `docker stats --no-stream --format "{{json .}}"` for live
+/// CPU/memory. Best-effort: if docker stats fails or produces nothing
+/// usable, [`ps`] just prints `-` for those columns instead of failing.
+fn container_stats() -> HashMap<String, ContainerUsage> {
+    let output = Command::new("docker")
+        .args(["stats", "--no-stream", "--format", "{{json .}}"])
+        .output();
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+    if !output.status.success() {
+        return HashMap::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<RawStatsEntry>(line).ok())
+        .map(|raw| (raw.name, ContainerUsage { cpu: raw.cpu_perc, memory: raw.mem_usage }))
+        .collect()
+}
+
+fn parse_labels(raw: &str) -> HashMap<String, String> {
+    raw.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RawPsEntry {
+    #[serde(rename = "Names")]
+    names: String,
+    #[serde(rename = "State")]
+    state: String,
+    #[serde(rename = "RunningFor")]
+    running_for: String,
+    #[serde(rename = "Labels")]
+    labels: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RawStatsEntry {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "CPUPerc")]
+    cpu_perc: String,
+    #[serde(rename = "MemUsage")]
+    mem_usage: String,
+}
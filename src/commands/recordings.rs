@@ -0,0 +1,67 @@
+use serde::Serialize;
+
+use crate::error::{PwnenvError, Result};
+use crate::recordings;
+
+/// Output shape for `recordings list --json`.
+#[derive(Serialize)]
+struct RecordingEntry<'a> {
+    id: &'a str,
+    env_name: &'a str,
+    timestamp: u64,
+    recorder: &'static str,
+}
+
+/// Prints every saved `enter --record` session, oldest first (same
+/// order [`crate::recordings::list`] already sorts them in).
+pub fn list(json: bool) -> Result<()> {
+    let recordings = recordings::list();
+
+    if json {
+        let entries: Vec<RecordingEntry> = recordings
+            .iter()
+            .map(|r| RecordingEntry {
+                id: &r.id,
+                env_name: &r.env_name,
+                timestamp: r.timestamp,
+                recorder: recorder_name(r.recorder),
+            })
+            .collect();
+        let out = serde_json::to_string_pretty(&entries)
+            .map_err(|e| PwnenvError::Docker(format!("failed to serialize recordings: {e}")))?;
+        println!("{out}");
+        return Ok(());
+    }
+
+    if recordings.is_empty() {
+        println!("no recordings yet. run `pwnenv enter --record` to make one.");
+        return Ok(());
+    }
+    for recording in &recordings {
+        println!(
+            "{:<28} {:<16} {}",
+            recording.id,
+            recording.env_name,
+            recorder_name(recording.recorder)
+        );
+    }
+    Ok(())
+}
+
+fn recorder_name(recorder: recordings::Recorder) -> &'static str {
+    match recorder {
+        recordings::Recorder::Asciinema => "asciinema",
+        recordings::Recorder::Script => "script",
+    }
+}
+
+/// Plays `id` back host-side (see [`crate::recordings::play`]).
+pub fn play(id: &str) -> Result<()> {
+    let recording =
+        recordings::find(id).ok_or_else(|| PwnenvError::Docker(format!("no recording named '{id}'")))?;
+    let status = recordings::play(&recording)?;
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!("the recording player exited with {status}")));
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use std::path::Path;
+
+use crate::config::Config;
+use crate::docker::lint::{lint_tools, Severity};
+use crate::docker::render_dockerfile;
+use crate::error::Result;
+
+/// Renders the Dockerfile for `config` to stdout, after running the same
+/// sanity checks `build` would run. Errors abort; warnings are printed to
+/// stderr but don't stop the render.
+pub fn render(config: &Config, build_context: &Path) -> Result<()> {
+    let findings = lint_tools(config, build_context);
+    for finding in &findings {
+        eprintln!("{finding}");
+    }
+    if findings.iter().any(|f| f.severity == Severity::Error) {
+        return Err(crate::error::PwnenvError::Docker(
+            "Dockerfile sanity checks failed; see errors above".to_string(),
+        ));
+    }
+
+    println!("{}", render_dockerfile(config, config.programs_dir.is_some(), None));
+    Ok(())
+}
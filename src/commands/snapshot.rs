@@ -0,0 +1,52 @@
+use std::process::Command;
+
+use crate::activity;
+use crate::error::{PwnenvError, Result};
+use crate::runtime::{container_name, RuntimeDir};
+
+/// Commits `env_name`'s running container to `tag`, so ad-hoc changes made
+/// inside a live container (extra tools installed by hand, a patched
+/// binary, whatever) survive past `down`/`rm` instead of being lost.
+/// Errors if the container isn't currently running.
+pub fn snapshot(env_name: &str, tag: &str) -> Result<()> {
+    let container = container_name(env_name);
+
+    if !is_running(&container)? {
+        return Err(PwnenvError::Docker(format!(
+            "container '{container}' is not running; nothing to snapshot"
+        )));
+    }
+
+    let output = Command::new("docker")
+        .args(["commit", &container, tag])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker commit for '{container}' exited with {}",
+            output.status
+        )));
+    }
+
+    let image_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let runtime = RuntimeDir::new(env_name);
+    activity::log_event(&runtime, "snapshot", &[tag.to_string()], Some(0));
+    println!("{tag}: {image_id}");
+    Ok(())
+}
+
+fn is_running(container: &str) -> Result<bool> {
+    let output = Command::new("docker")
+        .args(["inspect", "-f", "{{.State.Running}}", container])
+        .output()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "no container named '{container}' was found"
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim() == "true")
+}
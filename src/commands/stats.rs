@@ -0,0 +1,21 @@
+use std::process::Command;
+
+use crate::error::{PwnenvError, Result};
+use crate::runtime::container_name;
+
+/// Streams `docker stats` for an environment's container until the user
+/// interrupts it (Ctrl-C), giving a live view of CPU/memory/IO usage.
+pub fn top(env_name: &str) -> Result<()> {
+    let container = container_name(env_name);
+    let status = Command::new("docker")
+        .args(["stats", &container])
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker stats for '{container}' exited with {status}"
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crossterm::cursor::MoveTo;
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::ExecutableCommand;
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+use crate::labels;
+use crate::runtime::{container_name, RuntimeDir};
+use crate::sessions;
+use crate::verify;
+
+const WATCH_REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+const MAX_EVENT_LOG: usize = 10;
+
+/// Prints the status of an environment, including any tools that failed
+/// to install during the last `fail_fast = false` build. `verbose` also
+/// prints the running container's `dev.pwnenv.*` labels (see
+/// [`crate::labels`]), flagging a `config_hash` that no longer matches
+/// `config` — a sign the container was built from a `pwnenv.yaml` that's
+/// since changed.
+pub fn print_status(env_name: &str, config: &Config, verbose: bool) -> Result<()> {
+    let runtime = RuntimeDir::new(env_name);
+    println!("environment: {env_name}");
+
+    let failed_tools = list_failed_tools(&runtime)?;
+    if failed_tools.is_empty() {
+        println!("failed tools: none");
+    } else {
+        println!("failed tools: {}", failed_tools.join(", "));
+    }
+
+    print_git_drift();
+    print_verify_results(&runtime);
+
+    let sessions = sessions::list_active(&runtime);
+    println!("active sessions: {}", sessions.len());
+
+    let mounts = runtime.mounts();
+    if mounts.is_empty() {
+        println!("mounts: none");
+    } else {
+        println!("mounts:");
+        for mount in &mounts {
+            println!("  {}:{}", mount.host.display(), mount.container);
+        }
+    }
+
+    if verbose {
+        print_labels(env_name, config)?;
+    }
+
+    Ok(())
+}
+
+/// For users who keep `pwnenv.yaml` under version control, notes when it
+/// has uncommitted changes so they don't forget to commit before the next
+/// `up`/`build` picks up a config nobody else's checkout has yet. Prints
+/// nothing at all — not even a "not a git repo" line — when the current
+/// directory isn't a git repo, or git isn't installed; this is a
+/// convenience for config-in-git users, not something everyone needs to
+/// see.
+fn print_git_drift() {
+    let Some(dirty) = pwnenv_yaml_is_dirty() else {
+        return;
+    };
+    if dirty {
+        println!("config: pwnenv.yaml has uncommitted changes");
+    }
+}
+
+/// `Some(true)`/`Some(false)` if `pwnenv.yaml` is tracked in a git repo
+/// rooted at or above the current directory (the same cwd-relative
+/// resolution every other command uses to find `pwnenv.yaml` — see
+/// `Config::load(Path::new("pwnenv.yaml"))` in `main.rs`), `None` if
+/// there's no repo here or git isn't available.
+fn pwnenv_yaml_is_dirty() -> Option<bool> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "--", "pwnenv.yaml"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(!output.stdout.is_empty())
+}
+
+/// Shows the results saved by the last `up`/`verify` run, if any — see
+/// [`crate::verify`]. Says nothing at all if no tool defines `verify`
+/// commands, rather than printing an empty "verify: none" every time.
+fn print_verify_results(runtime: &RuntimeDir) {
+    let results = verify::load_results(runtime);
+    if results.is_empty() {
+        return;
+    }
+    let failures = results.iter().filter(|r| !r.passed).count();
+    println!("verify: {}/{} passed (run `pwnenv verify` to re-check)", results.len() - failures, results.len());
+}
+
+fn print_labels(env_name: &str, config: &Config) -> Result<()> {
+    let container = container_name(env_name);
+    let output = Command::new("docker")
+        .args(["inspect", "-f", "{{json .Config.Labels}}", &container])
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+
+    let Some(output) = output else {
+        println!("labels: container '{container}' not found; run `up` first");
+        return Ok(());
+    };
+
+    let recorded: HashMap<String, String> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    println!("labels:");
+    for key in [labels::VERSION, labels::ENV_NAME, labels::HOST_DIR, labels::CONFIG_HASH, labels::CREATED_AT] {
+        match recorded.get(key) {
+            Some(value) => println!("  {key}: {value}"),
+            None => println!("  {key}: <missing>"),
+        }
+    }
+
+    let current_hash = labels::config_hash(config);
+    if let Some(recorded_hash) = recorded.get(labels::CONFIG_HASH) {
+        if recorded_hash != &current_hash {
+            println!(
+                "  warning: config_hash label ({recorded_hash}) does not match the current \
+                 pwnenv.yaml ({current_hash}); rebuild to pick up the change."
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// A timestamped state transition, parsed out of `docker events`' JSON
+/// stream (see [`spawn_event_stream`]), for the scrolling log
+/// `watch_status` prints under the regular [`print_status`] output.
+struct Transition {
+    time: String,
+    action: String,
+}
+
+/// `status --watch`: redraws [`print_status`] every [`WATCH_REFRESH_INTERVAL`]
+/// and immediately after any `docker events` transition for this
+/// environment's container, until Ctrl-C or the container disappears
+/// from `docker ps -a` entirely (checked each redraw). Clears the
+/// terminal and redraws in place rather than opening a [`crate::commands::tui`]-style
+/// alternate screen, since this is meant to sit alongside a shell
+/// running the actual exploit, not take it over.
+pub fn watch_status(env_name: &str, config: &Config, verbose: bool) -> Result<()> {
+    let events = spawn_event_stream(env_name);
+    let mut log: Vec<Transition> = Vec::new();
+    let mut ever_seen = false;
+
+    loop {
+        while let Ok(transition) = events.try_recv() {
+            log.push(transition);
+            if log.len() > MAX_EVENT_LOG {
+                log.remove(0);
+            }
+        }
+
+        let container_exists = container_currently_known(env_name)?;
+        if container_exists {
+            ever_seen = true;
+        } else if ever_seen {
+            redraw(env_name, config, verbose, &log)?;
+            println!("\n{env_name}: container removed; exiting watch.");
+            return Ok(());
+        }
+
+        redraw(env_name, config, verbose, &log)?;
+        std::thread::sleep(WATCH_REFRESH_INTERVAL);
+    }
+}
+
+fn redraw(env_name: &str, config: &Config, verbose: bool, log: &[Transition]) -> Result<()> {
+    std::io::stdout()
+        .execute(Clear(ClearType::All))
+        .and_then(|stdout| stdout.execute(MoveTo(0, 0)))
+        .map_err(|e| PwnenvError::Docker(format!("failed to clear the terminal: {e}")))?;
+
+    print_status(env_name, config, verbose)?;
+
+    if !log.is_empty() {
+        println!("events:");
+        for transition in log {
+            println!("  [{}] {}", transition.time, transition.action);
+        }
+    }
+    println!("\n(watching; Ctrl-C to stop)");
+    Ok(())
+}
+
+fn container_currently_known(env_name: &str) -> Result<bool> {
+    Ok(super::ps::collect_rows()?.into_iter().any(|row| row.env_name == env_name))
+}
+
+#[derive(Debug, Deserialize)]
+struct RawDockerEvent {
+    status: Option<String>,
+    #[serde(rename = "Action")]
+    action: Option<String>,
+    time: Option<i64>,
+}
+
+/// Runs `docker events --filter label=dev.pwnenv.env_name=<env_name>
+/// --format "{{json .}}"` on a background thread and parses its NDJSON
+/// stream into [`Transition`]s. `docker events` itself reconnects to the
+/// daemon on its own, but the process can still exit (daemon restart,
+/// `docker` briefly unavailable); this respawns it after a short delay
+/// rather than leaving the watch silently stuck with a stale log.
+fn spawn_event_stream(env_name: &str) -> mpsc::Receiver<Transition> {
+    let (tx, rx) = mpsc::channel();
+    let env_name = env_name.to_string();
+    std::thread::spawn(move || loop {
+        if let Err(e) = stream_events_once(&env_name, &tx) {
+            eprintln!("pwnenv: docker events stream interrupted: {e}; reconnecting.");
+        }
+        if tx.send(Transition { time: "-".to_string(), action: String::new() }).is_err() {
+            return;
+        }
+        std::thread::sleep(Duration::from_secs(2));
+    });
+    rx
+}
+
+fn stream_events_once(env_name: &str, tx: &mpsc::Sender<Transition>) -> Result<()> {
+    let mut child = Command::new("docker")
+        .args([
+            "events",
+            "--filter",
+            &format!("label={}={env_name}", labels::ENV_NAME),
+            "--format",
+            "{{json .}}",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+
+    let stdout = child.stdout.take().ok_or_else(|| PwnenvError::Docker("docker events had no stdout".to_string()))?;
+    for line in BufReader::new(stdout).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(raw) = serde_json::from_str::<RawDockerEvent>(&line) else {
+            continue;
+        };
+        let action = raw.action.or(raw.status).unwrap_or_else(|| "event".to_string());
+        let time = raw.time.map(|t| t.to_string()).unwrap_or_else(|| "-".to_string());
+        if tx.send(Transition { time, action }).is_err() {
+            break;
+        }
+    }
+
+    let _ = child.wait();
+    Ok(())
+}
+
+fn list_failed_tools(runtime: &RuntimeDir) -> Result<Vec<String>> {
+    let dir = runtime.failed_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<String> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    Ok(names)
+}
@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use crate::commands::config::write_atomic;
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+
+/// A curated [`Config`] for a common CTF category, so `pwnenv template`
+/// gives a better starting point than [`Config::default`] alone. Looked
+/// up by [`lookup`] from the name passed to `pwnenv template`.
+fn lookup(kind: &str) -> Option<Config> {
+    let config = match kind {
+        "kernel" => Config {
+            include_tools: vec!["kernel".to_string()],
+            privileged: true,
+            ..Config::default()
+        },
+        "heap" => Config {
+            build_debug_glibc: true,
+            ..Config::default()
+        },
+        "rev" => Config {
+            include_tools: vec!["reversing".to_string()],
+            ..Config::default()
+        },
+        _ => return None,
+    };
+    Some(config)
+}
+
+/// The `kind`s [`lookup`] recognizes, for the error message when an
+/// unknown one is passed.
+const KNOWN_KINDS: &[&str] = &["kernel", "heap", "rev"];
+
+/// Writes the curated template for `kind` to `path`, after confirmation
+/// if `path` already exists (skippable with the global `--yes` flag) —
+/// same guard as [`crate::commands::config::reset`], since this also
+/// discards whatever's currently on disk.
+pub fn template(path: &Path, kind: &str, assume_yes: bool) -> Result<()> {
+    let Some(config) = lookup(kind) else {
+        return Err(PwnenvError::UnknownTemplate {
+            kind: kind.to_string(),
+            known: KNOWN_KINDS.join(", "),
+        });
+    };
+
+    if path.exists()
+        && !crate::prompt::confirm(
+            &format!("{} already exists and will be overwritten with the '{kind}' template. Continue?", path.display()),
+            assume_yes,
+        )
+    {
+        println!("aborted; {} left untouched", path.display());
+        return Ok(());
+    }
+
+    let contents = serde_yaml::to_string(&config).map_err(|source| PwnenvError::ConfigParse {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    write_atomic(path, &contents)?;
+    println!("{}: wrote the '{kind}' template", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unique_path(label: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("pwnenv-template-test-{label}-{}.yaml", std::process::id()))
+    }
+
+    #[test]
+    fn kernel_template_includes_qemu_bundle_and_is_privileged() {
+        let path = unique_path("kernel");
+
+        template(&path, "kernel", true).unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert!(config.include_tools.contains(&"kernel".to_string()));
+        assert!(config.privileged);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn unknown_kind_is_rejected_without_touching_the_file() {
+        let path = unique_path("unknown");
+
+        let err = template(&path, "web", true).unwrap_err();
+        assert!(matches!(err, PwnenvError::UnknownTemplate { .. }));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn declining_confirmation_leaves_an_existing_file_untouched() {
+        let path = unique_path("declined");
+        std::fs::write(&path, "base_image: custom:tag\n").unwrap();
+
+        template(&path, "rev", false).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "base_image: custom:tag\n");
+
+        std::fs::remove_file(&path).ok();
+    }
+}
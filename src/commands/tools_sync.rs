@@ -0,0 +1,36 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::remote_tools;
+
+/// Fetches every `remote_tools` URL in `config` into the cache `render`/
+/// `build` read from (see [`crate::remote_tools`]), and prints what
+/// changed. A URL that fails to fetch doesn't abort the others, but does
+/// make the process exit non-zero, so a CI step that syncs before a
+/// build notices a broken source instead of silently building with
+/// whatever was cached before.
+pub fn sync(config: &Config) -> Result<bool> {
+    if config.remote_tools.is_empty() {
+        println!("no remote_tools configured.");
+        return Ok(false);
+    }
+
+    let report = remote_tools::sync(&config.remote_tools)?;
+
+    for url in &report.fetched {
+        println!("fetched  {url}");
+    }
+    for url in &report.unchanged {
+        println!("unchanged {url}");
+    }
+    for url in &report.failed {
+        println!("FAILED   {url}");
+    }
+    println!(
+        "{} fetched, {} unchanged, {} failed",
+        report.fetched.len(),
+        report.unchanged.len(),
+        report.failed.len()
+    );
+
+    Ok(!report.failed.is_empty())
+}
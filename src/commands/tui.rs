@@ -0,0 +1,233 @@
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::ExecutableCommand;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Terminal;
+
+use crate::commands::ps::{collect_rows, PsRow};
+use crate::error::{PwnenvError, Result};
+use crate::runtime::RuntimeDir;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the background refresh thread hands back each tick: either a
+/// fresh row list, or a docker-side error (e.g. the daemon isn't
+/// running) to show in place of the list instead of crashing the TUI.
+enum Refresh {
+    Rows(Vec<PsRow>),
+    Error(String),
+}
+
+/// A read-only live dashboard: a list pane of every pwnenv environment
+/// docker knows about (state, CPU, memory), and a detail pane for
+/// whichever one is selected (host dir, mounts, orphan status).
+///
+/// This is the initial milestone described in the request that added
+/// it: browsing only. Actions (`enter`, rebuild, kill, a log tail) are
+/// deliberately left for later, once this read-only shell has proven
+/// out the refresh/render plumbing.
+pub fn tui() -> Result<()> {
+    let mut terminal = setup_terminal()?;
+    let result = run(&mut terminal);
+    teardown_terminal(&mut terminal)?;
+    result
+}
+
+fn run(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> Result<()> {
+    let rx = spawn_refresh_thread();
+
+    let mut rows: Vec<PsRow> = Vec::new();
+    let mut error: Option<String> = None;
+    let mut list_state = ListState::default();
+    let mut last_tick = Instant::now();
+
+    loop {
+        if let Ok(refresh) = rx.try_recv() {
+            match refresh {
+                Refresh::Rows(fresh) => {
+                    rows = fresh;
+                    error = None;
+                    clamp_selection(&mut list_state, rows.len());
+                }
+                Refresh::Error(message) => error = Some(message),
+            }
+            last_tick = Instant::now();
+        }
+
+        terminal
+            .draw(|frame| draw(frame, &rows, error.as_deref(), &mut list_state))
+            .map_err(|e| PwnenvError::Docker(format!("failed to draw tui: {e}")))?;
+
+        let timeout = REFRESH_INTERVAL
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or(Duration::from_millis(50));
+        if event::poll(timeout).map_err(|e| PwnenvError::Docker(e.to_string()))? {
+            match event::read().map_err(|e| PwnenvError::Docker(e.to_string()))? {
+                Event::Key(key) => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Down | KeyCode::Char('j') => select_next(&mut list_state, rows.len()),
+                    KeyCode::Up | KeyCode::Char('k') => select_prev(&mut list_state, rows.len()),
+                    _ => {}
+                },
+                Event::Resize(_, _) => {
+                    // ratatui re-reads the terminal size on the next
+                    // `draw`, so there's nothing to do here beyond
+                    // letting the loop come back around.
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut ratatui::Frame, rows: &[PsRow], error: Option<&str>, list_state: &mut ListState) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(frame.area());
+
+    if let Some(message) = error {
+        let block = Paragraph::new(message.to_string())
+            .block(Block::default().borders(Borders::ALL).title("pwnenv (docker error)"));
+        frame.render_widget(block, columns[0]);
+        frame.render_widget(Block::default().borders(Borders::ALL).title("detail"), columns[1]);
+        return;
+    }
+
+    if rows.is_empty() {
+        let placeholder = Paragraph::new("no pwnenv environments found.")
+            .block(Block::default().borders(Borders::ALL).title("pwnenv"));
+        frame.render_widget(placeholder, columns[0]);
+        frame.render_widget(Block::default().borders(Borders::ALL).title("detail"), columns[1]);
+        return;
+    }
+
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            let marker = if row.orphaned { " (orphaned)" } else { "" };
+            let line = format!(
+                "{:<20} {:<10} {:<8} {:<8}{marker}",
+                row.env_name,
+                row.state,
+                row.cpu.as_deref().unwrap_or("-"),
+                row.memory.as_deref().unwrap_or("-"),
+            );
+            ListItem::new(Line::from(line))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("pwnenv environments (q to quit)"))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan));
+    frame.render_stateful_widget(list, columns[0], list_state);
+
+    let detail = list_state
+        .selected()
+        .and_then(|i| rows.get(i))
+        .map(render_detail)
+        .unwrap_or_default();
+    frame.render_widget(
+        Paragraph::new(detail).block(Block::default().borders(Borders::ALL).title("detail")),
+        columns[1],
+    );
+}
+
+fn render_detail(row: &PsRow) -> String {
+    let mounts = RuntimeDir::new(&row.env_name)
+        .mounts()
+        .into_iter()
+        .map(|m| format!("{} -> {}", m.host.display(), m.container))
+        .collect::<Vec<_>>();
+
+    let mut lines = vec![
+        format!("env:        {}", row.env_name),
+        format!("container:  {}", row.container_name),
+        format!("state:      {}", row.state),
+        format!("uptime:     {}", row.uptime),
+        format!("cpu:        {}", row.cpu.as_deref().unwrap_or("-")),
+        format!("memory:     {}", row.memory.as_deref().unwrap_or("-")),
+        format!("host dir:   {}", row.host_dir),
+        format!("orphaned:   {}", row.orphaned),
+    ];
+    if mounts.is_empty() {
+        lines.push("mounts:     none".to_string());
+    } else {
+        lines.push("mounts:".to_string());
+        lines.extend(mounts.into_iter().map(|m| format!("  {m}")));
+    }
+    lines.join("\n")
+}
+
+fn clamp_selection(list_state: &mut ListState, len: usize) {
+    match (list_state.selected(), len) {
+        (_, 0) => list_state.select(None),
+        (None, _) => list_state.select(Some(0)),
+        (Some(i), len) if i >= len => list_state.select(Some(len - 1)),
+        _ => {}
+    }
+}
+
+fn select_next(list_state: &mut ListState, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let next = list_state.selected().map_or(0, |i| (i + 1).min(len - 1));
+    list_state.select(Some(next));
+}
+
+fn select_prev(list_state: &mut ListState, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let prev = list_state.selected().map_or(0, |i| i.saturating_sub(1));
+    list_state.select(Some(prev));
+}
+
+/// Runs `collect_rows` on a timer on a background thread, so a slow or
+/// hanging `docker` call never blocks input handling or redraws. The
+/// channel is bounded to 1: a tick whose result hasn't been picked up
+/// yet is replaced by the next one rather than piling up.
+fn spawn_refresh_thread() -> mpsc::Receiver<Refresh> {
+    let (tx, rx) = mpsc::sync_channel(1);
+    std::thread::spawn(move || loop {
+        let refresh = match collect_rows() {
+            Ok(rows) => Refresh::Rows(rows),
+            Err(e) => Refresh::Error(e.to_string()),
+        };
+        // Drop the tick if the TUI already exited and dropped its end.
+        if tx.send(refresh).is_err() {
+            return;
+        }
+        std::thread::sleep(REFRESH_INTERVAL);
+    });
+    rx
+}
+
+fn setup_terminal() -> Result<Terminal<CrosstermBackend<std::io::Stdout>>> {
+    enable_raw_mode().map_err(|e| PwnenvError::Docker(format!("failed to enable raw mode: {e}")))?;
+    let mut stdout = std::io::stdout();
+    stdout
+        .execute(EnterAlternateScreen)
+        .map_err(|e| PwnenvError::Docker(format!("failed to enter alternate screen: {e}")))?;
+    Terminal::new(CrosstermBackend::new(stdout))
+        .map_err(|e| PwnenvError::Docker(format!("failed to initialize terminal: {e}")))
+}
+
+/// Best-effort: always restores the terminal even if `run` returned an
+/// error, so a crash doesn't leave the user's shell in raw mode.
+fn teardown_terminal(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> Result<()> {
+    disable_raw_mode().map_err(|e| PwnenvError::Docker(format!("failed to disable raw mode: {e}")))?;
+    terminal
+        .backend_mut()
+        .execute(LeaveAlternateScreen)
+        .map_err(|e| PwnenvError::Docker(format!("failed to leave alternate screen: {e}")))?;
+    Ok(())
+}
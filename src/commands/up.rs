@@ -0,0 +1,105 @@
+use std::net::TcpListener;
+use std::path::Path;
+
+use crate::activity;
+use crate::config::{validate_image_tag, Config};
+use crate::docker::render_compose;
+use crate::error::{PwnenvError, Result};
+use crate::labels::Labels;
+use crate::runtime::{container_name, RuntimeDir};
+use crate::trace;
+use crate::verify;
+
+/// Brings an environment's compose service up, after checking that its
+/// `forwarded_port` (if any) isn't already taken by something else on
+/// the host — docker's own error for that is an opaque "bind: address
+/// already in use" that doesn't say which of our environments collided.
+/// `host_dir` is the challenge directory `up` was run from, stamped onto
+/// the container as the `dev.pwnenv.host_dir` label (see [`crate::labels`]).
+pub fn up(
+    env_name: &str,
+    cli_tag: Option<&str>,
+    config: &Config,
+    runtime: &RuntimeDir,
+    host_dir: &Path,
+    trace_dir: Option<&Path>,
+) -> Result<()> {
+    if let Some(tag) = cli_tag {
+        validate_image_tag(tag)?;
+    }
+    if let Some(port) = config.forwarded_port {
+        check_port_available(port)?;
+    }
+
+    let mut config = config.clone();
+    if let Some(privileged) = runtime.privileged_override() {
+        config.privileged = privileged;
+    }
+
+    let flag_path = runtime.root().join("flag");
+    let local_flag = flag_path.exists().then_some(flag_path.as_path());
+    let extra_mounts = runtime.mounts();
+    let extra_ports = runtime.extra_ports();
+    let image_tag = crate::runtime::resolve_image_tag(cli_tag, runtime, &config);
+    let image_ref = runtime.image_override().unwrap_or_else(|| image_tag.clone());
+    let labels = Labels::new(env_name, &config, host_dir);
+    let compose = render_compose(&config, &image_ref, env_name, host_dir, local_flag, &extra_mounts, &extra_ports, &labels)?;
+    std::fs::write(runtime.root().join("docker-compose.yml"), &compose)?;
+
+    if let Some(trace_dir) = trace_dir {
+        trace::write_artifact(trace_dir, "docker-compose.yml", &compose)?;
+        trace::write_resolved_config(trace_dir, &config)?;
+        trace::write_redacted_env(trace_dir, &compose)?;
+    }
+
+    let mut up_args = vec![
+        "-f".to_string(),
+        runtime.root().join("docker-compose.yml").display().to_string(),
+        "--project-directory".to_string(),
+        host_dir.display().to_string(),
+        "up".to_string(),
+        "-d".to_string(),
+    ];
+    if runtime.offline() {
+        up_args.push("--pull".to_string());
+        up_args.push("never".to_string());
+    }
+    let status = crate::compose::resolve(config.compose_command.as_deref())
+        .command(&up_args)
+        .status()
+        .map_err(|e| PwnenvError::Docker(e.to_string()))?;
+
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker compose up exited with {status}"
+        )));
+    }
+    activity::log_event(runtime, "up", &[image_tag], Some(0));
+    println!("{env_name}: up.");
+
+    // Best-effort: a broken tool install shouldn't tear down an
+    // otherwise-fine environment. `pwnenv verify` re-runs the same checks
+    // and exits non-zero on failure, for scripts that want to gate on it.
+    let container = container_name(env_name);
+    let results = verify::run_verifications(&container, &config);
+    if !results.is_empty() {
+        let failures = verify::print_results(&results);
+        let _ = verify::save_results(runtime, &results);
+        if failures > 0 {
+            eprintln!(
+                "warning: {failures}/{} verify command(s) failed; rerun `pwnenv verify` after investigating",
+                results.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn check_port_available(port: u16) -> Result<()> {
+    match TcpListener::bind(("0.0.0.0", port)) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(PwnenvError::Docker(format!(
+            "port {port} is already in use on the host; stop whatever's using it before `up`"
+        ))),
+    }
+}
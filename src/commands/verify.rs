@@ -0,0 +1,31 @@
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+use crate::runtime::{container_name, RuntimeDir};
+use crate::verify as verify_engine;
+
+/// Re-runs every tool's `verify` commands against `env_name`'s running
+/// container and prints a pass/fail table, same as the best-effort pass
+/// `up` already runs. Unlike `up`, a failure here is fatal — the whole
+/// point of running this on demand is to gate a script on the exit code.
+pub fn verify(env_name: &str, config: &Config) -> Result<()> {
+    let container = container_name(env_name);
+    let runtime = RuntimeDir::new(env_name);
+    let results = verify_engine::run_verifications(&container, config);
+
+    if results.is_empty() {
+        println!("{env_name}: no tools define `verify` commands.");
+        return Ok(());
+    }
+
+    let failures = verify_engine::print_results(&results);
+    verify_engine::save_results(&runtime, &results)?;
+
+    if failures > 0 {
+        return Err(PwnenvError::Docker(format!(
+            "{failures}/{} verify command(s) failed",
+            results.len()
+        )));
+    }
+    println!("{env_name}: all {} verify command(s) passed.", results.len());
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! Resolves whether to drive `docker compose` (the v2 plugin) or the
+//! standalone `docker-compose` (v1) binary, since every call site that
+//! shells out to compose needs the same answer.
+
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// The resolved compose command line: `program` plus whatever `prefix`
+/// arguments need to come before the caller's own (`up -d`, `kill`, ...).
+/// For v2 that's `docker` plus `["compose"]`; for v1 it's `docker-compose`
+/// plus `[]`; for a [`crate::config::Config::compose_command`] override
+/// it's whatever the override string splits into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposeCommand {
+    pub program: String,
+    pub prefix: Vec<String>,
+}
+
+impl ComposeCommand {
+    /// Builds the `Command`, with `args` appended after `self.prefix`.
+    pub fn command(&self, args: &[String]) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.prefix);
+        command.args(args);
+        command
+    }
+}
+
+static DETECTED: OnceLock<ComposeCommand> = OnceLock::new();
+
+/// `override_cmd` is [`crate::config::Config::compose_command`] — a
+/// literal command line (e.g. `"podman-compose"` or `"docker-compose"`)
+/// that skips autodetection entirely. With no override, autodetects
+/// `docker compose` vs `docker-compose` via [`detect`], probed once and
+/// cached for the rest of the process.
+pub fn resolve(override_cmd: Option<&str>) -> ComposeCommand {
+    if let Some(override_cmd) = override_cmd {
+        let mut parts = override_cmd.split_whitespace().map(str::to_string);
+        let program = parts.next().unwrap_or_else(|| "docker".to_string());
+        return ComposeCommand { program, prefix: parts.collect() };
+    }
+    DETECTED.get_or_init(detect).clone()
+}
+
+/// Probes `docker compose version`; if that fails (no v2 plugin
+/// installed), falls back to the standalone `docker-compose` binary and
+/// prints a one-time warning, since v1's behavior and flag set aren't
+/// identical to v2's.
+fn detect() -> ComposeCommand {
+    let v2_available = Command::new("docker")
+        .args(["compose", "version"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    if v2_available {
+        return ComposeCommand { program: "docker".to_string(), prefix: vec!["compose".to_string()] };
+    }
+
+    eprintln!(
+        "warning: docker compose v2 plugin not found; falling back to standalone docker-compose (v1), \
+         whose flags and behavior aren't identical to v2's. Install the compose plugin for the best \
+         experience, or set compose_command in pwnenv.yaml to silence this check."
+    );
+    ComposeCommand { program: "docker-compose".to_string(), prefix: Vec::new() }
+}
@@ -0,0 +1,898 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PwnenvError, Result};
+use crate::presets;
+
+/// `--set key=value` overrides from the CLI (see [`set_overrides`]),
+/// applied by every [`Config::load`] for the rest of the process. Same
+/// set-once-at-startup shape as [`crate::runtime::set_config_dir_override`].
+static SET_OVERRIDES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Records `--set key=value` overrides to apply on top of every config
+/// this process loads for the rest of its run. Called once, from `main`,
+/// before the first [`Config::load`].
+pub fn set_overrides(values: Vec<String>) {
+    let _ = SET_OVERRIDES.set(values);
+}
+
+/// A single tool to install into the environment image, expressed as a
+/// named block of raw Dockerfile instruction lines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolConfig {
+    pub name: String,
+    pub script: Vec<String>,
+
+    /// When true, this tool's script runs in a separate `builder` stage
+    /// instead of the final image, and only the paths listed in
+    /// `artifacts` are carried over. Use this for tools that need a
+    /// compiler toolchain to produce something small (e.g. building a
+    /// debug glibc) that the final image shouldn't ship.
+    #[serde(default)]
+    pub build_only: bool,
+
+    /// Paths to `COPY --from=builder` into the final image when
+    /// `build_only` is set. Ignored otherwise.
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+
+    /// Shell commands run inside the container after `up` (and on demand
+    /// via `pwnenv verify`) to catch an install that silently broke, e.g.
+    /// a pip resolver pulling in a conflicting version. See
+    /// [`crate::verify`]. A tool with no `verify` commands is never
+    /// checked — there's no forced minimum.
+    #[serde(default)]
+    pub verify: Vec<String>,
+
+    /// When this tool's name matches one from a `preset` or
+    /// `include_tools` bundle, run `script` *after* the default tool's
+    /// own script instead of replacing it outright — so picking up one
+    /// extra install line doesn't require copying the whole default
+    /// script into `pwnenv.yaml`. Has no effect on a tool with no
+    /// same-named default (it's just a regular tool).
+    #[serde(default)]
+    pub append: bool,
+
+    /// Keys into the top-level `secrets` map that this tool's `RUN`
+    /// commands need (e.g. a token for cloning a private repo). Each one
+    /// is mounted with `--mount=type=secret,id=<key>` on this tool's
+    /// `RUN` line instead of an `ARG`/`ENV`, so the value never lands in
+    /// an image layer or `docker history`. See [`Config::secrets`].
+    #[serde(default)]
+    pub secrets: Vec<String>,
+}
+
+/// Bumped whenever a breaking, structural change is made to the
+/// `pwnenv.yaml` schema (as opposed to an additive field with a default,
+/// which doesn't need a bump). Surfaced by `pwnenv __introspect` for
+/// wrapper scripts to feature-detect against.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The full pwnenv environment config, loaded from `pwnenv.yaml` in the
+/// challenge directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default = "default_base_image")]
+    pub base_image: String,
+
+    #[serde(default)]
+    pub tools: Vec<ToolConfig>,
+
+    /// When true (the default), a failing tool install aborts the build
+    /// immediately. When false, each tool is installed best-effort and
+    /// failures are collected into a post-build report instead.
+    #[serde(default = "default_fail_fast")]
+    pub fail_fast: bool,
+
+    /// When true, mount the host's `$SSH_AUTH_SOCK` into the container and
+    /// point `SSH_AUTH_SOCK` at it, so tools can `git clone`/`scp` with the
+    /// host's SSH identity.
+    #[serde(default)]
+    pub forward_ssh_agent: bool,
+
+    /// Shell `enter` execs into the running container with.
+    #[serde(default = "default_shell")]
+    pub shell: String,
+
+    /// When true, `enter` (with no `--as` profile) runs `shell` with `-l`,
+    /// so login-only profile/rc files load the way a real login shell
+    /// would. Defaults to false, matching the exec-directly behavior
+    /// before this option existed.
+    #[serde(default)]
+    pub login_shell: bool,
+
+    /// Named base-image preset (e.g. `ubuntu-18.04`, `debian-10`) that
+    /// sets `base_image` and prepends any install tweaks that release
+    /// needs. Takes priority over an explicit `base_image` when set.
+    #[serde(default)]
+    pub preset: Option<String>,
+
+    /// `/dev/shm` size passed to `docker compose` (e.g. `"256m"`). `None`
+    /// leaves docker's default (usually 64m) in place.
+    #[serde(default)]
+    pub shm_size: Option<String>,
+
+    /// Path (relative to the config) to a challenge binary. When set and
+    /// `preset` isn't, `base_image` is picked automatically from the
+    /// glibc version that binary links against.
+    #[serde(default)]
+    pub auto_detect_libc_from: Option<String>,
+
+    /// Named entry commands for `enter --as <profile>`, e.g. `{"debug":
+    /// "gdb -q /chall"}`. `enter` with no `--as` still uses `shell`.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, String>,
+
+    /// When true (and `auto_detect_libc_from` found a version), builds a
+    /// debug glibc matching the challenge's version inside the image
+    /// instead of relying on whatever debug symbols the base image ships.
+    #[serde(default)]
+    pub build_debug_glibc: bool,
+
+    /// Populated by [`Config::load`] from `auto_detect_libc_from`; not
+    /// part of the on-disk schema.
+    #[serde(skip)]
+    pub detected_glibc_version: Option<String>,
+
+    /// Named tool bundles to pull in (see [`crate::tool_presets`]), e.g.
+    /// `["reversing"]` for ghidra + radare2. Unknown names are ignored.
+    #[serde(default)]
+    pub include_tools: Vec<String>,
+
+    /// Host directory (relative to the config) holding challenge
+    /// binaries/sources to copy into the environment's runtime dir on
+    /// `init`. `None` means there's nothing to copy.
+    #[serde(default)]
+    pub programs_dir: Option<String>,
+
+    /// Host port forwarded to the environment's service, if any.
+    #[serde(default)]
+    pub forwarded_port: Option<u16>,
+
+    /// A host-side script `init` runs after it finishes setting the
+    /// environment up, for coordinated setup that has to happen outside
+    /// the container (starting a proxy, copying a flag template into
+    /// `programs_dir` before it's copied in turn). Run with
+    /// `PWNENV_ENV_NAME` and `PWNENV_FORWARDED_PORT` (empty if unset) in
+    /// its environment, resolved relative to `pwnenv.yaml`'s own
+    /// directory if relative. A non-zero exit fails `init` itself, same
+    /// as any other setup step it can't recover from.
+    #[serde(default)]
+    pub post_init_hook: Option<std::path::PathBuf>,
+
+    /// Host directory mounted at `/workspace` inside the container.
+    #[serde(default)]
+    pub workspace_dir: Option<String>,
+
+    /// Mount `workspace_dir` read-only, so nothing inside the container
+    /// can touch the host's copy of the challenge.
+    #[serde(default)]
+    pub workspace_readonly: bool,
+
+    /// Only meaningful alongside `workspace_readonly`: adds a writable
+    /// tmpfs overlay at `/workspace-scratch` so there's still somewhere
+    /// to write build artifacts without touching the host files.
+    #[serde(default)]
+    pub workspace_overlay: bool,
+
+    /// Host files/dirs (relative to this config, like `programs_dir`)
+    /// that get baked into the image's build context via `COPY` at
+    /// `/workspace/<path>`, instead of relying on `workspace_dir`'s bind
+    /// mount. For the few things (a big IDA database, a prebuilt rootfs)
+    /// worth having in a cached layer so container recreation doesn't
+    /// wait on the host copy again. `workspace_dir`'s mount is layered
+    /// on top at container start and shadows whatever's baked at the
+    /// same path, unless `workspace_overlay` is used.
+    #[serde(default)]
+    pub bake: Vec<String>,
+
+    /// The `docker-compose.yml` top-level `version:` key. `None` (the
+    /// default) omits it, since compose v2 ignores it and recent
+    /// versions warn that it's obsolete; set it (e.g. `"3.9"`) only if
+    /// something in your toolchain still insists on it being there.
+    #[serde(default)]
+    pub compose_version: Option<String>,
+
+    /// Build-time parameters rendered as `ARG` declarations at the top of
+    /// every stage in the Dockerfile (see
+    /// [`crate::docker::dockerfile::render_dockerfile`]), for tool
+    /// scripts that want a value (a pwndbg commit, an internal mirror
+    /// URL) without hardcoding it. `build --build-arg KEY=VALUE` (see
+    /// [`crate::commands::build::parse_build_args`]) overrides a key set
+    /// here, rather than requiring an edit to this file for a one-off build.
+    #[serde(default)]
+    pub build_args: BTreeMap<String, String>,
+
+    /// Raw `sources.list` lines (e.g. `deb https://mirror.example/ubuntu
+    /// jammy main`) written to `/etc/apt/sources.list.d/pwnenv.list`
+    /// before any tool script runs (see
+    /// [`crate::docker::dockerfile::render_dockerfile`]), for teams
+    /// behind a mirror or needing an extra repo that every tool's own
+    /// `apt update` should see, not just the first one's. Checked by
+    /// `config validate`'s lint (see [`crate::docker::lint::lint_tools`])
+    /// for lines that don't start with `deb`/`deb-src`.
+    #[serde(default)]
+    pub apt_sources: Vec<String>,
+
+    /// Which gdb plugin to install: `"pwndbg"`, `"gef"`, `"peda"`, or
+    /// `"none"` (the default, same as leaving it unset) for plain
+    /// upstream gdb. Mutually exclusive — [`Config::apply_gdb_plugin`]
+    /// only ever installs the one named here under a single `"gdb"`
+    /// tool, regardless of anything else in `tools`/`include_tools`.
+    /// Pinned install versions are resolved into `build_args` (see
+    /// [`crate::gdb_plugins`]) so they're overridable the same way any
+    /// other pinned tool version in this file already is, and `.gdbinit`
+    /// generation (`follow-fork-mode`, the debug-glibc source directory)
+    /// happens the same way no matter which plugin (or none) is chosen.
+    /// `init --gdb-plugin` (see [`crate::runtime::RuntimeDir::gdb_plugin_override`])
+    /// overrides this per environment without editing `pwnenv.yaml`.
+    #[serde(default)]
+    pub gdb_plugin: Option<String>,
+
+    /// BuildKit secrets, keyed by the id a `ToolConfig.secrets` entry
+    /// refers to, valued by the host path of a file holding the secret
+    /// (e.g. a deploy token for cloning a private repo). Passed to
+    /// `docker build` as `--secret id=<key>,src=<path>` and requires
+    /// BuildKit (`DOCKER_BUILDKIT=1`, set automatically by `build` when
+    /// this map is non-empty). Unlike `build_args`, a secret's value is
+    /// never written into an image layer or `docker history` — only the
+    /// tool's own `RUN` command sees it, and only at
+    /// `/run/secrets/<key>` for the duration of that command.
+    #[serde(default)]
+    pub secrets: BTreeMap<String, std::path::PathBuf>,
+
+    /// Other `pwnenv.yaml`-shaped files (paths relative to this one)
+    /// whose `tools` get merged in ahead of this file's own — lets a
+    /// team share a `tools/gdb.yaml` snippet across many challenges
+    /// instead of copy-pasting it (YAML anchors handle reuse *within*
+    /// one file; this handles reuse *across* files).
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Whether the container runs `--privileged`. Defaults to true since
+    /// most pwn tooling (gdb, ptrace-based tracers) needs it; set to
+    /// false (or pass `init --no-privileged`) to run with just
+    /// `SYS_PTRACE` instead.
+    #[serde(default = "default_privileged")]
+    pub privileged: bool,
+
+    /// Linux capabilities added to the container when `privileged` is
+    /// false (ignored otherwise, since `privileged: true` already grants
+    /// everything). Defaults to just `SYS_PTRACE`, which is what most pwn
+    /// tooling (gdb, ptrace-based tracers) needs; add e.g. `SYS_ADMIN` or
+    /// `NET_ADMIN` for challenges that need more. Each entry must be a
+    /// capability name docker/the kernel actually recognizes.
+    #[serde(default = "default_cap_add")]
+    pub cap_add: Vec<String>,
+
+    /// Extra bind mounts, as `host:container` pairs, alongside
+    /// `workspace_dir` — e.g. a shared `common/` directory in a finals
+    /// setup with several per-challenge dirs. Combined with any `--mount`
+    /// flags passed to `init`; see [`crate::mounts`].
+    #[serde(default)]
+    pub mounts: Vec<String>,
+
+    /// When true (the default), `programs_dir`'s own `.gitignore` files
+    /// are honored during the copy, same as `programs_exclude` patterns.
+    #[serde(default = "default_respect_gitignore")]
+    pub respect_gitignore: bool,
+
+    /// Gitignore-syntax patterns (relative to `programs_dir`) to skip
+    /// during the copy, independent of any `.gitignore` files.
+    #[serde(default)]
+    pub programs_exclude: Vec<String>,
+
+    /// Paths (relative to `programs_dir`) to always copy even if
+    /// `respect_gitignore` or `programs_exclude` would otherwise skip
+    /// them.
+    #[serde(default)]
+    pub programs_force_include: Vec<String>,
+
+    /// Gitignore-syntax patterns (relative to `programs_dir`); when
+    /// non-empty, only matching files are copied (everything else is
+    /// skipped, as if excluded), instead of the whole directory — for a
+    /// challenge where only a binary and its libc matter, baking
+    /// everything else in `programs_dir` just bloats the image and the
+    /// build context for no reason. Empty (the default) copies
+    /// everything, same as before this option existed.
+    /// `programs_force_include` still wins over this, same as it already
+    /// wins over `programs_exclude`/`.gitignore`.
+    #[serde(default)]
+    pub programs_include: Vec<String>,
+
+    /// Custom DNS servers for both the build and the running container,
+    /// e.g. `["1.1.1.1", "8.8.8.8"]`. Useful behind corporate networks
+    /// where docker's default resolver can't reach package mirrors.
+    /// Each entry must be a valid IP address.
+    #[serde(default)]
+    pub dns: Vec<String>,
+
+    /// Compose `restart:` policy for the service: `"no"` (the default),
+    /// `"on-failure"`, `"always"`, or `"unless-stopped"`. Set this for
+    /// long-running challenge infra that should survive a daemon
+    /// restart without a manual `up`.
+    #[serde(default = "default_restart_policy")]
+    pub restart_policy: String,
+
+    /// Explicit compose `user:` for the container — a name, uid,
+    /// `user:group`, or `uid:gid` (e.g. `"root"` or `"1000:1000"`), see
+    /// [`crate::docker::compose::validate_container_user`]. `None` (the
+    /// default) leaves the image's own default user in place, unchanged
+    /// from before this option existed; set it for challenges that
+    /// assume root-owned files and break under whatever non-root user
+    /// the base image runs as otherwise.
+    #[serde(default)]
+    pub container_user: Option<String>,
+
+    /// The pwnenv version that last wrote this config, stamped by `init`
+    /// and refreshed by `config upgrade`. `None` means the config predates
+    /// this field entirely. See [`crate::version`].
+    #[serde(default)]
+    pub generated_by: Option<String>,
+
+    /// Caps how many environments may be `up` at once, across every
+    /// pwnenv challenge directory on this host. `None` (the default)
+    /// leaves it unlimited. See [`crate::commands::limit`]; `up --force`
+    /// bypasses this per-invocation.
+    #[serde(default)]
+    pub max_running_environments: Option<u32>,
+
+    /// Registers the `i386` architecture and installs 32-bit multiarch
+    /// packages (`libc6:i386`, `gcc-multilib`, and friends) for a 32-bit
+    /// challenge, which otherwise fails at runtime with no i386 libc
+    /// available at all. See [`crate::arch::i386_tool`], which
+    /// `apply_i386` prepends ahead of every other tool so the
+    /// architecture is registered before anything else's `apt-get`
+    /// layer runs.
+    #[serde(default)]
+    pub i386: bool,
+
+    /// The tag `build`/`up` give the environment's image, so it can be
+    /// referenced predictably with `docker run` outside pwnenv instead of
+    /// the generic `pwnenv-env` every environment gets by default.
+    /// `init --image-tag` sets a per-environment override without editing
+    /// this field (see [`crate::runtime::RuntimeDir::image_tag_override`]);
+    /// an explicit `--tag` on `build`/`up` itself wins over both.
+    #[serde(default)]
+    pub image_tag: Option<String>,
+
+    /// Shell command lines run by the generated entrypoint script (see
+    /// [`crate::entrypoint`]) before it hands off to `tini`, e.g.
+    /// starting `sshd` or warming a cache. Runs on every container start,
+    /// not just the first — keep lines idempotent.
+    #[serde(default)]
+    pub on_start: Vec<String>,
+
+    /// Overrides [`crate::compose::resolve`]'s autodetection outright,
+    /// e.g. `"docker-compose"` to force the standalone v1 binary, or
+    /// `"podman-compose"` to drive a different compose implementation
+    /// entirely. Unset (the default) autodetects `docker compose` (v2)
+    /// vs `docker-compose` (v1), warning once if only v1 is found.
+    #[serde(default)]
+    pub compose_command: Option<String>,
+
+    /// URLs (an https link to a YAML file, or a git repo — see
+    /// [`crate::remote_tools`]) whose `tools` are merged in ahead of
+    /// this file's own, same priority as `include_tools`. Never fetched
+    /// by `Config::load` itself — run `pwnenv tools sync` first, which
+    /// is what actually touches the network; this just reads whatever
+    /// that last cached, so `render`/`build` work offline. A remote
+    /// tool with the same name as a local one loses to the local one,
+    /// with a warning.
+    #[serde(default)]
+    pub remote_tools: Vec<String>,
+}
+
+fn default_respect_gitignore() -> bool {
+    true
+}
+
+fn default_privileged() -> bool {
+    true
+}
+
+fn default_cap_add() -> Vec<String> {
+    vec!["SYS_PTRACE".to_string()]
+}
+
+/// The subset of [`Config`] an `include`d file is expected to provide.
+#[derive(Debug, Clone, Deserialize, Default)]
+struct IncludeFragment {
+    #[serde(default)]
+    tools: Vec<ToolConfig>,
+}
+
+fn default_base_image() -> String {
+    "ubuntu:22.04".to_string()
+}
+
+fn default_fail_fast() -> bool {
+    true
+}
+
+fn default_shell() -> String {
+    "/bin/bash".to_string()
+}
+
+fn default_restart_policy() -> String {
+    "no".to_string()
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            base_image: default_base_image(),
+            tools: Vec::new(),
+            fail_fast: default_fail_fast(),
+            forward_ssh_agent: false,
+            shell: default_shell(),
+            login_shell: false,
+            preset: None,
+            shm_size: None,
+            auto_detect_libc_from: None,
+            profiles: BTreeMap::new(),
+            build_debug_glibc: false,
+            detected_glibc_version: None,
+            include_tools: Vec::new(),
+            programs_dir: None,
+            forwarded_port: None,
+            post_init_hook: None,
+            workspace_dir: None,
+            workspace_readonly: false,
+            workspace_overlay: false,
+            bake: Vec::new(),
+            compose_version: None,
+            build_args: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            include: Vec::new(),
+            privileged: default_privileged(),
+            cap_add: default_cap_add(),
+            mounts: Vec::new(),
+            respect_gitignore: default_respect_gitignore(),
+            programs_exclude: Vec::new(),
+            programs_force_include: Vec::new(),
+            programs_include: Vec::new(),
+            dns: Vec::new(),
+            restart_policy: default_restart_policy(),
+            container_user: None,
+            generated_by: None,
+            max_running_environments: None,
+            i386: false,
+            image_tag: None,
+            on_start: Vec::new(),
+            compose_command: None,
+            remote_tools: Vec::new(),
+            apt_sources: Vec::new(),
+            gdb_plugin: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Config> {
+        let raw = std::fs::read_to_string(path).map_err(|source| PwnenvError::ConfigRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if raw.trim().is_empty() {
+            // A config that's merely empty (vs. invalid YAML) is almost
+            // always a write interrupted mid-way, not a deliberate empty
+            // file, so it gets its own clearer message and recovery hint
+            // instead of an opaque serde EOF error.
+            return Err(PwnenvError::ConfigEmpty(path.to_path_buf()));
+        }
+        let mut value: serde_yaml::Value =
+            serde_yaml::from_str(&raw).map_err(|source| PwnenvError::ConfigParse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        if let Some(overrides) = SET_OVERRIDES.get() {
+            for raw_override in overrides {
+                apply_set_override(&mut value, raw_override)?;
+            }
+        }
+        let mut config: Config =
+            serde_yaml::from_value(value).map_err(|source| PwnenvError::ConfigParse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        config.apply_includes(path)?;
+        config.apply_preset();
+        config.apply_auto_libc_detect();
+        config.apply_debug_glibc();
+        config.apply_include_tools();
+        config.apply_remote_tools();
+        config.apply_gdb_plugin();
+        config.apply_entrypoint_tool();
+        config.apply_i386();
+        if let Some(tag) = &config.image_tag {
+            validate_image_tag(tag)?;
+        }
+        if let Some(plugin) = &config.gdb_plugin {
+            crate::gdb_plugins::validate_plugin(plugin)?;
+        }
+        Ok(config)
+    }
+
+    /// Merges `tools` from every file in `include`, resolved relative to
+    /// `base_path`'s directory, ahead of this file's own tools.
+    fn apply_includes(&mut self, base_path: &Path) -> Result<()> {
+        let base_dir = base_path.parent().unwrap_or_else(|| Path::new("."));
+        let mut merged = Vec::new();
+
+        for include_path in &self.include {
+            let resolved = base_dir.join(include_path);
+            let raw = std::fs::read_to_string(&resolved).map_err(|source| PwnenvError::ConfigRead {
+                path: resolved.clone(),
+                source,
+            })?;
+            let fragment: IncludeFragment =
+                serde_yaml::from_str(&raw).map_err(|source| PwnenvError::ConfigParse {
+                    path: resolved.clone(),
+                    source,
+                })?;
+            merged.extend(fragment.tools);
+        }
+
+        merged.extend(std::mem::take(&mut self.tools));
+        self.tools = merged;
+        Ok(())
+    }
+
+    /// Prepends tools from every bundle named in `include_tools`, ahead
+    /// of the user's own tools (so a same-named user tool still wins,
+    /// or — if it sets `append` — has its script appended to the
+    /// bundled tool's instead of replacing it).
+    fn apply_include_tools(&mut self) {
+        let mut bundled = Vec::new();
+        for name in &self.include_tools.clone() {
+            if let Some(tools) = crate::tool_presets::lookup(name) {
+                bundled.extend(tools);
+            }
+            crate::tool_presets::apply_default_build_args(self, name);
+        }
+        apply_append_overrides(&bundled, &mut self.tools);
+        bundled.retain(|tool| !self.tools.iter().any(|t| t.name == tool.name));
+        bundled.extend(std::mem::take(&mut self.tools));
+        self.tools = bundled;
+    }
+
+    /// Merges `remote_tools`' cached tool definitions in, same priority
+    /// as `include_tools` (see [`Self::apply_include_tools`]): a remote
+    /// tool loses outright to a same-named local one, with a warning,
+    /// rather than being appended to it.
+    fn apply_remote_tools(&mut self) {
+        if self.remote_tools.is_empty() {
+            return;
+        }
+        let mut remote = crate::remote_tools::load_cached(&self.remote_tools);
+        for tool in &remote {
+            if self.tools.iter().any(|t| t.name == tool.name) {
+                eprintln!(
+                    "warning: remote tool '{}' conflicts with a local tool of the same name; keeping the local one",
+                    tool.name
+                );
+            }
+        }
+        remote.retain(|tool| !self.tools.iter().any(|t| t.name == tool.name));
+        remote.extend(std::mem::take(&mut self.tools));
+        self.tools = remote;
+    }
+
+    /// If `preset` didn't already pick a base image and
+    /// `auto_detect_libc_from` is set, sniffs the referenced binary's
+    /// glibc version and picks a matching base image. Detection failures
+    /// are non-fatal: the explicit/default `base_image` is kept.
+    fn apply_auto_libc_detect(&mut self) {
+        if self.preset.is_some() {
+            return;
+        }
+        let Some(binary) = &self.auto_detect_libc_from else {
+            return;
+        };
+        let Ok(Some(version)) = crate::libc_detect::detect_glibc_version(std::path::Path::new(binary))
+        else {
+            return;
+        };
+        self.base_image = crate::libc_detect::base_image_for_glibc(&version).to_string();
+        self.detected_glibc_version = Some(version);
+    }
+
+    /// If `build_debug_glibc` is set and a glibc version was detected,
+    /// appends a `build_only` tool that compiles a matching debug glibc
+    /// from source and carries the built `.debug` artifacts into the
+    /// final image.
+    fn apply_debug_glibc(&mut self) {
+        if !self.build_debug_glibc {
+            return;
+        }
+        let Some(version) = self.detected_glibc_version.clone() else {
+            return;
+        };
+        self.tools.push(crate::libc_detect::debug_glibc_tool(&version));
+    }
+
+    /// If `preset` is set, points `base_image` at it and prepends the
+    /// preset's tools ahead of the user's own (a user tool with the same
+    /// name still wins, since it's appended after — or, if it sets
+    /// `append`, has its script appended to the preset tool's instead
+    /// of replacing it).
+    fn apply_preset(&mut self) {
+        let Some(name) = self.preset.clone() else {
+            return;
+        };
+        let Some(preset) = presets::lookup(&name) else {
+            return;
+        };
+
+        self.base_image = preset.base_image.to_string();
+
+        let mut tools = preset.tool_configs();
+        apply_append_overrides(&tools, &mut self.tools);
+        tools.retain(|preset_tool| !self.tools.iter().any(|t| t.name == preset_tool.name));
+        tools.extend(std::mem::take(&mut self.tools));
+        self.tools = tools;
+    }
+
+    /// Installs `gdb_plugin` (`"pwndbg"`/`"gef"`/`"peda"`, or plain gdb
+    /// for `"none"`/unset) as a single `"gdb"` tool, plus the always-on
+    /// `"gdbinit"` tool that writes `/root/.gdbinit` (see
+    /// [`crate::gdb_plugins`]). Drops any existing `"gdb"`/`"gdbinit"`
+    /// tool first, so this is safe to call again after `build`/`up`
+    /// apply `init --gdb-plugin`'s per-environment override — the second
+    /// call's result simply replaces the first's.
+    pub fn apply_gdb_plugin(&mut self) {
+        let plugin = self.gdb_plugin.clone().unwrap_or_else(|| "none".to_string());
+        self.tools.retain(|tool| tool.name != "gdb" && tool.name != "gdbinit");
+        crate::gdb_plugins::apply_default_ref(self, &plugin);
+        let gdbinit = crate::gdb_plugins::gdbinit_tool(self, &plugin);
+        self.tools.push(crate::gdb_plugins::plugin_tool(&plugin));
+        self.tools.push(gdbinit);
+    }
+
+    /// If `i386` is set, inserts [`crate::arch::i386_tool`] at the very
+    /// front of `tools` — applied last, after every other tool source
+    /// (`include`/`preset`/`include_tools`) has had its say, so nothing
+    /// can end up ahead of it and run its own `apt-get` before the
+    /// architecture is registered.
+    fn apply_i386(&mut self) {
+        if !self.i386 {
+            return;
+        }
+        self.tools.insert(0, crate::arch::i386_tool());
+    }
+
+    /// Every environment gets [`crate::entrypoint::tini_tool`], unlike
+    /// `i386` which is opt-in — the generated entrypoint script (see
+    /// [`crate::entrypoint`]) always hands off to `tini`, so it always
+    /// needs to be installed. Runs ahead of `apply_i386` so `i386` still
+    /// ends up first in `tools` regardless.
+    fn apply_entrypoint_tool(&mut self) {
+        self.tools.insert(0, crate::entrypoint::tini_tool());
+    }
+}
+
+/// Applies one `--set key=value` (see [`set_overrides`]) onto the
+/// freshly-parsed, not-yet-typed config `value`. `key` is dot-separated
+/// (`tools.0.name`); a segment that parses as an integer indexes into a
+/// sequence (appending when it equals the sequence's current length),
+/// anything else is a mapping key, created (as `null`) if it's not there
+/// yet so a deeper segment has somewhere to land. `value` is parsed as
+/// YAML, so `--set i386=true` sets an actual bool rather than the string
+/// `"true"`; anything that doesn't parse as YAML is kept as a string.
+fn apply_set_override(root: &mut serde_yaml::Value, raw: &str) -> Result<()> {
+    let (key, value) = raw.split_once('=').ok_or_else(|| {
+        PwnenvError::Docker(format!("--set '{raw}' is not of the form key=value"))
+    })?;
+    let value: serde_yaml::Value =
+        serde_yaml::from_str(value).unwrap_or_else(|_| serde_yaml::Value::String(value.to_string()));
+
+    let segments: Vec<&str> = key.split('.').collect();
+    set_path(root, &segments, value)
+}
+
+fn set_path(node: &mut serde_yaml::Value, segments: &[&str], value: serde_yaml::Value) -> Result<()> {
+    let (segment, rest) = segments
+        .split_first()
+        .expect("apply_set_override never calls this with an empty key");
+
+    if rest.is_empty() {
+        return assign(node, segment, value);
+    }
+    set_path(child_mut(node, segment)?, rest, value)
+}
+
+/// Sets `segment` on `node` (a mapping key, or a sequence index) to
+/// `value`, auto-vivifying `node` itself from `null` into whichever shape
+/// `segment` implies.
+fn assign(node: &mut serde_yaml::Value, segment: &str, value: serde_yaml::Value) -> Result<()> {
+    autovivify(node, segment);
+    match (node, segment.parse::<usize>()) {
+        (serde_yaml::Value::Sequence(seq), Ok(index)) if index == seq.len() => {
+            seq.push(value);
+            Ok(())
+        }
+        (serde_yaml::Value::Sequence(seq), Ok(index)) => match seq.get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(PwnenvError::Docker(format!(
+                "--set index {index} is out of range for a list of length {}",
+                seq.len()
+            ))),
+        },
+        (serde_yaml::Value::Mapping(map), _) => {
+            map.insert(serde_yaml::Value::String(segment.to_string()), value);
+            Ok(())
+        }
+        (node, _) => Err(PwnenvError::Docker(format!(
+            "--set can't address key '{segment}' on {node:?}"
+        ))),
+    }
+}
+
+/// Same idea as [`assign`], but returns a mutable reference to the child
+/// at `segment` (auto-vivified to `null` if it's missing) instead of
+/// overwriting it, for [`set_path`] to keep descending into.
+fn child_mut<'a>(node: &'a mut serde_yaml::Value, segment: &str) -> Result<&'a mut serde_yaml::Value> {
+    autovivify(node, segment);
+    match (node, segment.parse::<usize>()) {
+        (serde_yaml::Value::Sequence(seq), Ok(index)) => {
+            if index == seq.len() {
+                seq.push(serde_yaml::Value::Null);
+            }
+            let len = seq.len();
+            seq.get_mut(index).ok_or_else(|| {
+                PwnenvError::Docker(format!(
+                    "--set index {index} is out of range for a list of length {len}"
+                ))
+            })
+        }
+        (serde_yaml::Value::Mapping(map), _) => {
+            let key = serde_yaml::Value::String(segment.to_string());
+            if !map.contains_key(&key) {
+                map.insert(key.clone(), serde_yaml::Value::Null);
+            }
+            Ok(map.get_mut(&key).expect("just inserted above if it was missing"))
+        }
+        (node, _) => Err(PwnenvError::Docker(format!(
+            "--set can't address key '{segment}' on {node:?}"
+        ))),
+    }
+}
+
+/// Turns a `null` `node` into an empty sequence or mapping depending on
+/// whether `segment` looks like a list index, so a `--set` that names a
+/// path no one's written to yet (e.g. a brand new nested field) has
+/// somewhere to go instead of erroring.
+fn autovivify(node: &mut serde_yaml::Value, segment: &str) {
+    if !node.is_null() {
+        return;
+    }
+    *node = if segment.parse::<usize>().is_ok() {
+        serde_yaml::Value::Sequence(Vec::new())
+    } else {
+        serde_yaml::Value::Mapping(serde_yaml::Mapping::new())
+    };
+}
+
+/// Rejects an `image_tag` docker would refuse outright: empty, containing
+/// whitespace, or using uppercase letters (docker repository names must
+/// be lowercase). Doesn't attempt the full reference grammar — docker
+/// itself is the final authority once the tag reaches `docker build -t`.
+pub fn validate_image_tag(tag: &str) -> Result<()> {
+    if tag.is_empty() || tag.chars().any(|c| c.is_whitespace() || c.is_uppercase()) {
+        return Err(PwnenvError::InvalidImageTag(tag.to_string()));
+    }
+    Ok(())
+}
+
+/// For each tool in `overrides` that sets `append` and shares its name
+/// with one in `defaults`, splices the default's `script` ahead of the
+/// override's own — in place, before `overrides` is merged against
+/// `defaults` by name elsewhere in `apply_preset`/`apply_include_tools`.
+/// A tool with no same-named default is left untouched.
+fn apply_append_overrides(defaults: &[ToolConfig], overrides: &mut [ToolConfig]) {
+    for tool in overrides.iter_mut() {
+        if !tool.append {
+            continue;
+        }
+        if let Some(default) = defaults.iter().find(|t| t.name == tool.name) {
+            let mut script = default.script.clone();
+            script.append(&mut tool.script);
+            tool.script = script;
+        }
+    }
+}
+
+#[cfg(test)]
+mod set_override_tests {
+    use super::*;
+
+    fn yaml(raw: &str) -> serde_yaml::Value {
+        serde_yaml::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn top_level_scalar_is_overwritten_with_its_yaml_type() {
+        let mut value = yaml("i386: false\n");
+        apply_set_override(&mut value, "i386=true").unwrap();
+        assert_eq!(value["i386"], serde_yaml::Value::Bool(true));
+    }
+
+    #[test]
+    fn missing_nested_key_is_created_on_demand() {
+        let mut value = yaml("base_image: ubuntu\n");
+        apply_set_override(&mut value, "build_args.MIRROR=https://mirror.example").unwrap();
+        assert_eq!(
+            value["build_args"]["MIRROR"],
+            serde_yaml::Value::String("https://mirror.example".to_string())
+        );
+    }
+
+    #[test]
+    fn list_index_at_the_end_appends() {
+        let mut value = yaml("apt_sources: []\n");
+        apply_set_override(&mut value, "apt_sources.0=deb https://mirror.example jammy main").unwrap();
+        assert_eq!(value["apt_sources"].as_sequence().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn list_index_out_of_range_is_an_error() {
+        let mut value = yaml("apt_sources: []\n");
+        assert!(apply_set_override(&mut value, "apt_sources.5=deb x y z").is_err());
+    }
+
+    #[test]
+    fn existing_list_index_is_replaced_in_place() {
+        let mut value = yaml("apt_sources: [\"deb a b c\"]\n");
+        apply_set_override(&mut value, "apt_sources.0=deb d e f").unwrap();
+        assert_eq!(
+            value["apt_sources"][0],
+            serde_yaml::Value::String("deb d e f".to_string())
+        );
+    }
+
+    #[test]
+    fn malformed_override_without_an_equals_sign_is_an_error() {
+        let mut value = yaml("base_image: ubuntu\n");
+        assert!(apply_set_override(&mut value, "i386").is_err());
+    }
+}
+
+#[cfg(test)]
+mod gdb_plugin_tests {
+    use super::*;
+
+    #[test]
+    fn unset_gdb_plugin_installs_plain_gdb_and_gdbinit() {
+        let mut config = Config::default();
+        config.apply_gdb_plugin();
+        assert_eq!(config.tools.iter().filter(|t| t.name == "gdb").count(), 1);
+        assert_eq!(config.tools.iter().filter(|t| t.name == "gdbinit").count(), 1);
+    }
+
+    #[test]
+    fn switching_plugins_replaces_the_previous_gdb_tool_instead_of_stacking() {
+        let mut config = Config { gdb_plugin: Some("pwndbg".to_string()), ..Config::default() };
+        config.apply_gdb_plugin();
+        config.gdb_plugin = Some("gef".to_string());
+        config.apply_gdb_plugin();
+
+        assert_eq!(config.tools.iter().filter(|t| t.name == "gdb").count(), 1);
+        let gdb_tool = config.tools.iter().find(|t| t.name == "gdb").unwrap();
+        assert!(gdb_tool.script.iter().any(|line| line.contains("gef")));
+        assert!(!gdb_tool.script.iter().any(|line| line.contains("pwndbg")));
+    }
+
+    #[test]
+    fn chosen_plugin_pins_a_default_build_arg() {
+        let mut config = Config { gdb_plugin: Some("pwndbg".to_string()), ..Config::default() };
+        config.apply_gdb_plugin();
+        assert!(config.build_args.contains_key("PWNDBG_REF"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Line-based diffing helpers for `pwnenv diff-env`: a minimal unified
+//! diff (see [`unified_diff`]), good enough for Dockerfiles/compose
+//! files/package lists without pulling in a diff crate, plus the
+//! [`CategoryResult`] shape each comparison category reports into.
+
+use serde::Serialize;
+
+/// One category `diff-env` compared (config, Dockerfile, compose, base
+/// image, packages). `unavailable` takes priority over `differs`/`detail`
+/// when set: there was nothing to compare (e.g. an environment that was
+/// never built has no `docker-compose.yml` yet), not nothing different.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryResult {
+    pub category: String,
+    pub differs: bool,
+    pub unavailable: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl CategoryResult {
+    pub fn same(category: &str) -> Self {
+        CategoryResult { category: category.to_string(), differs: false, unavailable: None, detail: None }
+    }
+
+    pub fn different(category: &str, detail: String) -> Self {
+        CategoryResult { category: category.to_string(), differs: true, unavailable: None, detail: Some(detail) }
+    }
+
+    pub fn unavailable(category: &str, reason: String) -> Self {
+        CategoryResult { category: category.to_string(), differs: false, unavailable: Some(reason), detail: None }
+    }
+
+    /// [`Self::same`] if `a == b`, else [`Self::different`] with
+    /// [`unified_diff`]'s output as the detail.
+    pub fn from_text(category: &str, a: &str, b: &str) -> Self {
+        if a == b {
+            Self::same(category)
+        } else {
+            Self::different(category, unified_diff(a, b))
+        }
+    }
+}
+
+/// A minimal line-based unified diff: the longest common subsequence of
+/// lines, walked forward to emit ` `/`-`/`+`-prefixed lines — the same
+/// shape `diff -u` without context windowing produces. Good enough for
+/// the Dockerfiles/compose files/package lists `diff-env` compares,
+/// which are at most a few hundred lines.
+pub fn unified_diff(a: &str, b: &str) -> String {
+    let a_lines: Vec<&str> = a.lines().collect();
+    let b_lines: Vec<&str> = b.lines().collect();
+    let table = lcs_table(&a_lines, &b_lines);
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0usize, 0usize);
+    while i < a_lines.len() || j < b_lines.len() {
+        if i < a_lines.len() && j < b_lines.len() && a_lines[i] == b_lines[j] {
+            out.push(format!(" {}", a_lines[i]));
+            i += 1;
+            j += 1;
+        } else if j < b_lines.len() && (i == a_lines.len() || table[i][j + 1] > table[i + 1][j]) {
+            out.push(format!("+{}", b_lines[j]));
+            j += 1;
+        } else {
+            out.push(format!("-{}", a_lines[i]));
+            i += 1;
+        }
+    }
+    out.join("\n")
+}
+
+/// `table[i][j]` is the length of the longest common subsequence of
+/// `a[i..]` and `b[j..]`, filled bottom-up so [`unified_diff`] can walk
+/// it forward without recursion.
+fn lcs_table(a: &[&str], b: &[&str]) -> Vec<Vec<u32>> {
+    let mut table = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_text_is_all_context_lines() {
+        assert_eq!(unified_diff("a\nb\nc", "a\nb\nc"), " a\n b\n c");
+    }
+
+    #[test]
+    fn a_changed_line_shows_as_removed_then_added() {
+        let diff = unified_diff("a\nb\nc", "a\nx\nc");
+        assert_eq!(diff, " a\n-b\n+x\n c");
+    }
+
+    #[test]
+    fn an_appended_line_shows_as_a_trailing_addition() {
+        let diff = unified_diff("a\nb", "a\nb\nc");
+        assert_eq!(diff, " a\n b\n+c");
+    }
+
+    #[test]
+    fn a_removed_line_shows_as_a_deletion() {
+        let diff = unified_diff("a\nb\nc", "a\nc");
+        assert_eq!(diff, " a\n-b\n c");
+    }
+
+    #[test]
+    fn from_text_reports_same_for_identical_input() {
+        let result = CategoryResult::from_text("dockerfile", "FROM ubuntu\n", "FROM ubuntu\n");
+        assert!(!result.differs);
+        assert!(result.detail.is_none());
+    }
+
+    #[test]
+    fn from_text_reports_differs_with_a_diff_for_changed_input() {
+        let result = CategoryResult::from_text("dockerfile", "FROM ubuntu:20.04\n", "FROM ubuntu:22.04\n");
+        assert!(result.differs);
+        assert!(result.detail.unwrap().contains("-FROM ubuntu:20.04"));
+    }
+}
@@ -0,0 +1,480 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+use crate::labels::Labels;
+use crate::mounts::Mount;
+
+/// Renders the `docker-compose.yml` for `config`'s environment, given the
+/// image tag produced by the build. `host_dir` is the challenge directory
+/// `up` was invoked from; a relative `config.workspace_dir` resolves
+/// against it, the same base [`crate::mounts::parse`] uses for bind
+/// mounts, rather than against whatever the process's cwd happens to be
+/// when `docker compose` actually reads the file. `local_flag` is the
+/// host path of a local test flag (see [`crate::commands::flag`]) to
+/// mount read-only at `/flag`, if any. `extra_mounts` are the bind mounts
+/// `init` resolved (see [`crate::mounts`]), mounted alongside
+/// `workspace_dir`. `extra_ports` are `(host, container)` pairs beyond
+/// `config.forwarded_port`, added by `open-port` (see
+/// [`crate::commands::open_port`]) without touching `pwnenv.yaml`.
+/// `labels` (see [`crate::labels`]) are stamped onto the service so
+/// `status --verbose`/`ps`/orphan-detection can find this container
+/// reliably. The top-level `version:` key is omitted unless
+/// `config.compose_version` is set — compose v2 ignores it, and recent
+/// versions warn it's obsolete. `init: true` is always set, pairing with
+/// [`crate::entrypoint`]'s `tini` handoff as a second line of defense
+/// against zombie processes. `config.container_user`, when set, becomes
+/// an explicit `user:` line (see [`validate_container_user`]) for
+/// challenges that assume root-owned files in the image; otherwise
+/// docker's own default for the image applies, unchanged from before
+/// this option existed.
+#[allow(clippy::too_many_arguments)]
+pub fn render_compose(
+    config: &Config,
+    image_tag: &str,
+    service_name: &str,
+    host_dir: &Path,
+    local_flag: Option<&Path>,
+    extra_mounts: &[Mount],
+    extra_ports: &[(u16, u16)],
+    labels: &Labels,
+) -> Result<String> {
+    let mut out = String::new();
+    if let Some(version) = &config.compose_version {
+        let _ = writeln!(out, "version: \"{version}\"");
+    }
+    let _ = writeln!(out, "services:");
+    let _ = writeln!(out, "  {service_name}:");
+    let _ = writeln!(out, "    image: {image_tag}");
+    let _ = writeln!(out, "    init: true");
+
+    if let Some(shm_size) = &config.shm_size {
+        let _ = writeln!(out, "    shm_size: {shm_size}");
+    }
+
+    let mut ports: Vec<(u16, u16)> = config.forwarded_port.map(|port| (port, port)).into_iter().collect();
+    ports.extend(extra_ports);
+    if !ports.is_empty() {
+        let _ = writeln!(out, "    ports:");
+        for (host, container) in &ports {
+            let _ = writeln!(out, "      - \"{host}:{container}\"");
+        }
+    }
+
+    if config.privileged {
+        let _ = writeln!(out, "    privileged: true");
+    } else {
+        validate_capabilities(&config.cap_add)?;
+        let _ = writeln!(out, "    cap_add:");
+        for cap in &config.cap_add {
+            let _ = writeln!(out, "      - {cap}");
+        }
+    }
+
+    if !config.dns.is_empty() {
+        validate_dns(&config.dns)?;
+        let _ = writeln!(out, "    dns:");
+        for server in &config.dns {
+            let _ = writeln!(out, "      - {server}");
+        }
+    }
+
+    if config.restart_policy != "no" {
+        validate_restart_policy(&config.restart_policy)?;
+        let _ = writeln!(out, "    restart: {}", config.restart_policy);
+    }
+
+    if let Some(container_user) = &config.container_user {
+        validate_container_user(container_user)?;
+        let _ = writeln!(out, "    user: \"{container_user}\"");
+    }
+
+    let mut volumes: Vec<String> = Vec::new();
+    let mut environment: Vec<String> = Vec::new();
+    let mut tmpfs: Vec<String> = Vec::new();
+
+    if let Some(workspace_dir) = &config.workspace_dir {
+        let suffix = if config.workspace_readonly { ":ro" } else { "" };
+        let workspace_dir = Path::new(workspace_dir);
+        let workspace_dir = if workspace_dir.is_absolute() {
+            workspace_dir.to_path_buf()
+        } else {
+            host_dir.join(workspace_dir)
+        };
+        volumes.push(format!("{}:/workspace{suffix}", workspace_dir.display()));
+        if config.workspace_readonly && config.workspace_overlay {
+            tmpfs.push("/workspace-scratch".to_string());
+        }
+    }
+
+    if config.forward_ssh_agent {
+        let sock = std::env::var("SSH_AUTH_SOCK").map_err(|_| PwnenvError::MissingEnvVar {
+            var: "SSH_AUTH_SOCK".to_string(),
+            option: "forward_ssh_agent".to_string(),
+        })?;
+        volumes.push(format!("{sock}:{sock}"));
+        environment.push(format!("SSH_AUTH_SOCK={sock}"));
+    }
+
+    if let Some(flag_path) = local_flag {
+        volumes.push(format!("{}:/flag:ro", flag_path.display()));
+    }
+
+    for mount in extra_mounts {
+        volumes.push(format!("{}:{}", mount.host.display(), mount.container));
+    }
+
+    if !volumes.is_empty() {
+        let _ = writeln!(out, "    volumes:");
+        for volume in &volumes {
+            let _ = writeln!(out, "      - {volume}");
+        }
+    }
+
+    if !environment.is_empty() {
+        let _ = writeln!(out, "    environment:");
+        for entry in &environment {
+            let _ = writeln!(out, "      - {entry}");
+        }
+    }
+
+    if !tmpfs.is_empty() {
+        let _ = writeln!(out, "    tmpfs:");
+        for mount in &tmpfs {
+            let _ = writeln!(out, "      - {mount}");
+        }
+    }
+
+    let _ = writeln!(out, "    labels:");
+    for (key, value) in labels.as_pairs() {
+        let _ = writeln!(out, "      {key}: \"{value}\"");
+    }
+
+    Ok(out)
+}
+
+/// Lists the service names declared under a rendered compose file's
+/// top-level `services:` key, e.g. for [`crate::commands::enter::enter`]
+/// to validate a `--service` against what's actually there. Today that's
+/// always exactly one entry (named after the environment itself — see
+/// [`render_compose`]), but this reads the file rather than assuming
+/// that, so it keeps working if `render_compose` ever grows support for
+/// more than one service.
+pub fn service_names(compose_yaml: &str) -> Result<Vec<String>> {
+    let parsed: serde_yaml::Value = serde_yaml::from_str(compose_yaml)
+        .map_err(|e| PwnenvError::Docker(format!("failed to parse docker-compose.yml: {e}")))?;
+    let services = parsed
+        .get("services")
+        .and_then(|v| v.as_mapping())
+        .cloned()
+        .unwrap_or_default();
+    Ok(services.keys().filter_map(|k| k.as_str().map(str::to_string)).collect())
+}
+
+/// Rejects any entry in `dns` that isn't a valid IP address, since
+/// `docker compose`/`docker build` would otherwise fail opaquely mid-build.
+pub fn validate_dns(dns: &[String]) -> Result<()> {
+    for server in dns {
+        if server.parse::<std::net::IpAddr>().is_err() {
+            return Err(PwnenvError::InvalidDns(server.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// The capability names the kernel/docker actually recognize (minus the
+/// `CAP_` prefix, matching docker's own `cap_add:` syntax), so a typo like
+/// `SYS_PTRAC` errors here instead of being silently ignored by docker.
+const KNOWN_CAPABILITIES: &[&str] = &[
+    "CHOWN", "DAC_OVERRIDE", "DAC_READ_SEARCH", "FOWNER", "FSETID", "KILL", "SETGID", "SETUID",
+    "SETPCAP", "LINUX_IMMUTABLE", "NET_BIND_SERVICE", "NET_BROADCAST", "NET_ADMIN", "NET_RAW",
+    "IPC_LOCK", "IPC_OWNER", "SYS_MODULE", "SYS_RAWIO", "SYS_CHROOT", "SYS_PTRACE", "SYS_PACCT",
+    "SYS_ADMIN", "SYS_BOOT", "SYS_NICE", "SYS_RESOURCE", "SYS_TIME", "SYS_TTY_CONFIG", "MKNOD",
+    "LEASE", "AUDIT_WRITE", "AUDIT_CONTROL", "SETFCAP", "MAC_OVERRIDE", "MAC_ADMIN", "SYSLOG",
+    "WAKE_ALARM", "BLOCK_SUSPEND", "AUDIT_READ", "PERFMON", "BPF", "CHECKPOINT_RESTORE",
+];
+
+pub fn validate_capabilities(caps: &[String]) -> Result<()> {
+    for cap in caps {
+        if !KNOWN_CAPABILITIES.contains(&cap.as_str()) {
+            return Err(PwnenvError::InvalidCapability(cap.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// The `restart:` values `docker compose` accepts.
+const RESTART_POLICIES: &[&str] = &["no", "on-failure", "always", "unless-stopped"];
+
+pub fn validate_restart_policy(restart_policy: &str) -> Result<()> {
+    if !RESTART_POLICIES.contains(&restart_policy) {
+        return Err(PwnenvError::InvalidRestartPolicy(restart_policy.to_string()));
+    }
+    Ok(())
+}
+
+/// Checks `container_user` looks like something docker's `user:` would
+/// actually accept — `name`, `uid`, `name:group`, or `uid:gid` — before
+/// it ends up verbatim in `docker-compose.yml` and fails opaquely mid-`up`.
+/// This is a syntax check only; whether the name/uid actually exists in
+/// the image is on the user, same as a typo'd `shell` only surfaces once
+/// `enter` probes for it.
+pub fn validate_container_user(container_user: &str) -> Result<()> {
+    let is_valid_part = |part: &str| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+    let valid = match container_user.split_once(':') {
+        Some((user, group)) => is_valid_part(user) && is_valid_part(group),
+        None => is_valid_part(container_user),
+    };
+    if !valid {
+        return Err(PwnenvError::InvalidContainerUser(container_user.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_labels() -> Labels {
+        Labels::new("chall", &Config::default(), Path::new("/chall"))
+    }
+
+    #[test]
+    fn ssh_agent_forwarding_mounts_and_sets_env() {
+        std::env::set_var("SSH_AUTH_SOCK", "/tmp/ssh-agent.sock");
+        let config = Config {
+            forward_ssh_agent: true,
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("/tmp/ssh-agent.sock:/tmp/ssh-agent.sock"));
+        assert!(compose.contains("SSH_AUTH_SOCK=/tmp/ssh-agent.sock"));
+        std::env::remove_var("SSH_AUTH_SOCK");
+    }
+
+    #[test]
+    fn relative_workspace_dir_resolves_against_host_dir() {
+        let config = Config {
+            workspace_dir: Some("chall".to_string()),
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/home/user/ctf"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("/home/user/ctf/chall:/workspace"));
+    }
+
+    #[test]
+    fn absolute_workspace_dir_is_used_as_is() {
+        let config = Config {
+            workspace_dir: Some("/srv/chall".to_string()),
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/home/user/ctf"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("/srv/chall:/workspace"));
+    }
+
+    #[test]
+    fn shm_size_is_rendered_when_set() {
+        let config = Config {
+            shm_size: Some("256m".to_string()),
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("shm_size: 256m"));
+    }
+
+    #[test]
+    fn extra_ports_are_rendered_alongside_forwarded_port() {
+        let config = Config {
+            forwarded_port: Some(1337),
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[(8080, 80)], &test_labels()).unwrap();
+        assert!(compose.contains("\"1337:1337\""));
+        assert!(compose.contains("\"8080:80\""));
+    }
+
+    #[test]
+    fn extra_ports_alone_still_render_a_ports_block() {
+        let compose = render_compose(&Config::default(), "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[(9000, 9000)], &test_labels()).unwrap();
+        assert!(compose.contains("\"9000:9000\""));
+    }
+
+    #[test]
+    fn ssh_agent_forwarding_errors_without_env_var() {
+        std::env::remove_var("SSH_AUTH_SOCK");
+        let config = Config {
+            forward_ssh_agent: true,
+            ..Config::default()
+        };
+        assert!(render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).is_err());
+    }
+
+    #[test]
+    fn extra_mounts_are_rendered_as_volumes() {
+        let config = Config::default();
+        let extra = vec![Mount {
+            host: std::path::PathBuf::from("/host/common"),
+            container: "/common".to_string(),
+        }];
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &extra, &[], &test_labels()).unwrap();
+        assert!(compose.contains("/host/common:/common"));
+    }
+
+    #[test]
+    fn dns_servers_are_rendered() {
+        let config = Config {
+            dns: vec!["1.1.1.1".to_string(), "8.8.8.8".to_string()],
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("    dns:"));
+        assert!(compose.contains("      - 1.1.1.1"));
+        assert!(compose.contains("      - 8.8.8.8"));
+    }
+
+    #[test]
+    fn labels_are_rendered() {
+        let config = Config::default();
+        let labels = Labels::new("chall", &config, Path::new("/chall"));
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &labels).unwrap();
+        assert!(compose.contains("    labels:"));
+        assert!(compose.contains("dev.pwnenv.env_name: \"chall\""));
+        assert!(compose.contains("dev.pwnenv.host_dir: \"/chall\""));
+    }
+
+    #[test]
+    fn invalid_dns_server_is_rejected() {
+        let config = Config {
+            dns: vec!["not-an-ip".to_string()],
+            ..Config::default()
+        };
+        assert!(render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).is_err());
+    }
+
+    #[test]
+    fn default_restart_policy_is_omitted() {
+        let compose = render_compose(&Config::default(), "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(!compose.contains("restart:"));
+    }
+
+    #[test]
+    fn restart_policy_is_rendered_when_set() {
+        let config = Config {
+            restart_policy: "unless-stopped".to_string(),
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("    restart: unless-stopped"));
+    }
+
+    #[test]
+    fn invalid_restart_policy_is_rejected() {
+        let config = Config {
+            restart_policy: "sometimes".to_string(),
+            ..Config::default()
+        };
+        assert!(render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).is_err());
+    }
+
+    #[test]
+    fn default_cap_add_is_sys_ptrace() {
+        let config = Config { privileged: false, ..Config::default() };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("    cap_add:"));
+        assert!(compose.contains("      - SYS_PTRACE"));
+    }
+
+    #[test]
+    fn custom_capabilities_are_rendered() {
+        let config = Config {
+            privileged: false,
+            cap_add: vec!["SYS_PTRACE".to_string(), "SYS_ADMIN".to_string(), "NET_ADMIN".to_string()],
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("      - SYS_ADMIN"));
+        assert!(compose.contains("      - NET_ADMIN"));
+    }
+
+    #[test]
+    fn invalid_capability_is_rejected() {
+        let config = Config {
+            privileged: false,
+            cap_add: vec!["SYS_PTRAC".to_string()],
+            ..Config::default()
+        };
+        assert!(render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).is_err());
+    }
+
+    #[test]
+    fn container_user_is_omitted_by_default() {
+        let compose = render_compose(&Config::default(), "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(!compose.contains("user:"));
+    }
+
+    #[test]
+    fn container_user_renders_as_a_user_line() {
+        let config = Config {
+            container_user: Some("root".to_string()),
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("user: \"root\"\n"));
+    }
+
+    #[test]
+    fn container_user_accepts_uid_gid_form() {
+        assert!(validate_container_user("1000:1000").is_ok());
+    }
+
+    #[test]
+    fn invalid_container_user_is_rejected() {
+        let config = Config {
+            container_user: Some("root:".to_string()),
+            ..Config::default()
+        };
+        assert!(render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).is_err());
+    }
+
+    #[test]
+    fn version_key_is_omitted_by_default() {
+        let compose = render_compose(&Config::default(), "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(!compose.starts_with("version:"));
+    }
+
+    #[test]
+    fn version_key_is_rendered_when_set() {
+        let config = Config {
+            compose_version: Some("3.9".to_string()),
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.starts_with("version: \"3.9\"\n"));
+    }
+
+    #[test]
+    fn service_names_lists_the_one_rendered_service() {
+        let compose = render_compose(&Config::default(), "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert_eq!(service_names(&compose).unwrap(), vec!["chall".to_string()]);
+    }
+
+    #[test]
+    fn service_names_on_unparseable_yaml_is_an_error() {
+        assert!(service_names("not: [valid").is_err());
+    }
+
+    #[test]
+    fn cap_add_is_ignored_when_privileged() {
+        let config = Config {
+            privileged: true,
+            cap_add: vec!["not-a-real-capability".to_string()],
+            ..Config::default()
+        };
+        let compose = render_compose(&config, "pwnenv-env", "chall", Path::new("/tmp/pwnenv-test"), None, &[], &[], &test_labels()).unwrap();
+        assert!(compose.contains("privileged: true"));
+        assert!(!compose.contains("cap_add"));
+    }
+}
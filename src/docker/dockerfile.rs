@@ -0,0 +1,488 @@
+use std::fmt::Write as _;
+
+use crate::config::{Config, ToolConfig};
+
+/// Directory inside the image where guarded tool installs drop a marker
+/// file (one empty file per tool name) when `fail_fast` is disabled.
+pub const FAILED_MARKER_DIR: &str = "/opt/pwnenv/failed";
+
+/// `build --only <tool>`'s request: force `tool`'s own `RUN` layer (and
+/// everything docker builds after it) to miss cache even when its script
+/// text is unchanged, by making that layer depend on an `ARG` whose
+/// value is different every time — docker caches a `RUN` by its
+/// substituted command text, so a changing `nonce` is always a miss,
+/// regardless of whether the script itself changed.
+pub struct CacheBust<'a> {
+    pub tool: &'a str,
+    pub nonce: &'a str,
+}
+
+/// Renders the full Dockerfile for `config`.
+///
+/// Each tool's `script` is a list of raw Dockerfile instruction lines.
+/// Consecutive `RUN` lines are batched into a single modern heredoc `RUN
+/// <<EOF` block (one layer per batch instead of one per line) and legacy
+/// `ENV KEY value` lines are normalized to `ENV KEY=value`. When
+/// `config.fail_fast` is false, each tool's `RUN` batch is wrapped in a
+/// guard that records the failure under [`FAILED_MARKER_DIR`] instead of
+/// failing the build, so later tools still get a chance to install.
+///
+/// Tools marked `build_only` run in a throwaway `builder` stage instead,
+/// and only their `artifacts` are copied into the final image, so the
+/// compiler/toolchain they needed never ends up in the shipped image.
+///
+/// `include_programs` bakes the build context's `programs/` directory
+/// (the `init`-copied snapshot of `config.programs_dir`) into the image
+/// at [`crate::programs::PROGRAMS_CONTAINER_PATH`] via `COPY`; pass
+/// `false` when `init --no-copy` was used, since that directory won't
+/// exist in the build context. When `config.programs_include` is set,
+/// that snapshot (and so this `COPY`) only ever contained the matching
+/// files in the first place — see [`crate::programs::CopyFilter`] — so
+/// there's nothing extra to restrict here.
+///
+/// Every path in `config.bake` is also `COPY`'d in, from the build
+/// context's `bake/<path>` (the `init`-copied snapshot, see
+/// [`crate::bake`]) to `/workspace/<path>` in the image — unconditionally,
+/// since `bake` has no `--no-copy`-style override. Note that
+/// `workspace_dir`'s bind mount (rendered into `docker-compose.yml`, not
+/// here) lands on top of `/workspace` at container start and shadows
+/// whatever was baked at the same path, unless `workspace_overlay` is set.
+///
+/// `config.apt_sources`' lines (see [`render_apt_sources`]) are written to
+/// `/etc/apt/sources.list.d/pwnenv.list` right after each stage's `ARG`
+/// block, ahead of every tool, so a custom mirror or extra repo is in
+/// place before that stage's first `apt update`.
+///
+/// Every key in `config.build_args` is declared as an `ARG` right after
+/// each `FROM` line, so it's in scope in both the `builder` stage and the
+/// final one (docker scopes `ARG` per-stage). A `$` in a value is escaped
+/// as `$$`, since otherwise docker would try to expand it as a variable
+/// reference inside the `ARG` default itself.
+///
+/// `cache_bust` implements `build --only <tool>` (see [`CacheBust`]):
+/// when set and its `tool` is present, that tool's `RUN` layer (and
+/// everything rendered after it) always misses docker's build cache,
+/// while every earlier layer is untouched and still cacheable as usual.
+///
+/// Finally, [`crate::entrypoint::render_entrypoint`] writes a generated
+/// `/usr/local/bin/pwnenv-entrypoint.sh` (running `config.on_start`'s
+/// lines before handing off to `tini`) and sets it as the `ENTRYPOINT`,
+/// so the container's liveness doesn't depend on the base image's own
+/// `CMD`. Re-rendered from scratch on every `build`, so an `on_start`
+/// change always reaches the image on the next build without any
+/// separate regeneration step.
+///
+/// When `config.secrets` is non-empty, a `# syntax=docker/dockerfile:1`
+/// directive is written as the very first line — required for
+/// `--mount=type=secret` to parse on older BuildKit frontends — and every
+/// tool that lists one of those keys in its own `secrets` gets
+/// `--mount=type=secret,id=<key>` added to its `RUN` line, so the secret
+/// is available at `/run/secrets/<key>` for that command only and never
+/// written into a layer. [`crate::commands::build::build_image`] is
+/// responsible for actually passing `--secret id=<key>,src=<path>` (and
+/// setting `DOCKER_BUILDKIT=1`) to `docker build`.
+pub fn render_dockerfile(config: &Config, include_programs: bool, cache_bust: Option<CacheBust>) -> String {
+    let mut out = String::new();
+    let (build_tools, final_tools): (Vec<_>, Vec<_>) =
+        config.tools.iter().partition(|tool| tool.build_only);
+
+    if !config.secrets.is_empty() {
+        let _ = writeln!(out, "# syntax=docker/dockerfile:1");
+    }
+
+    if !build_tools.is_empty() {
+        let _ = writeln!(out, "FROM {} AS builder", config.base_image);
+        render_build_args(config, &mut out);
+        if let Some(cache_bust) = &cache_bust {
+            let _ = writeln!(out, "ARG PWNENV_CACHEBUST={}", cache_bust.nonce);
+        }
+        render_apt_sources(config, &mut out);
+        for tool in &build_tools {
+            out.push_str(&render_tool(tool, config.fail_fast, cache_bust.as_ref()));
+        }
+    }
+
+    let _ = writeln!(out, "FROM {}", config.base_image);
+    render_build_args(config, &mut out);
+    if let Some(cache_bust) = &cache_bust {
+        let _ = writeln!(out, "ARG PWNENV_CACHEBUST={}", cache_bust.nonce);
+    }
+
+    render_apt_sources(config, &mut out);
+
+    if !config.fail_fast {
+        let _ = writeln!(out, "RUN mkdir -p {FAILED_MARKER_DIR}");
+    }
+
+    if include_programs {
+        let _ = writeln!(
+            out,
+            "COPY programs {}",
+            crate::programs::PROGRAMS_CONTAINER_PATH
+        );
+    }
+
+    for relative in &config.bake {
+        let _ = writeln!(out, "COPY bake/{relative} /workspace/{relative}");
+    }
+
+    for tool in &build_tools {
+        for artifact in &tool.artifacts {
+            let _ = writeln!(out, "COPY --from=builder {artifact} {artifact}");
+        }
+    }
+
+    for tool in &final_tools {
+        out.push_str(&render_tool(tool, config.fail_fast, cache_bust.as_ref()));
+    }
+
+    crate::entrypoint::render_entrypoint(config, &mut out);
+
+    out
+}
+
+fn render_build_args(config: &Config, out: &mut String) {
+    for (key, value) in &config.build_args {
+        let escaped = value.replace('$', "$$");
+        let _ = writeln!(out, "ARG {key}={escaped}");
+    }
+}
+
+/// Writes `config.apt_sources`' lines to
+/// `/etc/apt/sources.list.d/pwnenv.list`, right at the top of the stage
+/// and before any tool script, so a custom mirror or extra repo is in
+/// place for every tool's own `apt update`, not just the first one's.
+/// The outer heredoc fence is quoted (`<<'EOF'`) for the same reason as
+/// [`crate::entrypoint::render_entrypoint`]'s: a `$` in a source line
+/// (e.g. a signed-by keyring path) shouldn't be expanded as a build arg
+/// before the inner `cat` heredoc ever sees it.
+fn render_apt_sources(config: &Config, out: &mut String) {
+    if config.apt_sources.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "RUN <<'EOF'");
+    let _ = writeln!(out, "mkdir -p /etc/apt/sources.list.d");
+    let _ = writeln!(out, "cat > /etc/apt/sources.list.d/pwnenv.list <<'SOURCES'");
+    for line in &config.apt_sources {
+        let _ = writeln!(out, "{line}");
+    }
+    let _ = writeln!(out, "SOURCES");
+    let _ = writeln!(out, "EOF");
+}
+
+fn render_tool(tool: &ToolConfig, fail_fast: bool, cache_bust: Option<&CacheBust>) -> String {
+    let mut out = String::new();
+    let mut run_batch: Vec<String> = Vec::new();
+
+    if cache_bust.is_some_and(|cache_bust| cache_bust.tool == tool.name) {
+        run_batch.push(": \"$PWNENV_CACHEBUST\"".to_string());
+    }
+
+    for line in &tool.script {
+        match run_body(line) {
+            Some(body) => run_batch.push(body.to_string()),
+            None => {
+                flush_run_batch(&mut run_batch, tool, fail_fast, &mut out);
+                let _ = writeln!(out, "{}", normalize_line(line));
+            }
+        }
+    }
+    flush_run_batch(&mut run_batch, tool, fail_fast, &mut out);
+
+    out
+}
+
+fn flush_run_batch(batch: &mut Vec<String>, tool: &ToolConfig, fail_fast: bool, out: &mut String) {
+    if batch.is_empty() {
+        return;
+    }
+
+    let mounts = tool
+        .secrets
+        .iter()
+        .map(|id| format!(" --mount=type=secret,id={id}"))
+        .collect::<String>();
+    let _ = writeln!(out, "RUN{mounts} <<EOF");
+    if fail_fast {
+        for command in batch.iter() {
+            let _ = writeln!(out, "{command}");
+        }
+    } else {
+        let body = batch.join(" && ");
+        let _ = writeln!(
+            out,
+            "({body}) || (echo \"pwnenv: tool '{name}' failed to install\" >&2 && touch {dir}/{name})",
+            name = tool.name,
+            dir = FAILED_MARKER_DIR,
+        );
+    }
+    let _ = writeln!(out, "EOF");
+
+    batch.clear();
+}
+
+/// Strips a leading `RUN ` (case-insensitive) from a tool script line,
+/// returning the shell command that follows, or `None` if the line is
+/// some other instruction.
+fn run_body(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let rest = trimmed
+        .strip_prefix("RUN ")
+        .or_else(|| trimmed.strip_prefix("run "))?;
+    Some(rest.trim())
+}
+
+/// Rewrites legacy `ENV KEY value` into modern `ENV KEY=value`; every
+/// other instruction is returned unchanged.
+fn normalize_line(line: &str) -> String {
+    let trimmed = line.trim();
+    let Some(rest) = trimmed
+        .strip_prefix("ENV ")
+        .or_else(|| trimmed.strip_prefix("env "))
+    else {
+        return line.to_string();
+    };
+
+    let mut parts = rest.splitn(2, char::is_whitespace);
+    let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+        return line.to_string();
+    };
+
+    if key.contains('=') {
+        return line.to_string();
+    }
+    format!("ENV {key}={}", value.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, script: &[&str]) -> ToolConfig {
+        ToolConfig {
+            name: name.to_string(),
+            script: script.iter().map(|s| s.to_string()).collect(),
+            build_only: false,
+            append: false,
+            artifacts: Vec::new(),
+            verify: Vec::new(),
+            secrets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn fail_fast_batches_consecutive_runs_into_one_heredoc() {
+        let config = Config {
+            tools: vec![tool("gdb", &["RUN apt-get update", "RUN apt-get install -y gdb"])],
+            fail_fast: true,
+            ..Config::default()
+        };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert_eq!(dockerfile.matches("RUN <<EOF").count(), 1);
+        assert!(dockerfile.contains("apt-get update\n"));
+        assert!(dockerfile.contains("apt-get install -y gdb\n"));
+        assert!(!dockerfile.contains("touch"));
+    }
+
+    #[test]
+    fn non_fail_fast_guards_the_whole_batch_and_touches_marker() {
+        let config = Config {
+            tools: vec![tool(
+                "pwndbg",
+                &["RUN apt-get update", "RUN apt-get install -y git", "ENV PWNDBG legacy"],
+            )],
+            fail_fast: false,
+            ..Config::default()
+        };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert!(dockerfile.contains(&format!("mkdir -p {FAILED_MARKER_DIR}")));
+        assert!(dockerfile.contains("(apt-get update && apt-get install -y git) || (echo \"pwnenv: tool 'pwndbg' failed to install\""));
+        assert!(dockerfile.contains(&format!("touch {FAILED_MARKER_DIR}/pwndbg")));
+        assert!(dockerfile.contains("ENV PWNDBG=legacy\n"));
+    }
+
+    #[test]
+    fn cargo_and_gem_built_tools_only_ship_their_artifacts_to_the_final_stage() {
+        let config = Config { tools: crate::tool_presets::lookup("pwn").unwrap(), ..Config::default() };
+        let dockerfile = render_dockerfile(&config, false, None);
+
+        let (builder_stage, final_stage) = dockerfile.split_once("\nFROM ").unwrap();
+        assert!(builder_stage.contains("AS builder"));
+        assert!(builder_stage.contains("cargo install pwninit"));
+        assert!(builder_stage.contains("gem install --no-document"));
+
+        // the rust/ruby-dev toolchains stay in the builder stage...
+        assert!(!final_stage.contains("apt-get install -y cargo"));
+        assert!(!final_stage.contains("ruby-dev"));
+        // ...while the produced artifacts and the (separate, lightweight)
+        // ruby runtime needed to execute `one_gadget`'s script do ship.
+        assert!(final_stage.contains("COPY --from=builder /root/.cargo/bin/pwninit /root/.cargo/bin/pwninit"));
+        assert!(final_stage.contains("COPY --from=builder /usr/local/bin/one_gadget /usr/local/bin/one_gadget"));
+        assert!(final_stage.contains("COPY --from=builder /var/lib/gems /var/lib/gems"));
+        assert!(final_stage.contains("apt-get install -y ruby\n"));
+    }
+
+    #[test]
+    fn multi_package_apt_installs_in_one_tool_are_batched_into_one_run() {
+        let seccomp = crate::tool_presets::lookup("sandboxing")
+            .unwrap()
+            .into_iter()
+            .find(|tool| tool.name == "seccomp")
+            .unwrap();
+        let config = Config {
+            tools: vec![seccomp],
+            fail_fast: true,
+            ..Config::default()
+        };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert_eq!(dockerfile.matches("RUN <<EOF").count(), 1);
+        assert!(dockerfile.contains("apt-get install -y libseccomp2\n"));
+        assert!(dockerfile.contains("apt-get install -y libseccomp-dev\n"));
+        assert!(dockerfile.contains("apt-get install -y seccomp-tools\n"));
+    }
+
+    #[test]
+    fn programs_are_copied_into_the_image_when_included() {
+        let config = Config::default();
+        assert!(render_dockerfile(&config, true, None).contains("COPY programs /programs"));
+        assert!(!render_dockerfile(&config, false, None).contains("COPY programs"));
+    }
+
+    #[test]
+    fn bake_paths_are_copied_to_their_workspace_path() {
+        let config = Config {
+            bake: vec!["idb/challenge.i64".to_string(), "rootfs.img".to_string()],
+            ..Config::default()
+        };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert!(dockerfile.contains("COPY bake/idb/challenge.i64 /workspace/idb/challenge.i64"));
+        assert!(dockerfile.contains("COPY bake/rootfs.img /workspace/rootfs.img"));
+    }
+
+    #[test]
+    fn no_bake_paths_means_no_bake_copy_lines() {
+        let dockerfile = render_dockerfile(&Config::default(), false, None);
+        assert!(!dockerfile.contains("COPY bake/"));
+    }
+
+    #[test]
+    fn apt_sources_are_written_before_the_first_tool() {
+        let config = Config {
+            apt_sources: vec!["deb https://mirror.example/ubuntu jammy main".to_string()],
+            tools: vec![tool("gdb", &["RUN apt-get update && apt-get install -y gdb"])],
+            fail_fast: true,
+            ..Config::default()
+        };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert!(dockerfile.contains("cat > /etc/apt/sources.list.d/pwnenv.list <<'SOURCES'"));
+        assert!(dockerfile.contains("deb https://mirror.example/ubuntu jammy main\n"));
+        let sources_pos = dockerfile.find("pwnenv.list").unwrap();
+        let tool_pos = dockerfile.find("apt-get install -y gdb").unwrap();
+        assert!(sources_pos < tool_pos);
+    }
+
+    #[test]
+    fn no_apt_sources_means_no_sources_list_write() {
+        let dockerfile = render_dockerfile(&Config::default(), false, None);
+        assert!(!dockerfile.contains("sources.list.d"));
+    }
+
+    #[test]
+    fn build_args_are_declared_after_from() {
+        let mut build_args = std::collections::BTreeMap::new();
+        build_args.insert("PWNDBG_REF".to_string(), "2024.02.14".to_string());
+        let config = Config { build_args, ..Config::default() };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert!(dockerfile.contains("ARG PWNDBG_REF=2024.02.14\n"));
+    }
+
+    #[test]
+    fn build_args_are_declared_in_both_stages() {
+        let mut build_args = std::collections::BTreeMap::new();
+        build_args.insert("MIRROR".to_string(), "https://mirror.example".to_string());
+        let config = Config {
+            build_args,
+            tools: vec![ToolConfig {
+                name: "glibc".to_string(),
+                script: vec!["RUN build-glibc".to_string()],
+                build_only: true,
+                append: false,
+                artifacts: vec!["/build/libc.so.6".to_string()],
+                verify: Vec::new(),
+                secrets: Vec::new(),
+            }],
+            ..Config::default()
+        };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert_eq!(dockerfile.matches("ARG MIRROR=https://mirror.example").count(), 2);
+    }
+
+    #[test]
+    fn dollar_signs_in_build_arg_values_are_escaped() {
+        let mut build_args = std::collections::BTreeMap::new();
+        build_args.insert("PRICE".to_string(), "$5".to_string());
+        let config = Config { build_args, ..Config::default() };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert!(dockerfile.contains("ARG PRICE=$$5\n"));
+    }
+
+    #[test]
+    fn cache_bust_adds_an_arg_and_a_no_op_referencing_it_only_to_the_targeted_tool() {
+        let config = Config {
+            tools: vec![tool("gdb", &["RUN apt-get install -y gdb"]), tool("pwndbg", &["RUN git clone pwndbg"])],
+            fail_fast: true,
+            ..Config::default()
+        };
+        let dockerfile =
+            render_dockerfile(&config, false, Some(CacheBust { tool: "pwndbg", nonce: "12345" }));
+        assert!(dockerfile.contains("ARG PWNENV_CACHEBUST=12345"));
+        let gdb_run = dockerfile.split("RUN <<EOF").nth(1).unwrap();
+        assert!(!gdb_run.contains("PWNENV_CACHEBUST"));
+        let pwndbg_run = dockerfile.split("RUN <<EOF").nth(2).unwrap();
+        assert!(pwndbg_run.contains(": \"$PWNENV_CACHEBUST\""));
+    }
+
+    #[test]
+    fn no_cache_bust_means_no_arg_at_all() {
+        let config = Config { tools: vec![tool("gdb", &["RUN apt-get install -y gdb"])], ..Config::default() };
+        assert!(!render_dockerfile(&config, false, None).contains("PWNENV_CACHEBUST"));
+    }
+
+    #[test]
+    fn secret_mount_is_added_only_to_the_tool_that_declares_it() {
+        let mut secret_tool = tool("git-clone-private", &["RUN git clone https://example.com/private.git"]);
+        secret_tool.secrets = vec!["deploy_token".to_string()];
+        let mut secrets = std::collections::BTreeMap::new();
+        secrets.insert("deploy_token".to_string(), std::path::PathBuf::from("/run/deploy-token"));
+        let config = Config {
+            tools: vec![tool("gdb", &["RUN apt-get install -y gdb"]), secret_tool],
+            fail_fast: true,
+            secrets,
+            ..Config::default()
+        };
+        let dockerfile = render_dockerfile(&config, false, None);
+        assert!(dockerfile.starts_with("# syntax=docker/dockerfile:1\n"));
+        let gdb_run = dockerfile.split("RUN <<EOF").nth(1).unwrap().split("\nEOF").next().unwrap();
+        assert!(!gdb_run.contains("--mount=type=secret"));
+        assert!(dockerfile.contains("RUN --mount=type=secret,id=deploy_token <<EOF"));
+    }
+
+    #[test]
+    fn no_secrets_means_no_syntax_directive() {
+        let config = Config { tools: vec![tool("gdb", &["RUN apt-get install -y gdb"])], ..Config::default() };
+        assert!(!render_dockerfile(&config, false, None).contains("syntax=docker/dockerfile"));
+    }
+
+    #[test]
+    fn entrypoint_is_always_appended() {
+        let dockerfile = render_dockerfile(&Config::default(), false, None);
+        assert!(dockerfile.contains("ENTRYPOINT [\"/usr/local/bin/pwnenv-entrypoint.sh\"]"));
+        assert!(dockerfile.ends_with("ENTRYPOINT [\"/usr/local/bin/pwnenv-entrypoint.sh\"]\n"));
+    }
+
+    #[test]
+    fn legacy_env_syntax_is_normalized() {
+        assert_eq!(normalize_line("ENV PATH $PATH:/opt/gdb"), "ENV PATH=$PATH:/opt/gdb");
+        assert_eq!(normalize_line("ENV PATH=/opt/gdb"), "ENV PATH=/opt/gdb");
+    }
+}
@@ -0,0 +1,303 @@
+use std::path::Path;
+
+use crate::config::Config;
+
+const KNOWN_INSTRUCTIONS: &[&str] = &[
+    "FROM", "RUN", "ENV", "ARG", "COPY", "ADD", "WORKDIR", "CMD", "ENTRYPOINT", "EXPOSE",
+    "VOLUME", "USER", "LABEL", "SHELL", "STOPSIGNAL", "ONBUILD", "HEALTHCHECK",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct LintFinding {
+    pub tool: String,
+    pub line_index: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl std::fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{level}: tool '{}', script line {}: {}",
+            self.tool, self.line_index, self.message
+        )
+    }
+}
+
+/// Validates every tool's Dockerfile instruction lines before they ever
+/// reach `docker build`, so mistakes point back at the offending tool and
+/// line instead of surfacing as an opaque `docker build` failure.
+///
+/// `build_context` is used to check that `COPY` sources actually exist.
+pub fn lint_tools(config: &Config, build_context: &Path) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+
+    for (line_index, line) in config.apt_sources.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !is_apt_source_line(trimmed) {
+            findings.push(finding(
+                "apt_sources".to_string(),
+                line_index,
+                Severity::Error,
+                format!("'{trimmed}' does not look like an apt source line; expected it to start with `deb` or `deb-src`"),
+            ));
+        }
+    }
+
+    for tool in &config.tools {
+        for key in &tool.secrets {
+            if !config.secrets.contains_key(key) {
+                findings.push(finding(
+                    tool.name.clone(),
+                    0,
+                    Severity::Error,
+                    format!("secret '{key}' is not declared in the top-level `secrets` map"),
+                ));
+            }
+        }
+
+        for (line_index, line) in tool.script.iter().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let instruction = trimmed
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .to_ascii_uppercase();
+
+            if !KNOWN_INSTRUCTIONS.contains(&instruction.as_str()) {
+                findings.push(finding(
+                    tool.name.clone(),
+                    line_index,
+                    Severity::Error,
+                    format!("'{instruction}' is not a known Dockerfile instruction"),
+                ));
+                continue;
+            }
+
+            match instruction.as_str() {
+                "FROM" => findings.push(finding(
+                    tool.name.clone(),
+                    line_index,
+                    Severity::Error,
+                    "FROM must not appear in a tool script; set base_image instead".to_string(),
+                )),
+                "COPY" | "ADD" => {
+                    if let Some(source) = copy_source(trimmed) {
+                        if !build_context.join(source).exists() {
+                            findings.push(finding(
+                                tool.name.clone(),
+                                line_index,
+                                Severity::Error,
+                                format!("{instruction} source '{source}' does not exist in the build context"),
+                            ));
+                        }
+                    }
+                }
+                "WORKDIR" => {
+                    if let Some(arg) = trimmed.split_whitespace().nth(1) {
+                        if !arg.starts_with('/') {
+                            findings.push(finding(
+                                tool.name.clone(),
+                                line_index,
+                                Severity::Error,
+                                format!("WORKDIR argument '{arg}' must be an absolute path"),
+                            ));
+                        }
+                    }
+                }
+                "ENV" if is_legacy_env_syntax(trimmed) => {
+                    findings.push(finding(
+                        tool.name.clone(),
+                        line_index,
+                        Severity::Warning,
+                        "legacy `ENV KEY value` syntax; newer docker warns on this, prefer `ENV KEY=value`".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    findings
+}
+
+fn finding(tool: String, line_index: usize, severity: Severity, message: String) -> LintFinding {
+    LintFinding {
+        tool,
+        line_index,
+        severity,
+        message,
+    }
+}
+
+/// `COPY <source> <dest>` (ignoring `--from=` / `--chown=` flags), taking
+/// only the first source.
+fn copy_source(line: &str) -> Option<&str> {
+    line.split_whitespace()
+        .skip(1)
+        .find(|token| !token.starts_with("--"))
+}
+
+/// A (very loose) check that `line` looks like a `sources.list` entry:
+/// `deb`/`deb-src`, optionally followed by an `[options]` block, then a
+/// URI and at least a suite.
+fn is_apt_source_line(line: &str) -> bool {
+    let mut words = line.split_whitespace();
+    let Some(first) = words.next() else {
+        return false;
+    };
+    if first != "deb" && first != "deb-src" {
+        return false;
+    }
+    let mut rest: Vec<&str> = words.collect();
+    if rest.first().is_some_and(|word| word.starts_with('[')) {
+        rest.remove(0);
+    }
+    rest.len() >= 2
+}
+
+/// `ENV KEY value` without an `=` right after the key is the legacy form;
+/// `ENV KEY=value` (and `ENV KEY=value KEY2=value2`) is the modern one.
+fn is_legacy_env_syntax(line: &str) -> bool {
+    match line.split_whitespace().nth(1) {
+        Some(first_pair) => !first_pair.contains('='),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ToolConfig;
+
+    fn config_with(script: &[&str]) -> Config {
+        Config {
+            tools: vec![ToolConfig {
+                name: "gdb".to_string(),
+                script: script.iter().map(|s| s.to_string()).collect(),
+                build_only: false,
+                append: false,
+                artifacts: Vec::new(),
+                verify: Vec::new(),
+                secrets: Vec::new(),
+            }],
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn malformed_apt_source_is_an_error() {
+        let config = Config { apt_sources: vec!["not-a-source-line".to_string()], ..Config::default() };
+        let findings = lint_tools(&config, Path::new("."));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn well_formed_apt_source_is_clean() {
+        let config = Config {
+            apt_sources: vec!["deb [arch=amd64] https://mirror.example/ubuntu jammy main".to_string()],
+            ..Config::default()
+        };
+        assert!(lint_tools(&config, Path::new(".")).is_empty());
+    }
+
+    #[test]
+    fn unknown_instruction_is_an_error() {
+        let config = config_with(&["FOO bar"]);
+        let findings = lint_tools(&config, Path::new("."));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn legacy_env_syntax_is_a_warning() {
+        let config = config_with(&["ENV PATH $PATH:/opt/gdb"]);
+        let findings = lint_tools(&config, Path::new("."));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn modern_env_syntax_is_clean() {
+        let config = config_with(&["ENV PATH=$PATH:/opt/gdb"]);
+        assert!(lint_tools(&config, Path::new(".")).is_empty());
+    }
+
+    #[test]
+    fn relative_workdir_is_an_error() {
+        let config = config_with(&["WORKDIR opt/gdb"]);
+        let findings = lint_tools(&config, Path::new("."));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn missing_copy_source_is_an_error() {
+        let config = config_with(&["COPY does-not-exist.sh /opt/gdb/install.sh"]);
+        let findings = lint_tools(&config, Path::new("."));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn known_instructions_are_accepted() {
+        let config = config_with(&[
+            "RUN apt-get install -y gdb",
+            "ARG GDB_VERSION=1.0",
+            "ENV GDB_VERSION=1.0",
+            "WORKDIR /opt/gdb",
+        ]);
+        assert!(lint_tools(&config, Path::new(".")).is_empty());
+    }
+
+    #[test]
+    fn secret_not_declared_in_the_top_level_map_is_an_error() {
+        let mut config = config_with(&["RUN git clone https://example.com/private.git"]);
+        config.tools[0].secrets = vec!["deploy_token".to_string()];
+        let findings = lint_tools(&config, Path::new("."));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert!(findings[0].message.contains("deploy_token"));
+    }
+
+    #[test]
+    fn secret_declared_in_the_top_level_map_is_clean() {
+        let mut config = config_with(&["RUN git clone https://example.com/private.git"]);
+        config.tools[0].secrets = vec!["deploy_token".to_string()];
+        config.secrets.insert("deploy_token".to_string(), std::path::PathBuf::from("/run/deploy-token"));
+        assert!(lint_tools(&config, Path::new(".")).is_empty());
+    }
+
+    #[test]
+    fn missing_run_prefix_is_an_error_naming_the_tool_and_line() {
+        // The classic authoring mistake this check exists for: a tool
+        // author writes `apt install foo` instead of `RUN apt install
+        // foo`, and the first word ("apt") isn't a Dockerfile instruction.
+        let config = config_with(&["apt install gdb"]);
+        let findings = lint_tools(&config, Path::new("."));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings[0].tool, "gdb");
+        assert_eq!(findings[0].line_index, 0);
+        assert!(findings[0].to_string().contains("gdb"));
+    }
+}
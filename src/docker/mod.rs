@@ -0,0 +1,6 @@
+pub mod compose;
+pub mod dockerfile;
+pub mod lint;
+
+pub use compose::render_compose;
+pub use dockerfile::render_dockerfile;
@@ -0,0 +1,86 @@
+use std::fmt::Write as _;
+
+use crate::config::{Config, ToolConfig};
+
+/// Where the generated entrypoint script lands in the image.
+pub const ENTRYPOINT_CONTAINER_PATH: &str = "/usr/local/bin/pwnenv-entrypoint.sh";
+
+/// Installs `tini`, unconditionally — every image needs a real PID 1 to
+/// reap zombies and forward signals now that [`render_entrypoint`] makes
+/// the entrypoint script (not the base image's own `CMD`) what actually
+/// runs. Prepended by [`crate::config::Config::load`] ahead of every
+/// user tool, same as [`crate::arch::i386_tool`], since nothing else a
+/// tool's script does depends on it being present first.
+pub fn tini_tool() -> ToolConfig {
+    ToolConfig {
+        name: "pwnenv-tini".to_string(),
+        script: vec!["RUN apt-get update && apt-get install -y tini".to_string()],
+        build_only: false,
+        append: false,
+        artifacts: Vec::new(),
+        verify: vec!["tini --version".to_string()],
+        secrets: Vec::new(),
+    }
+}
+
+/// The `/usr/local/bin/pwnenv-entrypoint.sh` contents: `config.on_start`'s
+/// lines, in order, then `exec tini -- sleep infinity` so the container
+/// stays up under a real init process instead of whatever the base
+/// image's default `CMD` happens to do. With no `on_start` lines this is
+/// just the `tini` handoff — the same "stay up and reap zombies" behavior
+/// every environment gets regardless of config.
+pub fn render_entrypoint_script(config: &Config) -> String {
+    let mut out = String::from("#!/bin/sh\nset -e\n");
+    for line in &config.on_start {
+        let _ = writeln!(out, "{line}");
+    }
+    out.push_str("exec tini -- sleep infinity\n");
+    out
+}
+
+/// Appends the `RUN`/`ENTRYPOINT` instructions that write
+/// [`render_entrypoint_script`]'s output to [`ENTRYPOINT_CONTAINER_PATH`]
+/// and make it the image's entrypoint. The outer heredoc fence is quoted
+/// (`<<'EOF'`) so BuildKit doesn't try to expand a `$` in an `on_start`
+/// line as a build arg before the inner `cat` heredoc ever sees it.
+pub fn render_entrypoint(config: &Config, out: &mut String) {
+    let _ = writeln!(out, "RUN <<'EOF'");
+    let _ = writeln!(out, "cat > {ENTRYPOINT_CONTAINER_PATH} <<'SCRIPT'");
+    out.push_str(&render_entrypoint_script(config));
+    let _ = writeln!(out, "SCRIPT");
+    let _ = writeln!(out, "chmod +x {ENTRYPOINT_CONTAINER_PATH}");
+    let _ = writeln!(out, "EOF");
+    let _ = writeln!(out, "ENTRYPOINT [\"{ENTRYPOINT_CONTAINER_PATH}\"]");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_script_just_hands_off_to_tini() {
+        let script = render_entrypoint_script(&Config::default());
+        assert_eq!(script, "#!/bin/sh\nset -e\nexec tini -- sleep infinity\n");
+    }
+
+    #[test]
+    fn on_start_lines_run_before_the_tini_handoff() {
+        let config = Config {
+            on_start: vec!["service ssh start".to_string(), "echo ready".to_string()],
+            ..Config::default()
+        };
+        let script = render_entrypoint_script(&config);
+        let tini_pos = script.find("exec tini").unwrap();
+        assert!(script.find("service ssh start").unwrap() < tini_pos);
+        assert!(script.find("echo ready").unwrap() < tini_pos);
+    }
+
+    #[test]
+    fn dockerfile_snippet_writes_and_activates_the_entrypoint() {
+        let mut out = String::new();
+        render_entrypoint(&Config::default(), &mut out);
+        assert!(out.contains(&format!("cat > {ENTRYPOINT_CONTAINER_PATH} <<'SCRIPT'")));
+        assert!(out.contains(&format!("chmod +x {ENTRYPOINT_CONTAINER_PATH}")));
+        assert!(out.contains(&format!("ENTRYPOINT [\"{ENTRYPOINT_CONTAINER_PATH}\"]")));
+    }
+}
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Errors surfaced by pwnenv's own logic, as opposed to errors bubbled up
+/// from `docker`/`docker compose` (those are wrapped in `Docker` below with
+/// whatever context we have at the call site).
+#[derive(Debug, Error)]
+pub enum PwnenvError {
+    #[error("failed to read config at {path}: {source}")]
+    ConfigRead {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse config at {path}: {source}")]
+    ConfigParse {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+
+    #[error(
+        "config at {0} is empty or truncated (e.g. from a write interrupted mid-way); run `pwnenv config reset` to regenerate it with defaults"
+    )]
+    ConfigEmpty(PathBuf),
+
+    #[error("no environment named '{0}' was found")]
+    UnknownEnvironment(String),
+
+    #[error("docker command failed: {0}")]
+    Docker(String),
+
+    #[error("{var} is not set; cannot honor '{option}'")]
+    MissingEnvVar { var: String, option: String },
+
+    #[error("invalid mount '{0}': expected 'host:container'")]
+    InvalidMount(String),
+
+    #[error("invalid build arg '{0}': expected 'KEY=VALUE'")]
+    InvalidBuildArg(String),
+
+    #[error("invalid image tag '{0}': expected a non-empty docker tag with no whitespace or uppercase letters")]
+    InvalidImageTag(String),
+
+    #[error("host path '{path}' {reason}")]
+    InvalidHostPath { path: PathBuf, reason: String },
+
+    #[error("mount host path '{0}' does not exist")]
+    MountHostMissing(PathBuf),
+
+    #[error("mount container path '{path}' is used by more than one mount ({first} and {second})")]
+    MountCollision {
+        path: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("{} file(s) failed during programs copy:\n{}", .0.len(), .0.join("\n"))]
+    ProgramsCopyFailed(Vec<String>),
+
+    #[error("invalid DNS server '{0}': expected an IP address")]
+    InvalidDns(String),
+
+    #[error("invalid restart_policy '{0}': expected one of 'no', 'on-failure', 'always', 'unless-stopped'")]
+    InvalidRestartPolicy(String),
+
+    #[error("invalid cap_add entry '{0}': not a known Linux capability")]
+    InvalidCapability(String),
+
+    #[error("invalid container_user '{0}': expected a user name, uid, 'user:group', or 'uid:gid'")]
+    InvalidContainerUser(String),
+
+    #[error("invalid gdb_plugin '{value}': expected one of {expected}")]
+    InvalidGdbPlugin { value: String, expected: String },
+
+    #[error("no template named '{kind}'; expected one of {known}")]
+    UnknownTemplate { kind: String, known: String },
+
+    #[error("bake path '{0}' does not exist")]
+    BakePathMissing(PathBuf),
+
+    #[error(
+        "both --no-copy and --no-mount are set; the container would have no access to the challenge files"
+    )]
+    NoProgramsDelivery,
+
+    #[error(
+        "{path}'s generated_by ({config_version}) is newer than this pwnenv ({binary_version}); refusing to run a command that would rewrite it. Upgrade pwnenv or edit generated_by by hand if you're sure."
+    )]
+    ConfigNewerThanBinary {
+        path: PathBuf,
+        config_version: String,
+        binary_version: String,
+    },
+
+    #[error("invalid port mapping '{0}': expected 'host:container', both ports")]
+    InvalidPortMapping(String),
+
+    #[error("no shell hook for '{0}'; expected one of bash, zsh, fish")]
+    UnknownShell(String),
+
+    #[error(
+        "'{name}' is already registered to {existing}; refusing to adopt it for {attempted} too. \
+         Adopt under a different name with --name, or remove that environment's runtime dir first \
+         if {existing} no longer exists."
+    )]
+    AdoptConflict {
+        name: String,
+        existing: PathBuf,
+        attempted: PathBuf,
+    },
+
+    #[error(
+        "this directory ({0}) is inside pwnenv's own state directory; running `init` here would \
+         copy that tree into itself until the disk fills. cd into the actual challenge directory first."
+    )]
+    InitInsideRuntimeTree(PathBuf),
+
+    #[error("no service named '{service}' in this environment's compose config (available: {})", .available.join(", "))]
+    UnknownService { service: String, available: Vec<String> },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, PwnenvError>;
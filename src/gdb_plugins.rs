@@ -0,0 +1,183 @@
+use crate::config::{Config, ToolConfig};
+use crate::error::{PwnenvError, Result};
+
+/// Names `gdb_plugin` accepts. `"none"` (and an unset `gdb_plugin`) means
+/// plain upstream gdb with no plugin installed at all.
+pub const PLUGINS: &[&str] = &["pwndbg", "gef", "peda", "none"];
+
+/// Rejects a `gdb_plugin`/`init --gdb-plugin` value that isn't in
+/// [`PLUGINS`] instead of silently falling back to plain gdb the way
+/// [`plugin_tool`]'s catch-all arm does — a typo like `"pwndbeg"` should
+/// be an error, not a quietly-plain gdb.
+pub fn validate_plugin(plugin: &str) -> Result<()> {
+    if !PLUGINS.contains(&plugin) {
+        return Err(PwnenvError::InvalidGdbPlugin {
+            value: plugin.to_string(),
+            expected: PLUGINS.join(", "),
+        });
+    }
+    Ok(())
+}
+
+/// `(ARG name, pinned default)` for each plugin's install ref, declared
+/// as a `build_args` entry by [`Config::apply_gdb_plugin`] so a
+/// `pwnenv.yaml` that already sets the same key (or a `build
+/// --build-arg`, see [`crate::commands::build::parse_build_args`]) wins
+/// over the default without editing this file.
+fn pinned_ref(plugin: &str) -> Option<(&'static str, &'static str)> {
+    match plugin {
+        "pwndbg" => Some(("PWNDBG_REF", "2024.02.14")),
+        "gef" => Some(("GEF_REF", "2024.07.25")),
+        "peda" => Some(("PEDA_REF", "1.3")),
+        _ => None,
+    }
+}
+
+/// The single `"gdb"` tool for `plugin` (`"pwndbg"`/`"gef"`/`"peda"`),
+/// or plain gdb with no plugin for `"none"`/anything unrecognized —
+/// `apply_gdb_plugin` never installs more than one of these, regardless
+/// of what else is in `config.tools`.
+pub fn plugin_tool(plugin: &str) -> ToolConfig {
+    let script = match plugin {
+        "pwndbg" => vec![
+            "RUN apt-get update && apt-get install -y gdb git python3".to_string(),
+            "RUN git clone https://github.com/pwndbg/pwndbg /opt/pwndbg".to_string(),
+            "RUN cd /opt/pwndbg && git checkout \"$PWNDBG_REF\" && ./setup.sh".to_string(),
+        ],
+        "gef" => vec![
+            "RUN apt-get update && apt-get install -y gdb python3".to_string(),
+            "RUN wget -O /opt/.gef-${GEF_REF}.py \"https://github.com/hugsy/gef/raw/${GEF_REF}/gef.py\""
+                .to_string(),
+            "RUN echo \"source /opt/.gef-${GEF_REF}.py\" > /etc/gdb/gef.gdb".to_string(),
+        ],
+        "peda" => vec![
+            "RUN apt-get update && apt-get install -y gdb git python3".to_string(),
+            "RUN git clone https://github.com/longld/peda.git /opt/peda".to_string(),
+            "RUN cd /opt/peda && git checkout \"$PEDA_REF\"".to_string(),
+            "RUN echo \"source /opt/peda/peda.py\" > /etc/gdb/peda.gdb".to_string(),
+        ],
+        _ => vec!["RUN apt-get update && apt-get install -y gdb".to_string()],
+    };
+    let verify = match plugin {
+        "pwndbg" => vec!["gdb -q -batch -ex 'python import pwndbg'".to_string()],
+        "gef" => vec!["gdb -q -batch -x /etc/gdb/gef.gdb -ex 'python import gef'".to_string()],
+        "peda" => vec!["gdb -q -batch -x /etc/gdb/peda.gdb -ex 'python import peda'".to_string()],
+        _ => vec!["gdb --version".to_string()],
+    };
+
+    ToolConfig {
+        name: "gdb".to_string(),
+        script,
+        build_only: false,
+        append: false,
+        artifacts: Vec::new(),
+        verify,
+        secrets: Vec::new(),
+    }
+}
+
+/// Writes `/root/.gdbinit`: settings every environment wants regardless
+/// of `gdb_plugin` (`follow-fork-mode child`, so a `fork()`-ing challenge
+/// doesn't strand gdb on the parent), plus a `directory`/
+/// `debug-file-directory` line pointing at
+/// [`crate::libc_detect::debug_glibc_tool`]'s artifact when
+/// `build_debug_glibc` found one, then finally sources whichever
+/// plugin's own init file `plugin_tool` dropped under `/etc/gdb/` (a
+/// plain-gdb `"none"` has nothing to source).
+pub fn gdbinit_tool(config: &Config, plugin: &str) -> ToolConfig {
+    let mut lines = vec!["set follow-fork-mode child".to_string()];
+    if config.build_debug_glibc {
+        if let Some(version) = &config.detected_glibc_version {
+            lines.push(format!(
+                "set debug-file-directory /opt/pwnenv/glibc-{version}-debug/lib/debug"
+            ));
+        }
+    }
+    match plugin {
+        "gef" => lines.push("source /etc/gdb/gef.gdb".to_string()),
+        "peda" => lines.push("source /etc/gdb/peda.gdb".to_string()),
+        _ => {}
+    }
+
+    let mut script = vec!["RUN <<'EOF'".to_string()];
+    script.push("cat > /root/.gdbinit <<'GDBINIT'".to_string());
+    script.extend(lines);
+    script.push("GDBINIT".to_string());
+    script.push("EOF".to_string());
+
+    ToolConfig {
+        name: "gdbinit".to_string(),
+        script,
+        build_only: false,
+        append: false,
+        artifacts: Vec::new(),
+        verify: Vec::new(),
+        secrets: Vec::new(),
+    }
+}
+
+/// Sets `config.gdb_plugin`'s default `build_args` pin (see
+/// [`pinned_ref`]) unless `pwnenv.yaml` already set that key itself.
+pub fn apply_default_ref(config: &mut Config, plugin: &str) {
+    let Some((key, default_ref)) = pinned_ref(plugin) else {
+        return;
+    };
+    config
+        .build_args
+        .entry(key.to_string())
+        .or_insert_with(|| default_ref.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pwndbg_script_references_its_pinned_ref_arg() {
+        let tool = plugin_tool("pwndbg");
+        assert!(tool.script.iter().any(|line| line.contains("$PWNDBG_REF")));
+    }
+
+    #[test]
+    fn none_installs_plain_gdb_with_no_plugin_lines() {
+        let tool = plugin_tool("none");
+        assert!(tool.script.iter().any(|line| line.contains("apt-get install -y gdb")));
+        assert!(!tool.script.iter().any(|line| line.contains("pwndbg") || line.contains("gef") || line.contains("peda")));
+    }
+
+    #[test]
+    fn gdbinit_always_sets_follow_fork_mode() {
+        let config = Config::default();
+        let tool = gdbinit_tool(&config, "none");
+        assert!(tool.script.iter().any(|line| line.contains("follow-fork-mode child")));
+    }
+
+    #[test]
+    fn gdbinit_sources_the_chosen_plugin() {
+        let config = Config::default();
+        let tool = gdbinit_tool(&config, "gef");
+        assert!(tool.script.iter().any(|line| line.contains("source /etc/gdb/gef.gdb")));
+    }
+
+    #[test]
+    fn gdbinit_points_at_the_debug_glibc_artifact_when_built() {
+        let config = Config {
+            build_debug_glibc: true,
+            detected_glibc_version: Some("2.31".to_string()),
+            ..Config::default()
+        };
+        let tool = gdbinit_tool(&config, "pwndbg");
+        assert!(tool
+            .script
+            .iter()
+            .any(|line| line.contains("/opt/pwnenv/glibc-2.31-debug/lib/debug")));
+    }
+
+    #[test]
+    fn apply_default_ref_does_not_override_an_explicit_build_arg() {
+        let mut config = Config::default();
+        config.build_args.insert("PWNDBG_REF".to_string(), "custom".to_string());
+        apply_default_ref(&mut config, "pwndbg");
+        assert_eq!(config.build_args.get("PWNDBG_REF").map(String::as_str), Some("custom"));
+    }
+}
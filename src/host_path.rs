@@ -0,0 +1,118 @@
+//! Guards against host paths that would silently corrupt a compose file
+//! or Dockerfile `COPY`: non-UTF-8 bytes (common in attacker-named CTF
+//! archives) and paths past typical filesystem limits. Every such path
+//! eventually goes through `.display()` somewhere downstream, which
+//! replaces invalid bytes with `U+FFFD` — a mount or copy path built
+//! from that lossy text just points nowhere, so this rejects the path
+//! up front with a message naming the offending component instead.
+
+use std::path::Path;
+
+use crate::error::{PwnenvError, Result};
+
+/// Linux's own `PATH_MAX` (`<limits.h>`), the ceiling every mainstream
+/// filesystem pwnenv targets enforces for a full path.
+pub const MAX_HOST_PATH_LEN: usize = 4096;
+
+/// Rejects `path` if it isn't valid UTF-8 (docker's own CLI and compose
+/// file format have no escape for raw bytes) or exceeds
+/// [`MAX_HOST_PATH_LEN`]. Cheap enough to call on every host path pwnenv
+/// is about to splice into a compose file, Dockerfile, or label.
+pub fn validate(path: &Path) -> Result<()> {
+    if path.to_str().is_none() {
+        return Err(PwnenvError::InvalidHostPath {
+            path: path.to_path_buf(),
+            reason: "contains non-UTF-8 bytes, which docker's CLI and compose files can't represent"
+                .to_string(),
+        });
+    }
+    let len = path.as_os_str().len();
+    if len > MAX_HOST_PATH_LEN {
+        return Err(PwnenvError::InvalidHostPath {
+            path: path.to_path_buf(),
+            reason: format!("is {len} bytes long, past the {MAX_HOST_PATH_LEN}-byte PATH_MAX most filesystems enforce"),
+        });
+    }
+    Ok(())
+}
+
+/// A docker label value for `path`: the path itself when it's valid
+/// UTF-8 (the overwhelming common case), or a percent-encoded form of
+/// its raw bytes otherwise. Labels are metadata, not a path docker ever
+/// resolves back to disk, so unlike [`validate`] (used for mount/COPY
+/// paths, which docker really does need to resolve) this never errors —
+/// it just needs to round-trip without corrupting the label value.
+pub fn encode_label(path: &Path) -> String {
+    match path.to_str() {
+        Some(s) => s.to_string(),
+        None => percent_encode(raw_bytes(path)),
+    }
+}
+
+#[cfg(unix)]
+fn raw_bytes(path: &Path) -> &[u8] {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes()
+}
+
+#[cfg(not(unix))]
+fn raw_bytes(path: &Path) -> &[u8] {
+    path.to_str().unwrap_or_default().as_bytes()
+}
+
+fn percent_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for &byte in bytes {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'/' => {
+                out.push(byte as char);
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    #[test]
+    fn valid_utf8_paths_pass() {
+        assert!(validate(Path::new("/home/user/challenge")).is_ok());
+    }
+
+    #[test]
+    fn non_utf8_bytes_are_rejected() {
+        let raw = OsStr::from_bytes(b"/tmp/chall-\xFF\xFE");
+        let err = validate(Path::new(raw)).unwrap_err();
+        assert!(err.to_string().contains("non-UTF-8"));
+    }
+
+    #[test]
+    fn overlong_paths_are_rejected() {
+        let long_component = "a".repeat(MAX_HOST_PATH_LEN + 1);
+        let err = validate(Path::new("/tmp").join(&long_component).as_path()).unwrap_err();
+        assert!(err.to_string().contains("PATH_MAX"));
+    }
+
+    #[test]
+    fn valid_utf8_labels_pass_through_unchanged() {
+        assert_eq!(encode_label(Path::new("/home/user/challenge")), "/home/user/challenge");
+    }
+
+    #[test]
+    fn non_utf8_labels_are_percent_encoded() {
+        let raw = OsStr::from_bytes(b"/tmp/chall-\xFF\xFE");
+        assert_eq!(encode_label(Path::new(raw)), "/tmp/chall-%FF%FE");
+    }
+
+    #[test]
+    fn percent_encoded_labels_avoid_percent_signs_needing_escape_themselves() {
+        // a literal '%' in the path must itself be escaped so the
+        // encoding stays unambiguous to decode.
+        assert_eq!(percent_encode(b"100%done"), "100%25done");
+    }
+}
@@ -0,0 +1,94 @@
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+
+/// Labels pwnenv stamps onto every container and image it creates, so
+/// `status`, `ps`, and orphan/clean logic can find "ours" reliably
+/// instead of guessing by container/project name.
+pub const ENV_NAME: &str = "dev.pwnenv.env_name";
+pub const VERSION: &str = "dev.pwnenv.version";
+pub const HOST_DIR: &str = "dev.pwnenv.host_dir";
+pub const CONFIG_HASH: &str = "dev.pwnenv.config_hash";
+pub const CREATED_AT: &str = "dev.pwnenv.created_at";
+
+/// The label set for one environment, computed fresh each time `build`/`up`
+/// runs — `created_at` and `config_hash` are meant to drift when the
+/// environment is rebuilt from a changed config, which is exactly what
+/// `status --verbose` uses to flag a stale image.
+pub struct Labels {
+    pub env_name: String,
+    pub host_dir: String,
+    pub config_hash: String,
+    pub created_at: u64,
+}
+
+impl Labels {
+    pub fn new(env_name: &str, config: &Config, host_dir: &Path) -> Labels {
+        Labels {
+            env_name: env_name.to_string(),
+            host_dir: crate::host_path::encode_label(host_dir),
+            config_hash: config_hash(config),
+            created_at: now_unix(),
+        }
+    }
+
+    /// As `dev.pwnenv.*` key/value pairs, in a stable order, for rendering
+    /// into compose `labels:`/`docker build --label`.
+    pub fn as_pairs(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (VERSION, env!("CARGO_PKG_VERSION").to_string()),
+            (ENV_NAME, self.env_name.clone()),
+            (HOST_DIR, self.host_dir.clone()),
+            (CONFIG_HASH, self.config_hash.clone()),
+            (CREATED_AT, self.created_at.to_string()),
+        ]
+    }
+}
+
+/// A short, stable fingerprint of `config`'s on-disk shape, so a running
+/// container's `dev.pwnenv.config_hash` label can be compared against the
+/// current `pwnenv.yaml` to flag staleness (see `status --verbose`).
+pub fn config_hash(config: &Config) -> String {
+    let rendered = serde_yaml::to_string(config).unwrap_or_default();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    rendered.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// A `docker ps`/`docker image ls` `--filter` argument that matches only
+/// containers/images pwnenv created for `env_name`.
+pub fn env_name_filter(env_name: &str) -> String {
+    format!("label={ENV_NAME}={env_name}")
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_hash_changes_when_config_changes() {
+        let a = config_hash(&Config::default());
+        let b = config_hash(&Config { privileged: false, ..Config::default() });
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn config_hash_is_stable_for_the_same_config() {
+        let config = Config::default();
+        assert_eq!(config_hash(&config), config_hash(&config));
+    }
+
+    #[test]
+    fn env_name_filter_matches_the_docker_filter_syntax() {
+        assert_eq!(env_name_filter("chall"), "label=dev.pwnenv.env_name=chall");
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use crate::config::ToolConfig;
+
+/// Scans a binary for the highest `GLIBC_2.NN` symbol-version string it
+/// references, which is a decent proxy for "which glibc did this
+/// challenge link against". Good enough to pick a matching base image
+/// without pulling in a full ELF parser.
+pub fn detect_glibc_version(binary: &Path) -> std::io::Result<Option<String>> {
+    let data = std::fs::read(binary)?;
+    let mut best: Option<(u32, u32)> = None;
+
+    for window in data.windows(b"GLIBC_2.".len() + 4) {
+        if !window.starts_with(b"GLIBC_2.") {
+            continue;
+        }
+        let rest = &window[b"GLIBC_2.".len()..];
+        let digits: Vec<u8> = rest.iter().copied().take_while(u8::is_ascii_digit).collect();
+        if digits.is_empty() {
+            continue;
+        }
+        if let Ok(minor) = std::str::from_utf8(&digits).unwrap_or("").parse::<u32>() {
+            best = Some(best.map_or((2, minor), |(major, best_minor)| (major, best_minor.max(minor))));
+        }
+    }
+
+    Ok(best.map(|(major, minor)| format!("{major}.{minor}")))
+}
+
+/// Maps a glibc version (as produced by [`detect_glibc_version`]) to a
+/// base image known to ship that glibc, falling back to the newest
+/// supported image for anything newer than we know about.
+pub fn base_image_for_glibc(version: &str) -> &'static str {
+    let minor: u32 = version
+        .strip_prefix("2.")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(35);
+
+    match minor {
+        0..=27 => "ubuntu:18.04",
+        28..=31 => "ubuntu:20.04",
+        _ => "ubuntu:22.04",
+    }
+}
+
+/// A `build_only` tool that fetches glibc `version`'s source, builds it
+/// with debug info, and carries the debug build into the final image so
+/// pwndbg/gdb can symbolicate libc frames for that exact version.
+pub fn debug_glibc_tool(version: &str) -> ToolConfig {
+    let artifact = format!("/opt/pwnenv/glibc-{version}-debug");
+    ToolConfig {
+        name: format!("debug-glibc-{version}"),
+        script: vec![
+            "RUN apt-get update && apt-get install -y build-essential wget".to_string(),
+            format!("RUN wget -O /tmp/glibc.tar.gz https://ftp.gnu.org/gnu/libc/glibc-{version}.tar.gz"),
+            "RUN mkdir -p /tmp/glibc-src /tmp/glibc-build".to_string(),
+            "RUN tar -xf /tmp/glibc.tar.gz -C /tmp/glibc-src --strip-components=1".to_string(),
+            format!(
+                "RUN cd /tmp/glibc-build && /tmp/glibc-src/configure --prefix={artifact} CFLAGS=\"-g -O0\" && make -j$(nproc) && make install"
+            ),
+        ],
+        build_only: true,
+        append: false,
+        artifacts: vec![artifact],
+        verify: Vec::new(),
+        secrets: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_highest_glibc_version_seen() {
+        let mut data = b"junk".to_vec();
+        data.extend_from_slice(b"GLIBC_2.27\0");
+        data.extend_from_slice(b"GLIBC_2.31\0");
+        data.extend_from_slice(b"GLIBC_2.29\0");
+        let dir = std::env::temp_dir().join("pwnenv-libc-detect-test");
+        std::fs::write(&dir, &data).unwrap();
+        assert_eq!(detect_glibc_version(&dir).unwrap(), Some("2.31".to_string()));
+        std::fs::remove_file(&dir).ok();
+    }
+
+    #[test]
+    fn maps_versions_to_matching_images() {
+        assert_eq!(base_image_for_glibc("2.27"), "ubuntu:18.04");
+        assert_eq!(base_image_for_glibc("2.31"), "ubuntu:20.04");
+        assert_eq!(base_image_for_glibc("2.35"), "ubuntu:22.04");
+    }
+}
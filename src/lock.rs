@@ -0,0 +1,88 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{PwnenvError, Result};
+use crate::runtime::RuntimeDir;
+
+/// Guards against two `build`s racing on the same environment's Dockerfile
+/// and build log. Released automatically when dropped, so a build that
+/// returns early via `?` still releases it.
+pub struct BuildLock {
+    path: PathBuf,
+}
+
+impl BuildLock {
+    /// Acquires `runtime`'s build lock, stealing it (with a warning) if the
+    /// PID recorded in an existing lockfile is no longer running — e.g. a
+    /// previous pwnenv that crashed instead of releasing it.
+    pub fn acquire(runtime: &RuntimeDir) -> Result<BuildLock> {
+        let path = runtime.root().join("build.lock");
+
+        if let Some(holder) = read_lock_pid(&path) {
+            if pid_is_alive(holder) {
+                return Err(PwnenvError::Docker(format!(
+                    "a build is already in progress (pid {holder}); wait for it to finish or kill it"
+                )));
+            }
+            eprintln!("build lock held by dead pid {holder}; stealing it");
+        }
+
+        std::fs::write(&path, std::process::id().to_string())?;
+        Ok(BuildLock { path })
+    }
+}
+
+impl Drop for BuildLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn read_lock_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Whether `pid` still names a live process — the cheapest check
+/// available without a `kill(2)`/procfs dependency, and the one
+/// [`crate::sessions`] also uses to prune `enter` sessions left behind by
+/// a crash instead of a clean exit.
+#[cfg(unix)]
+pub(crate) fn pid_is_alive(pid: u32) -> bool {
+    Path::new(&format!("/proc/{pid}")).exists()
+}
+
+#[cfg(not(unix))]
+pub(crate) fn pid_is_alive(_pid: u32) -> bool {
+    // No cheap liveness check off Linux; assume alive so we never steal a
+    // lock (or prune a session) we can't actually verify is abandoned.
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn stale_lock_from_a_dead_pid_is_stolen() {
+        let env_name = "lock-test-stale";
+        let runtime = RuntimeDir::new(env_name);
+        runtime.ensure_exists().unwrap();
+        std::fs::write(runtime.root().join("build.lock"), "4294967295").unwrap();
+
+        let lock = BuildLock::acquire(&runtime).unwrap();
+        assert!(runtime.root().join("build.lock").exists());
+        drop(lock);
+        assert!(!runtime.root().join("build.lock").exists());
+    }
+
+    #[test]
+    fn releasing_the_lock_removes_the_file() {
+        let env_name = "lock-test-release";
+        let runtime = RuntimeDir::new(env_name);
+        runtime.ensure_exists().unwrap();
+
+        let lock = BuildLock::acquire(&runtime).unwrap();
+        drop(lock);
+        assert!(!runtime.root().join("build.lock").exists());
+    }
+}
@@ -0,0 +1,954 @@
+mod activity;
+mod arch;
+mod bake;
+mod build_id;
+mod commands;
+mod compose;
+mod config;
+mod diff_env;
+mod docker;
+mod entrypoint;
+mod error;
+mod gdb_plugins;
+mod host_path;
+mod labels;
+mod libc_detect;
+mod lock;
+mod manifest;
+mod mounts;
+mod output;
+mod presets;
+mod programs;
+mod prompt;
+mod recordings;
+mod remote_tools;
+mod runtime;
+mod sessions;
+mod tool_presets;
+mod trace;
+mod verify;
+mod version;
+
+use clap::{Parser, Subcommand};
+
+use config::Config;
+
+#[derive(Parser)]
+#[command(name = "pwnenv", about = "Disposable, reproducible pwn/CTF environments")]
+struct Cli {
+    /// Assume "yes" to any confirmation prompt instead of asking.
+    #[arg(short = 'y', long = "yes", visible_alias = "assume-yes", global = true)]
+    assume_yes: bool,
+
+    /// Disable ANSI color in human-readable output, same effect as
+    /// setting `NO_COLOR`. Has no effect on `--format json`/`--json`
+    /// output, which never carries color to begin with.
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Copy whatever `build`/`up` generate (Dockerfile, compose file,
+    /// resolved config, a redacted `.env`) into this directory, for
+    /// attaching to a bug report. Env values referenced by the compose
+    /// file are never copied in, only their names.
+    #[arg(long, global = true)]
+    trace: Option<std::path::PathBuf>,
+
+    /// Just parse `pwnenv.yaml` and report whether it's valid, then exit
+    /// — the given subcommand is never run. Skips everything a real
+    /// invocation would otherwise do first: no runtime dir is created
+    /// under `~/.local/share/pwnenv`, and docker is never invoked. For a
+    /// pre-flight check in a restricted environment where even that
+    /// setup would fail.
+    #[arg(long, global = true)]
+    config_check_only: bool,
+
+    /// Use `path` instead of the usual state directory for every
+    /// environment's runtime dir and global state (version-notice marker,
+    /// etc). Lets completely independent pwnenv setups (e.g. one for CTFs,
+    /// a hardened one for malware triage) coexist without moving
+    /// directories around. Overrides `PWNENV_CONFIG_DIR` and
+    /// `XDG_DATA_HOME` for this invocation; see [`crate::runtime::state_dir`]
+    /// for the full precedence order.
+    #[arg(long, global = true)]
+    config_dir: Option<std::path::PathBuf>,
+
+    /// Override one field in the loaded config for this invocation only,
+    /// without writing to `pwnenv.yaml`. Repeatable; later `--set`s of the
+    /// same key win. `key` is dot-separated for nested fields and list
+    /// indices (e.g. `--set tools.0.script.0=RUN true`); `value` is parsed
+    /// as YAML, so `--set i386=true` sets a real bool, not the string
+    /// `"true"`. The result is re-validated the same as a file on disk
+    /// would be, so a bad override still surfaces a clear error.
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    set: Vec<String>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Set an environment up from `pwnenv.yaml` (runtime dir, programs copy).
+    Init {
+        name: Option<String>,
+        /// Override config.privileged = false for this environment,
+        /// without editing pwnenv.yaml.
+        #[arg(long)]
+        no_privileged: bool,
+        /// Extra bind mount as `host:container`, alongside `workspace_dir`.
+        /// Repeatable. Relative `host` paths resolve against the cwd
+        /// `init` was run from.
+        #[arg(long = "mount")]
+        mount: Vec<String>,
+        /// Ignore the saved programs-dir manifest and copy everything,
+        /// as if this were the first `init`.
+        #[arg(long)]
+        force_copy: bool,
+        /// Skip the Dockerfile build and run a prebuilt image instead.
+        #[arg(long = "from-image")]
+        from_image: Option<String>,
+        /// Dereference symlinks in `programs_dir` that point outside it and
+        /// copy the target's contents as a regular file, instead of
+        /// skipping them.
+        #[arg(long)]
+        follow_external_symlinks: bool,
+        /// Don't bake `programs_dir` into the image. Conflicts with
+        /// `--no-mount` if both would leave the container with no access
+        /// to it at all.
+        #[arg(long)]
+        no_copy: bool,
+        /// Don't bind-mount `programs_dir`'s copied snapshot into the
+        /// container.
+        #[arg(long)]
+        no_mount: bool,
+        /// Never touch the network: `build` requires a matching image to
+        /// already exist locally instead of building one, and `up` never
+        /// pulls. For venues with no internet, paired with an image built
+        /// (and `docker save`d/loaded) ahead of time.
+        #[arg(long)]
+        offline: bool,
+        /// Tag `build`/`up` give this environment's image from now on,
+        /// without needing `--tag` repeated on every invocation. See
+        /// `pwnenv.yaml`'s `image_tag`.
+        #[arg(long = "image-tag")]
+        image_tag: Option<String>,
+        /// Skip the mount overlap check and the config-version guard, for
+        /// scripted/CI runs that already know the config is sound.
+        /// Distinct from the global `--yes`: `--yes` only auto-answers
+        /// yes/no prompts, `--force` disables validations that would
+        /// otherwise refuse to proceed at all.
+        #[arg(long)]
+        force: bool,
+        /// Override `pwnenv.yaml`'s `gdb_plugin` for this environment,
+        /// without editing it. One of `pwndbg`/`gef`/`peda`/`none`.
+        #[arg(long = "gdb-plugin")]
+        gdb_plugin: Option<String>,
+    },
+    /// Re-register an existing challenge directory after state loss (a
+    /// wiped config dir, a fresh machine, a teammate's exported compose
+    /// file), without a full `init` + `build`.
+    Adopt {
+        name: Option<String>,
+        /// Adopt from this specific container instead of searching for
+        /// one labeled `dev.pwnenv.env_name=<name>`.
+        #[arg(long)]
+        container: Option<String>,
+    },
+    /// Build the environment image from `pwnenv.yaml`.
+    Build {
+        /// Name of the environment (defaults to the current directory name).
+        #[arg(long)]
+        name: Option<String>,
+        /// Overrides `pwnenv.yaml`'s `image_tag` (and any `init
+        /// --image-tag`) for this invocation only. Defaults to
+        /// `pwnenv-env` if neither is set.
+        #[arg(long)]
+        tag: Option<String>,
+        /// A `KEY=VALUE` build argument, passed through to `docker build`
+        /// and declared as an `ARG` in the Dockerfile. Repeatable;
+        /// overrides a same-named key in `pwnenv.yaml`'s `build_args`.
+        #[arg(long = "build-arg")]
+        build_arg: Vec<String>,
+        /// Force just this tool's layer (and everything after it) to
+        /// rebuild, even if its script is unchanged — e.g. it installs
+        /// `latest` from somewhere that moved. Earlier layers still hit
+        /// docker's build cache as usual.
+        #[arg(long)]
+        only: Option<String>,
+    },
+    /// Show the status of an environment.
+    Status {
+        name: Option<String>,
+        /// Also print the `dev.pwnenv.*` labels on the running container,
+        /// flagging a mismatched `config_hash` against the current
+        /// `pwnenv.yaml`.
+        #[arg(long)]
+        verbose: bool,
+        /// Keep redrawing the status in place, reacting to `docker events`
+        /// for this environment's container, until Ctrl-C or the container
+        /// is removed.
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Upgrade an environment's runtime dir to the layout the installed
+    /// pwnenv expects, without needing to rebuild anything.
+    MigrateRuntime {
+        name: Option<String>,
+    },
+    /// Pretty-print an environment's `activity.log`: when it was built,
+    /// brought up, entered, analyzed, snapshotted, and so on.
+    History {
+        name: Option<String>,
+        /// Only show events at or after this point: a unix timestamp, or
+        /// a relative duration like `2h`/`3d`.
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Print the Dockerfile that would be built, after sanity-checking it.
+    Render,
+    /// Exec into an environment's running container with its configured shell.
+    Enter {
+        name: Option<String>,
+        /// Exec into a specific compose service instead of the
+        /// environment's default one (itself), for when an environment
+        /// ever runs more than one.
+        #[arg(long)]
+        service: Option<String>,
+        /// Run a named profile's command instead of the default shell.
+        #[arg(long = "as")]
+        profile: Option<String>,
+        /// Don't allocate a pty (`docker exec`'s `-t`). For scripted use,
+        /// where a tty in the middle would garble piped output.
+        #[arg(long)]
+        no_tty: bool,
+        /// Don't keep stdin open (`docker exec`'s `-i`).
+        #[arg(long)]
+        no_interactive: bool,
+        /// Capture the session with whichever recorder is available in
+        /// the container (asciinema, else `script(1)`) and save it under
+        /// `recordings list`/`recordings play`. See [`recordings`].
+        #[arg(long)]
+        record: bool,
+    },
+    /// Live resource usage for an environment's container.
+    #[command(visible_alias = "stats")]
+    Top {
+        name: Option<String>,
+    },
+    /// Run a headless reversing pass (ghidra/radare2/rizin) over a binary
+    /// inside the environment's container. For `ghidra`, exports
+    /// decompiled C for every function into `<binary>.decomp/`.
+    Analyze {
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long, default_value = "radare2")]
+        tool: String,
+        binary: String,
+        /// Re-run `ghidra` analysis even if `<binary>.decomp/` already
+        /// looks up to date.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Pre-pull `base_image` without building anything, e.g. to warm a
+    /// cache before going offline.
+    PullBase,
+    /// Commit an environment's running container to an image, preserving
+    /// ad-hoc changes made inside it.
+    #[command(visible_alias = "commit")]
+    Snapshot {
+        #[arg(long)]
+        name: Option<String>,
+        tag: String,
+    },
+    /// Docker-truth view of every pwnenv container across projects, with
+    /// live resource usage. Unlike `status`, this doesn't consult any
+    /// environment's runtime dir except to flag orphans.
+    Ps {
+        /// `table` (default) or `json`.
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// Manage the `pwnenv:*` images accumulated across builds, snapshots,
+    /// and presets. With no subcommand, lists every pwnenv-labeled image.
+    Images {
+        #[command(subcommand)]
+        command: Option<ImagesCommands>,
+        /// `table` (default) or `json`. Only applies to the bare listing.
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// List the tools `pwnenv.yaml` would install, sorted by name.
+    ListTools {
+        /// `text` (default, one name per line) or `json`.
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// Dashboard of every environment pwnenv has `init`'ed, with its
+    /// running status and forwarded port.
+    ListProfiles {
+        /// `table` (default) or `json`.
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// Write a local, non-secret flag that `up` mounts at `/flag`, for
+    /// testing an exploit without touching the real deployment's flag.
+    LocalFlag {
+        name: Option<String>,
+        #[arg(long)]
+        contents: Option<String>,
+    },
+    /// Bring an environment's container up via docker compose.
+    Up {
+        name: Option<String>,
+        /// Overrides `pwnenv.yaml`'s `image_tag` (and any `init
+        /// --image-tag`) for this invocation only. Defaults to
+        /// `pwnenv-env` if neither is set.
+        #[arg(long)]
+        tag: Option<String>,
+        /// Bypass `max_running_environments`, even if it would be exceeded.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Add a `host:container` port mapping to a running environment,
+    /// beyond `pwnenv.yaml`'s `forwarded_port`. Docker can't add a port
+    /// to a container in place, so this recreates it via `up -d` — the
+    /// container restarts.
+    OpenPort {
+        #[arg(long)]
+        name: Option<String>,
+        /// `host:container`, e.g. `8081:8080`.
+        port: String,
+    },
+    /// Generate an xinetd service file for standalone challenge deployment.
+    DeployXinetd {
+        #[arg(long, default_value = "chall")]
+        service_name: String,
+        binary: String,
+        port: u16,
+        #[arg(long, default_value = "xinetd.conf")]
+        out: std::path::PathBuf,
+    },
+    /// Inspect or validate `pwnenv.yaml`.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Operates on `pwnenv.yaml`'s `remote_tools` sources.
+    Tools {
+        #[command(subcommand)]
+        command: ToolsCommands,
+    },
+    /// List or play back `enter --record` sessions.
+    Recordings {
+        #[command(subcommand)]
+        command: RecordingsCommands,
+    },
+    /// Write a curated `pwnenv.yaml` for a common CTF category (`kernel`,
+    /// `heap`, `rev`) instead of starting from the one-size default.
+    Template {
+        kind: String,
+    },
+    /// Print a shell snippet (`eval`/`source` it from your rc file) that
+    /// reports a registered environment on `cd` and defines a `pe` alias
+    /// for `pwnenv enter`.
+    Hook {
+        /// `bash`, `zsh`, or `fish`.
+        shell: String,
+    },
+    /// Hidden, docker-free lookup behind `pwnenv hook`'s shell snippets:
+    /// prints the environment name registered to `dir` and exits 0, or
+    /// prints nothing and exits 1. Never loads `pwnenv.yaml`.
+    #[command(name = "__probe", hide = true)]
+    Probe { dir: std::path::PathBuf },
+    /// Live dashboard of every pwnenv environment: a list pane (state,
+    /// CPU, memory) and a detail pane for the selected one. Read-only
+    /// for now — `q`/Esc to quit, arrow keys or j/k to move the
+    /// selection.
+    Tui,
+    /// Escape hatch: runs `docker compose <args>` with the environment's
+    /// project name, compose file, project directory, and env file
+    /// already set. Use `--` before compose's own flags, e.g. `pwnenv
+    /// compose -- logs -f`.
+    Compose {
+        #[arg(long)]
+        name: Option<String>,
+        /// Print the full command instead of running it.
+        #[arg(long)]
+        print: bool,
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Escape hatch: runs `docker <args>`, substituting a literal
+    /// `{container}` token with the environment's resolved container
+    /// ID. Use `--` before docker's own flags, e.g. `pwnenv docker --
+    /// logs -f {container}`.
+    Docker {
+        #[arg(long)]
+        name: Option<String>,
+        /// Print the full command instead of running it.
+        #[arg(long)]
+        print: bool,
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Runs a non-interactive command in every selected environment's
+    /// container (fan-out, for things like `pip install -U pwntools`
+    /// across a whole team event's prebuilt challenges). Repeat `--name`
+    /// for specific environments, or pass `--all` for every one pwnenv
+    /// knows about. Use `--` before the command's own flags, e.g.
+    /// `pwnenv exec --all -- pip install -U pwntools`.
+    Exec {
+        /// An environment to target; repeatable. Conflicts with `--all`.
+        #[arg(long = "name", conflicts_with = "all")]
+        names: Vec<String>,
+        /// Target every environment pwnenv knows about instead of
+        /// specific `--name`s.
+        #[arg(long)]
+        all: bool,
+        /// Run up to this many containers' commands concurrently instead
+        /// of one at a time.
+        #[arg(long, default_value_t = 1)]
+        parallel: usize,
+        /// Bring a selected environment up first instead of skipping it
+        /// if it isn't already running.
+        #[arg(long)]
+        start: bool,
+        #[arg(last = true)]
+        command: Vec<String>,
+    },
+    /// Stop and remove an environment's container. Defaults to an
+    /// immediate `docker compose kill` (SIGKILL); `--graceful` stops it
+    /// first instead, giving it `--timeout` seconds to shut down on its
+    /// own. `--all` kills every environment instead, prompting for
+    /// confirmation first unless `--yes` is set.
+    Kill {
+        name: Option<String>,
+        /// Kill every environment instead of just `name` (or the cwd's).
+        #[arg(long, conflicts_with = "name")]
+        all: bool,
+        #[arg(long)]
+        graceful: bool,
+        #[arg(long, default_value_t = 10)]
+        timeout: u32,
+        /// Kill even if other `pwnenv enter` sessions are still attached.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Hidden JSON introspection document (CLI surface, config schema
+    /// version, resolved directories) for wrapper scripts. Not meant for
+    /// humans; use `--help` for that.
+    #[command(name = "__introspect", hide = true)]
+    Introspect,
+    /// Re-run every tool's `verify` commands against a running
+    /// environment. Exits non-zero if any failed.
+    Verify {
+        name: Option<String>,
+    },
+    /// Checks whether this host is ready to run pwnenv at all — docker
+    /// itself, `pwnenv.yaml`, configured mounts and ports — independent
+    /// of any particular environment. Exits non-zero if any check comes
+    /// back critical.
+    Doctor {
+        /// Print each check as a JSON object with name/status/detail
+        /// instead of one line per check, for CI to parse.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Collects `dpkg -l`, `pip freeze`, `cargo install --list`, and
+    /// `gem list` from a running environment's container into one
+    /// normalized package manifest, saved to the runtime dir. With no
+    /// subcommand, (re-)collects and prints it; `diff` compares two
+    /// previously-saved manifests instead.
+    Manifest {
+        #[command(subcommand)]
+        command: Option<ManifestCommands>,
+        name: Option<String>,
+        /// Print the collected manifest as JSON instead of one
+        /// `source/name version` line per package.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Copies the environment's own loader/libc (and, with `i386: true`,
+    /// their 32-bit counterparts) out of its container via `docker cp`
+    /// for host-side analysis, alongside a manifest of each file's glibc
+    /// version and build ID. Works whether the container is running or
+    /// merely exists.
+    CpLibs {
+        name: Option<String>,
+        #[arg(long, default_value = "./libs")]
+        out: std::path::PathBuf,
+        /// Overwrite a destination file that already exists and differs
+        /// from the container's copy, instead of erroring.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Compares two environments' configs, rendered Dockerfiles, compose
+    /// files, and base image digests, grouped by category — for tracking
+    /// down why an exploit works in one but not the other. Neither
+    /// environment needs to be the one the command is run from, or to
+    /// have ever been built.
+    DiffEnv {
+        env_a: String,
+        env_b: String,
+        /// Also compare each environment's last saved package manifest
+        /// (see `pwnenv manifest`).
+        #[arg(long)]
+        packages: bool,
+        /// `text` (default) or `json`.
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// Builds a specific glibc version inside an environment's container,
+    /// from the checkout the `"glibc"` tool preset (`include_tools:
+    /// [glibc]`) clones bminor/glibc into.
+    Glibc {
+        #[command(subcommand)]
+        command: GlibcCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ManifestCommands {
+    /// Compares two saved `manifest.json` files (e.g. before/after a
+    /// rebuild) and prints added, removed, and upgraded packages.
+    Diff {
+        a: std::path::PathBuf,
+        b: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Load `pwnenv.yaml` and run the Dockerfile sanity checks against it.
+    Validate,
+    /// Print a top-level scalar field's value.
+    Get { key: String },
+    /// Set a top-level scalar field's value.
+    Set { key: String, value: String },
+    /// Stamp `generated_by` with the running pwnenv's version, silencing
+    /// the "config predates this binary" notice.
+    Upgrade,
+    /// Overwrite `pwnenv.yaml` with default settings, after confirmation.
+    /// The recovery path when the file has been emptied or truncated.
+    Reset,
+    /// Show which fields the typed config schema would add, remove, or
+    /// change relative to what's on disk, without writing anything.
+    Diff,
+    /// Print the fully-resolved effective config — defaults, includes,
+    /// presets, and any `--set` overrides all applied — as YAML or JSON.
+    Show {
+        #[arg(long, default_value = "yaml")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ToolsCommands {
+    /// Fetch every `remote_tools` URL into the cache `render`/`build`
+    /// read from (see [`remote_tools`]).
+    Sync,
+}
+
+#[derive(Subcommand)]
+enum RecordingsCommands {
+    /// List every `enter --record` session saved under `recordings/`.
+    List {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Play back one recording by id (as printed by `enter --record` or
+    /// `recordings list`).
+    Play { id: String },
+}
+
+#[derive(Subcommand)]
+enum GlibcCommands {
+    /// Checks out `version` (e.g. `2.31`) in the container's glibc
+    /// checkout and builds it into `/opt/glibc-<version>`, skipping
+    /// `configure`/`make` if that directory already exists from a prior,
+    /// possibly-interrupted run. Prints a `patchelf` command to re-link a
+    /// binary against the result and a gdb `directory` command to pick up
+    /// its source, unless `--patch` is given, in which case the
+    /// `patchelf` command is run instead of just printed.
+    Build {
+        #[arg(long)]
+        name: Option<String>,
+        version: String,
+        /// Path (inside the container) to a binary to patchelf against
+        /// this build immediately, instead of just printing the command.
+        #[arg(long)]
+        patch: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ImagesCommands {
+    /// Remove one image, identified by tag, image ID (or a prefix of
+    /// one), or `dev.pwnenv.config_hash`. Refuses if any environment's
+    /// container still references it, unless `--force`.
+    Rm {
+        selector: String,
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove every pwnenv-labeled image with no referencing container.
+    Prune,
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    if let Some(config_dir) = cli.config_dir.clone() {
+        runtime::set_config_dir_override(config_dir);
+    }
+
+    if !cli.set.is_empty() {
+        config::set_overrides(cli.set.clone());
+    }
+
+    if cli.config_check_only {
+        return match Config::load(std::path::Path::new("pwnenv.yaml")) {
+            Ok(_) => {
+                println!("pwnenv.yaml: OK");
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        };
+    }
+
+    match cli.command {
+        Commands::Init {
+            name,
+            no_privileged,
+            mount,
+            force_copy,
+            from_image,
+            follow_external_symlinks,
+            no_copy,
+            no_mount,
+            offline,
+            image_tag,
+            force,
+            gdb_plugin,
+        } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            if !force {
+                version::guard_mutating(std::path::Path::new("pwnenv.yaml"), &config)?;
+                version::notify(&config);
+            }
+            let cwd = std::env::current_dir()?;
+            commands::init::init(
+                &env_name,
+                &config,
+                no_privileged,
+                &mount,
+                &cwd,
+                force_copy,
+                from_image.as_deref(),
+                follow_external_symlinks,
+                no_copy,
+                no_mount,
+                offline,
+                image_tag.as_deref(),
+                force,
+                gdb_plugin.as_deref(),
+            )?;
+        }
+        Commands::Adopt { name, container } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            let cwd = std::env::current_dir()?;
+            commands::adopt::adopt(&env_name, &config, &cwd, container.as_deref())?;
+        }
+        Commands::Build { name, tag, build_arg, only } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            version::guard_mutating(std::path::Path::new("pwnenv.yaml"), &config)?;
+            version::notify(&config);
+            let host_dir = std::env::current_dir()?;
+            let report = commands::build::build_image(
+                &env_name,
+                tag.as_deref(),
+                &config,
+                cli.assume_yes,
+                &host_dir,
+                cli.trace.as_deref(),
+                &build_arg,
+                only.as_deref(),
+            )?;
+            commands::build::print_report(&report, &runtime::RuntimeDir::new(&env_name));
+        }
+        Commands::Status { name, verbose, watch } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            version::notify(&config);
+            if watch {
+                commands::status::watch_status(&env_name, &config, verbose)?;
+            } else {
+                commands::status::print_status(&env_name, &config, verbose)?;
+            }
+        }
+        Commands::MigrateRuntime { name } => {
+            let env_name = env_name(name);
+            let runtime_dir = runtime::setup_minimum_requirements(&env_name)?;
+            activity::log_event(&runtime_dir, "migrate-runtime", &[], Some(0));
+            println!("{env_name}: runtime dir is up to date.");
+        }
+        Commands::History { name, since } => {
+            let env_name = env_name(name);
+            let since = since.as_deref().map(commands::history::parse_since).transpose()?;
+            commands::history::history(&env_name, since)?;
+        }
+        Commands::Render => {
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            version::notify(&config);
+            commands::render::render(&config, &std::env::current_dir()?)?;
+        }
+        Commands::Enter { name, service, profile, no_tty, no_interactive, record } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            version::notify(&config);
+            commands::enter::enter(&env_name, &config, service.as_deref(), profile.as_deref(), no_tty, no_interactive, record)?;
+        }
+        Commands::Top { name } => {
+            commands::stats::top(&env_name(name))?;
+        }
+        Commands::Analyze { name, tool, binary, force } => {
+            commands::analyze::analyze(&env_name(name), &tool, &binary, force)?;
+        }
+        Commands::PullBase => {
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            version::notify(&config);
+            commands::build::pull_base(&config)?;
+        }
+        Commands::Snapshot { name, tag } => {
+            commands::snapshot::snapshot(&env_name(name), &tag)?;
+        }
+        Commands::Ps { format } => {
+            let json = match format.as_str() {
+                "table" => false,
+                "json" => true,
+                other => {
+                    anyhow::bail!("unknown --format '{other}'; expected 'table' or 'json'")
+                }
+            };
+            commands::ps::ps(json, cli.no_color)?;
+        }
+        Commands::Images { command, format } => match command {
+            Some(ImagesCommands::Rm { selector, force }) => {
+                commands::images::images_rm(&selector, force)?;
+            }
+            Some(ImagesCommands::Prune) => {
+                commands::images::images_prune()?;
+            }
+            None => {
+                let json = match format.as_str() {
+                    "table" => false,
+                    "json" => true,
+                    other => {
+                        anyhow::bail!("unknown --format '{other}'; expected 'table' or 'json'")
+                    }
+                };
+                commands::images::images(json)?;
+            }
+        },
+        Commands::ListTools { format } => {
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            version::notify(&config);
+            let json = match format.as_str() {
+                "text" => false,
+                "json" => true,
+                other => {
+                    anyhow::bail!("unknown --format '{other}'; expected 'text' or 'json'")
+                }
+            };
+            commands::list_tools::list_tools(&config, json)?;
+        }
+        Commands::ListProfiles { format } => {
+            let json = match format.as_str() {
+                "table" => false,
+                "json" => true,
+                other => {
+                    anyhow::bail!("unknown --format '{other}'; expected 'table' or 'json'")
+                }
+            };
+            commands::list_profiles::list_profiles(json, cli.no_color)?;
+        }
+        Commands::LocalFlag { name, contents } => {
+            let env_name = env_name(name);
+            let runtime = runtime::setup_minimum_requirements(&env_name)?;
+            commands::flag::write_local_flag(&runtime, contents.as_deref())?;
+            println!("{env_name}: wrote local test flag to {}", runtime.root().join("flag").display());
+        }
+        Commands::Up { name, tag, force } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            version::guard_mutating(std::path::Path::new("pwnenv.yaml"), &config)?;
+            version::notify(&config);
+            let other_running: Vec<String> = commands::ps::collect_rows()?
+                .into_iter()
+                .filter(|row| row.state == "running" && row.env_name != env_name)
+                .map(|row| row.env_name)
+                .collect();
+            commands::limit::enforce_limit(
+                &env_name,
+                config.max_running_environments,
+                &other_running,
+                force,
+                cli.assume_yes,
+            )?;
+            let runtime = runtime::setup_minimum_requirements(&env_name)?;
+            let host_dir = std::env::current_dir()?;
+            commands::up::up(&env_name, tag.as_deref(), &config, &runtime, &host_dir, cli.trace.as_deref())?;
+        }
+        Commands::OpenPort { name, port } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            let runtime = runtime::setup_minimum_requirements(&env_name)?;
+            let host_dir = std::env::current_dir()?;
+            commands::open_port::open_port(&env_name, &port, &config, &runtime, &host_dir, cli.trace.as_deref())?;
+        }
+        Commands::DeployXinetd {
+            service_name,
+            binary,
+            port,
+            out,
+        } => {
+            commands::deploy::write_xinetd(&out, &service_name, &binary, port)?;
+            println!("wrote {}", out.display());
+        }
+        Commands::Config { command } => match command {
+            ConfigCommands::Validate => {
+                version::notify(&Config::load(std::path::Path::new("pwnenv.yaml"))?);
+                commands::config::validate(
+                    std::path::Path::new("pwnenv.yaml"),
+                    &std::env::current_dir()?,
+                )?;
+            }
+            ConfigCommands::Get { key } => {
+                version::notify(&Config::load(std::path::Path::new("pwnenv.yaml"))?);
+                commands::config::get(std::path::Path::new("pwnenv.yaml"), &key)?;
+            }
+            ConfigCommands::Set { key, value } => {
+                commands::config::set(std::path::Path::new("pwnenv.yaml"), &key, &value)?;
+            }
+            ConfigCommands::Upgrade => {
+                commands::config::upgrade(std::path::Path::new("pwnenv.yaml"))?;
+            }
+            ConfigCommands::Reset => {
+                commands::config::reset(std::path::Path::new("pwnenv.yaml"), cli.assume_yes)?;
+            }
+            ConfigCommands::Diff => {
+                version::notify(&Config::load(std::path::Path::new("pwnenv.yaml"))?);
+                commands::config::diff(std::path::Path::new("pwnenv.yaml"))?;
+            }
+            ConfigCommands::Show { format } => {
+                commands::config::show(std::path::Path::new("pwnenv.yaml"), &format)?;
+            }
+        },
+        Commands::Tools { command } => match command {
+            ToolsCommands::Sync => {
+                let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+                version::notify(&config);
+                if commands::tools_sync::sync(&config)? {
+                    std::process::exit(1);
+                }
+            }
+        },
+        Commands::Recordings { command } => match command {
+            RecordingsCommands::List { json } => {
+                commands::recordings::list(json)?;
+            }
+            RecordingsCommands::Play { id } => {
+                commands::recordings::play(&id)?;
+            }
+        },
+        Commands::Template { kind } => {
+            commands::template::template(std::path::Path::new("pwnenv.yaml"), &kind, cli.assume_yes)?;
+        }
+        Commands::Hook { shell } => {
+            commands::hook::hook(&shell)?;
+        }
+        Commands::Probe { dir } => {
+            if !commands::probe::probe(&dir)? {
+                std::process::exit(1);
+            }
+        }
+        Commands::Tui => {
+            commands::tui::tui()?;
+        }
+        Commands::Compose { name, print, args } => {
+            let env_name = env_name(name);
+            let host_dir = std::env::current_dir()?;
+            commands::passthrough::compose(&env_name, &host_dir, &args, print)?;
+        }
+        Commands::Docker { name, print, args } => {
+            let env_name = env_name(name);
+            commands::passthrough::docker(&env_name, &args, print)?;
+        }
+        Commands::Exec { names, all, parallel, start, command } => {
+            commands::exec::exec_all(&names, all, &command, parallel, start)?;
+        }
+        Commands::Kill { name, all, graceful, timeout, force } => {
+            if all {
+                commands::kill::kill_all(graceful, timeout, cli.assume_yes, force)?;
+            } else {
+                let env_name = env_name(name);
+                commands::kill::kill(&env_name, graceful, timeout, force)?;
+            }
+        }
+        Commands::Introspect => {
+            commands::introspect::introspect()?;
+        }
+        Commands::Verify { name } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            commands::verify::verify(&env_name, &config)?;
+        }
+        Commands::Doctor { json } => {
+            if commands::doctor::doctor(json)? {
+                std::process::exit(1);
+            }
+        }
+        Commands::Manifest { command, name, json } => match command {
+            Some(ManifestCommands::Diff { a, b }) => {
+                commands::manifest::diff(&a, &b)?;
+            }
+            None => {
+                let env_name = env_name(name);
+                commands::manifest::manifest(&env_name, json)?;
+            }
+        },
+        Commands::CpLibs { name, out, force } => {
+            let env_name = env_name(name);
+            let config = Config::load(std::path::Path::new("pwnenv.yaml"))?;
+            commands::cp_libs::cp_libs(&env_name, &config, &out, force)?;
+        }
+        Commands::DiffEnv { env_a, env_b, packages, format } => {
+            let json = match format.as_str() {
+                "text" => false,
+                "json" => true,
+                other => {
+                    anyhow::bail!("unknown --format '{other}'; expected 'text' or 'json'")
+                }
+            };
+            commands::diff_env::diff_env(&env_a, &env_b, packages, json)?;
+        }
+        Commands::Glibc { command: GlibcCommands::Build { name, version, patch } } => {
+            let env_name = env_name(name);
+            commands::glibc::build(&env_name, &version, patch.as_deref())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn env_name(name: Option<String>) -> String {
+    name.unwrap_or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "pwnenv".to_string())
+    })
+}
@@ -0,0 +1,293 @@
+//! Collects and diffs a package manifest from inside a running
+//! container: `dpkg -l`, `pip freeze`, `cargo install --list`, and `gem
+//! list`, normalized into one list of [`Package`]s. Meant for auditing
+//! exactly what ended up in an image before sharing it externally.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PwnenvError, Result};
+use crate::runtime::RuntimeDir;
+
+/// One installed package, from one of the four package managers below.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub source: String,
+}
+
+/// The full manifest for one container at one point in time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub packages: Vec<Package>,
+}
+
+/// Runs `dpkg -l`, `pip freeze`, `cargo install --list`, and `gem list`
+/// inside `container`, parsing whichever ones succeed. A package manager
+/// that isn't installed just contributes nothing — this is a best-effort
+/// survey, not a requirement that every one of the four exists.
+pub fn collect(container: &str) -> Manifest {
+    let mut packages = Vec::new();
+    if let Some(output) = exec(container, "dpkg -l") {
+        packages.extend(parse_dpkg(&output));
+    }
+    if let Some(output) = exec(container, "pip freeze") {
+        packages.extend(parse_pip_freeze(&output));
+    }
+    if let Some(output) = exec(container, "cargo install --list") {
+        packages.extend(parse_cargo_install_list(&output));
+    }
+    if let Some(output) = exec(container, "gem list") {
+        packages.extend(parse_gem_list(&output));
+    }
+    packages.sort_by(|a, b| (&a.source, &a.name).cmp(&(&b.source, &b.name)));
+    Manifest { packages }
+}
+
+fn exec(container: &str, command: &str) -> Option<String> {
+    let output = Command::new("docker").args(["exec", container, "/bin/sh", "-c", command]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `dpkg -l` output: a header block (column headers, a `+++` separator
+/// rule) followed by one `ii  name  version  arch  description` row per
+/// installed package. Only `ii` (installed, ok) rows count; a package
+/// mid-removal (`rc`, `un`, ...) isn't actually present anymore.
+fn parse_dpkg(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter(|line| line.starts_with("ii "))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            fields.next()?; // "ii"
+            let name = fields.next()?;
+            let version = fields.next()?;
+            Some(Package { name: name.to_string(), version: version.to_string(), source: "apt".to_string() })
+        })
+        .collect()
+}
+
+/// `pip freeze` output: one `name==version` per line. A package
+/// installed from a VCS/local path instead of an index (`-e git+...` or
+/// `name @ file://...`) has no `==version` and is skipped — there's no
+/// meaningful version string to report for it.
+fn parse_pip_freeze(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, version) = line.trim().split_once("==")?;
+            Some(Package { name: name.to_string(), version: version.to_string(), source: "pip".to_string() })
+        })
+        .collect()
+}
+
+/// `cargo install --list` output: each installed crate starts a new
+/// unindented `name v1.2.3:` line, followed by indented lines listing
+/// its installed binaries (which this doesn't care about).
+fn parse_cargo_install_list(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with(' ') && !line.is_empty())
+        .filter_map(|line| {
+            let line = line.trim_end_matches(':');
+            let (name, version) = line.split_once(" v")?;
+            Some(Package { name: name.to_string(), version: version.to_string(), source: "cargo".to_string() })
+        })
+        .collect()
+}
+
+/// `gem list` output: `name (version1, version2, ...)` per line, plus a
+/// leading `*** LOCAL GEMS ***` banner this skips. Only the newest
+/// (first-listed) version is kept when more than one is installed.
+fn parse_gem_list(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(" (")?;
+            let versions = rest.trim_end_matches(')');
+            let version = versions.split(',').next()?.trim();
+            Some(Package { name: name.trim().to_string(), version: version.to_string(), source: "gem".to_string() })
+        })
+        .collect()
+}
+
+pub fn manifest_path(runtime: &RuntimeDir) -> PathBuf {
+    runtime.root().join("manifest.json")
+}
+
+pub fn save(runtime: &RuntimeDir, manifest: &Manifest) -> Result<()> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| PwnenvError::Docker(format!("failed to serialize manifest: {e}")))?;
+    std::fs::write(manifest_path(runtime), json)?;
+    Ok(())
+}
+
+pub fn load(path: &std::path::Path) -> Result<Manifest> {
+    let raw = std::fs::read_to_string(path).map_err(|source| PwnenvError::ConfigRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_json::from_str(&raw)
+        .map_err(|e| PwnenvError::Docker(format!("failed to parse manifest at {}: {e}", path.display())))
+}
+
+/// One package's before/after version, for [`diff`]'s `upgraded` list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upgrade {
+    pub name: String,
+    pub source: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// What changed between two manifests, keyed by `(source, name)` so
+/// `pip`'s `requests` and a same-named `gem` don't collide.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    pub added: Vec<Package>,
+    pub removed: Vec<Package>,
+    pub upgraded: Vec<Upgrade>,
+}
+
+pub fn diff(before: &Manifest, after: &Manifest) -> ManifestDiff {
+    let mut result = ManifestDiff::default();
+
+    for after_pkg in &after.packages {
+        match before.packages.iter().find(|p| p.source == after_pkg.source && p.name == after_pkg.name) {
+            None => result.added.push(after_pkg.clone()),
+            Some(before_pkg) if before_pkg.version != after_pkg.version => {
+                result.upgraded.push(Upgrade {
+                    name: after_pkg.name.clone(),
+                    source: after_pkg.source.clone(),
+                    from: before_pkg.version.clone(),
+                    to: after_pkg.version.clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for before_pkg in &before.packages {
+        if !after.packages.iter().any(|p| p.source == before_pkg.source && p.name == before_pkg.name) {
+            result.removed.push(before_pkg.clone());
+        }
+    }
+
+    result
+}
+
+pub fn print_diff(diff: &ManifestDiff) {
+    if diff.added.is_empty() && diff.removed.is_empty() && diff.upgraded.is_empty() {
+        println!("no differences.");
+        return;
+    }
+    for pkg in &diff.added {
+        println!("+ {}/{} {}", pkg.source, pkg.name, pkg.version);
+    }
+    for pkg in &diff.removed {
+        println!("- {}/{} {}", pkg.source, pkg.name, pkg.version);
+    }
+    for upgrade in &diff.upgraded {
+        println!("~ {}/{} {} -> {}", upgrade.source, upgrade.name, upgrade.from, upgrade.to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dpkg_parses_only_ii_rows() {
+        let output = "\
+Desired=Unknown/Install/Remove/Purge/Hold
+| Status=Not/Inst/Conf-files/Unpacked/halF-conf/Half-inst/trig-aWait/Trig-pend
+|/ Err?=(none)/Reinst-required (Status,Err: uppercase=bad)
+||/ Name           Version      Architecture Description
++++-==============-============-============-=====================
+ii  gdb            12.1-3       amd64        GNU debugger
+rc  old-pkg        1.0-1        amd64        removed, config remains
+ii  libc6:amd64    2.35-0ubuntu1 amd64        GNU C Library
+";
+        let packages = parse_dpkg(output);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0], Package { name: "gdb".to_string(), version: "12.1-3".to_string(), source: "apt".to_string() });
+        assert_eq!(packages[1].name, "libc6:amd64");
+    }
+
+    #[test]
+    fn pip_freeze_skips_vcs_installs_with_no_pinned_version() {
+        let output = "requests==2.31.0\n-e git+https://example.com/pkg.git#egg=pkg\npwntools==4.12.0\n";
+        let packages = parse_pip_freeze(output);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "requests");
+        assert_eq!(packages[0].version, "2.31.0");
+        assert_eq!(packages[0].source, "pip");
+    }
+
+    #[test]
+    fn cargo_install_list_ignores_indented_binary_lines() {
+        let output = "ripgrep v13.0.0:\n    rg\nbat v0.24.0:\n    bat\n";
+        let packages = parse_cargo_install_list(output);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0], Package { name: "ripgrep".to_string(), version: "13.0.0".to_string(), source: "cargo".to_string() });
+        assert_eq!(packages[1].name, "bat");
+    }
+
+    #[test]
+    fn gem_list_keeps_only_the_first_listed_version() {
+        let output = "*** LOCAL GEMS ***\n\nrake (13.0.6, 13.0.1)\nbundler (2.4.10)\n";
+        let packages = parse_gem_list(output);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0], Package { name: "rake".to_string(), version: "13.0.6".to_string(), source: "gem".to_string() });
+        assert_eq!(packages[1].version, "2.4.10");
+    }
+
+    fn pkg(source: &str, name: &str, version: &str) -> Package {
+        Package { name: name.to_string(), version: version.to_string(), source: source.to_string() }
+    }
+
+    #[test]
+    fn diff_detects_added_removed_and_upgraded_packages() {
+        let before = Manifest { packages: vec![pkg("apt", "gdb", "12.1-3"), pkg("pip", "flask", "2.0.0")] };
+        let after = Manifest { packages: vec![pkg("apt", "gdb", "13.0-1"), pkg("pip", "requests", "2.31.0")] };
+
+        let result = diff(&before, &after);
+        assert_eq!(result.added, vec![pkg("pip", "requests", "2.31.0")]);
+        assert_eq!(result.removed, vec![pkg("pip", "flask", "2.0.0")]);
+        assert_eq!(
+            result.upgraded,
+            vec![Upgrade { name: "gdb".to_string(), source: "apt".to_string(), from: "12.1-3".to_string(), to: "13.0-1".to_string() }]
+        );
+    }
+
+    #[test]
+    fn identical_manifests_diff_to_nothing() {
+        let manifest = Manifest { packages: vec![pkg("apt", "gdb", "12.1-3")] };
+        assert_eq!(diff(&manifest, &manifest), ManifestDiff::default());
+    }
+
+    #[test]
+    fn same_name_different_source_does_not_collide() {
+        let before = Manifest { packages: vec![pkg("pip", "rake", "1.0.0")] };
+        let after = Manifest { packages: vec![pkg("pip", "rake", "1.0.0"), pkg("gem", "rake", "13.0.6")] };
+        let result = diff(&before, &after);
+        assert_eq!(result.added, vec![pkg("gem", "rake", "13.0.6")]);
+        assert!(result.upgraded.is_empty());
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let runtime = RuntimeDir::new(&format!("manifest-test-{}", std::process::id()));
+        runtime.ensure_exists().unwrap();
+        let manifest = Manifest { packages: vec![pkg("apt", "gdb", "12.1-3")] };
+        save(&runtime, &manifest).unwrap();
+        let loaded = load(&manifest_path(&runtime)).unwrap();
+        assert_eq!(loaded.packages, manifest.packages);
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+}
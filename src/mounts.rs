@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{PwnenvError, Result};
+
+/// A bind mount into the environment's container, beyond the primary
+/// `workspace_dir` mount — e.g. a shared `common/` directory alongside a
+/// per-challenge one in a finals setup. Recorded in the runtime dir so
+/// `rebuild`/`status` can reproduce and display it without re-resolving
+/// relative paths against a cwd that may have since changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub host: PathBuf,
+    pub container: String,
+}
+
+/// Parses a single `host:container` pair, resolving a relative `host`
+/// against `base_dir` (the directory `init` was invoked from, not the
+/// process's current one by the time this runs).
+pub fn parse(spec: &str, base_dir: &Path) -> Result<Mount> {
+    let (host, container) = spec
+        .split_once(':')
+        .ok_or_else(|| PwnenvError::InvalidMount(spec.to_string()))?;
+    if host.is_empty() || container.is_empty() {
+        return Err(PwnenvError::InvalidMount(spec.to_string()));
+    }
+
+    let host = Path::new(host);
+    let host = if host.is_absolute() {
+        host.to_path_buf()
+    } else {
+        base_dir.join(host)
+    };
+    crate::host_path::validate(&host)?;
+
+    Ok(Mount {
+        host,
+        container: container.to_string(),
+    })
+}
+
+/// Merges `cli_mounts` (from repeated `init --mount`) and `config_mounts`
+/// (the `mounts` config key), resolves relative host paths against
+/// `base_dir`, and validates that every host path exists and (unless
+/// `force` is set) no two mounts target the same container path. `force`
+/// is `init --force`'s overlap-check bypass: the host-path-exists check
+/// still runs, since skipping it would hand `docker` a bind mount that
+/// can't possibly work rather than one that merely shadows another.
+pub fn resolve(cli_mounts: &[String], config_mounts: &[String], base_dir: &Path, force: bool) -> Result<Vec<Mount>> {
+    let mounts: Vec<Mount> = cli_mounts
+        .iter()
+        .chain(config_mounts.iter())
+        .map(|spec| parse(spec, base_dir))
+        .collect::<Result<_>>()?;
+
+    for mount in &mounts {
+        if !mount.host.exists() {
+            return Err(PwnenvError::MountHostMissing(mount.host.clone()));
+        }
+    }
+
+    if !force {
+        for (i, a) in mounts.iter().enumerate() {
+            for b in &mounts[i + 1..] {
+                if a.container == b.container {
+                    return Err(PwnenvError::MountCollision {
+                        path: a.container.clone(),
+                        first: a.host.display().to_string(),
+                        second: b.host.display().to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(mounts)
+}
+
+/// Serializes `mounts` as `host:container` lines, for
+/// [`crate::runtime::RuntimeDir::set_mounts`].
+pub fn encode(mounts: &[Mount]) -> String {
+    mounts
+        .iter()
+        .map(|m| format!("{}:{}", m.host.display(), m.container))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parses back what [`encode`] wrote. Host paths are already absolute, so
+/// `base_dir` doesn't matter here; pass the runtime root for clarity.
+pub fn decode(raw: &str) -> Vec<Mount> {
+    raw.lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| parse(line, Path::new(".")).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_host_path_resolves_against_base_dir() {
+        let base = std::env::temp_dir().join("pwnenv-mounts-test-relative");
+        std::fs::create_dir_all(base.join("common")).unwrap();
+        let mount = parse("common:/common", &base).unwrap();
+        assert_eq!(mount.host, base.join("common"));
+        assert_eq!(mount.container, "/common");
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn missing_colon_is_rejected() {
+        let base = std::env::temp_dir();
+        assert!(parse("common", &base).is_err());
+    }
+
+    #[test]
+    fn collision_between_two_mounts_is_rejected() {
+        let base = std::env::temp_dir().join("pwnenv-mounts-test-collision");
+        std::fs::create_dir_all(base.join("a")).unwrap();
+        std::fs::create_dir_all(base.join("b")).unwrap();
+        let cli = vec!["a:/shared".to_string(), "b:/shared".to_string()];
+        let err = resolve(&cli, &[], &base, false).unwrap_err();
+        assert!(matches!(err, PwnenvError::MountCollision { .. }));
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn force_bypasses_the_collision_check() {
+        let base = std::env::temp_dir().join("pwnenv-mounts-test-force");
+        std::fs::create_dir_all(base.join("a")).unwrap();
+        std::fs::create_dir_all(base.join("b")).unwrap();
+        let cli = vec!["a:/shared".to_string(), "b:/shared".to_string()];
+        let mounts = resolve(&cli, &[], &base, true).unwrap();
+        assert_eq!(mounts.len(), 2);
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn missing_host_path_is_rejected() {
+        let base = std::env::temp_dir().join("pwnenv-mounts-test-missing");
+        std::fs::remove_dir_all(&base).ok();
+        let cli = vec!["nope:/nope".to_string()];
+        let err = resolve(&cli, &[], &base, false).unwrap_err();
+        assert!(matches!(err, PwnenvError::MountHostMissing(_)));
+    }
+
+    #[test]
+    fn encode_decode_roundtrips() {
+        let mounts = vec![
+            Mount { host: PathBuf::from("/a"), container: "/b".to_string() },
+            Mount { host: PathBuf::from("/c"), container: "/d".to_string() },
+        ];
+        assert_eq!(decode(&encode(&mounts)), mounts);
+    }
+}
@@ -0,0 +1,118 @@
+//! Shared human-output formatting: section headers, key-value blocks,
+//! status glyphs, and column-aligned tables, so `status`/`list-profiles`/
+//! `ps`/future subcommands stop each inventing their own `println!`
+//! layout. Every subcommand should still pair its table with a
+//! serializable struct and a `--format json`/`json: bool` escape hatch
+//! (see [`crate::commands::list_profiles::list_profiles`]) for scripts;
+//! this module is for the human path only.
+use std::io::IsTerminal;
+
+/// Resolves once per command invocation whether ANSI color is worth
+/// emitting. `NO_COLOR` (https://no-color.org) and the CLI's own
+/// `--no-color` flag both unconditionally disable it; otherwise it's on
+/// only when stdout is a real terminal, so redirecting to a file or pipe
+/// doesn't litter the output with escape codes.
+pub struct Style {
+    color: bool,
+}
+
+impl Style {
+    pub fn resolve(no_color_flag: bool) -> Style {
+        let color = !no_color_flag && std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal();
+        Style { color }
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// A bold section header, e.g. `"environments:"` above a table.
+    pub fn header(&self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    /// Renders `headers`/`rows` as a space-padded column table, each
+    /// column as wide as its widest cell (header included) plus one
+    /// space of padding — the layout `list-profiles`/`ps` already hand-roll
+    /// with `{:<20}`-style format strings, generalized to any column set.
+    pub fn table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+        for row in rows {
+            for (i, cell) in row.iter().enumerate() {
+                if let Some(w) = widths.get_mut(i) {
+                    *w = (*w).max(cell.len());
+                }
+            }
+        }
+
+        let mut out = String::new();
+        out.push_str(&self.header(&pad_row(headers.iter().map(|h| h.to_string()).collect(), &widths)));
+        for row in rows {
+            out.push('\n');
+            out.push_str(&pad_row(row.clone(), &widths));
+        }
+        out
+    }
+}
+
+fn pad_row(cells: Vec<String>, widths: &[usize]) -> String {
+    cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| {
+            let width = widths.get(i).copied().unwrap_or(0);
+            format!("{cell:<width$}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_style_emits_no_escape_codes() {
+        let style = Style::resolve(true);
+        assert_eq!(style.header("environments:"), "environments:");
+    }
+
+    #[test]
+    fn table_pads_columns_to_their_widest_cell() {
+        let style = Style::resolve(true);
+        let rendered = style.table(
+            &["name", "state"],
+            &[
+                vec!["chall".to_string(), "running".to_string()],
+                vec!["a".to_string(), "not up".to_string()],
+            ],
+        );
+        assert_eq!(rendered, "name  state\nchall running\na     not up");
+    }
+
+    #[test]
+    fn golden_list_profiles_row_with_color_disabled() {
+        let style = Style::resolve(true);
+        let rendered = style.table(
+            &["env", "state", "port"],
+            &[vec!["chall".to_string(), "running".to_string(), "1337".to_string()]],
+        );
+        assert_eq!(rendered, "env   state   port\nchall running 1337");
+    }
+
+    #[test]
+    fn golden_ps_row_with_color_disabled() {
+        let style = Style::resolve(true);
+        let rendered = style.table(
+            &["env", "state", "cpu", "memory"],
+            &[vec!["chall".to_string(), "running".to_string(), "0.5%".to_string(), "12MiB".to_string()]],
+        );
+        assert_eq!(rendered, "env   state   cpu  memory\nchall running 0.5% 12MiB");
+    }
+}
@@ -0,0 +1,53 @@
+use crate::config::ToolConfig;
+
+/// A named, ready-made base image plus the tool tweaks it needs (older
+/// releases often need different apt invocations, e.g. `apt-get update`
+/// against archived/EOL mirrors). Selected via `Config.preset`.
+pub struct Preset {
+    pub base_image: &'static str,
+    /// Tools prepended to the user's tool list, ahead of anything they
+    /// define themselves (a user tool with the same name overrides it).
+    pub tools: &'static [(&'static str, &'static [&'static str])],
+}
+
+pub fn lookup(name: &str) -> Option<Preset> {
+    let preset = match name {
+        "ubuntu-22.04" => Preset {
+            base_image: "ubuntu:22.04",
+            tools: &[],
+        },
+        "ubuntu-18.04" => Preset {
+            base_image: "ubuntu:18.04",
+            tools: &[(
+                "apt-sources",
+                &["RUN sed -i 's|archive.ubuntu.com|old-releases.ubuntu.com|g' /etc/apt/sources.list"],
+            )],
+        },
+        "debian-10" => Preset {
+            base_image: "debian:10",
+            tools: &[(
+                "apt-sources",
+                &["RUN sed -i 's|deb.debian.org|archive.debian.org|g; s|security.debian.org|archive.debian.org|g' /etc/apt/sources.list"],
+            )],
+        },
+        _ => return None,
+    };
+    Some(preset)
+}
+
+impl Preset {
+    pub fn tool_configs(&self) -> Vec<ToolConfig> {
+        self.tools
+            .iter()
+            .map(|(name, script)| ToolConfig {
+                name: name.to_string(),
+                script: script.iter().map(|s| s.to_string()).collect(),
+                build_only: false,
+                append: false,
+                artifacts: Vec::new(),
+                verify: Vec::new(),
+                secrets: Vec::new(),
+            })
+            .collect()
+    }
+}
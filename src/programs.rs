@@ -0,0 +1,929 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+
+use filetime::FileTime;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::error::{PwnenvError, Result};
+
+/// What to do about `.gitignore`/explicit excludes while walking the
+/// programs directory. Built once by the caller (`init`) and threaded
+/// through so [`copy_programs`] doesn't need to know about [`crate::config::Config`].
+#[derive(Debug, Default, Clone)]
+pub struct CopyFilter {
+    pub respect_gitignore: bool,
+    pub exclude: Vec<String>,
+    pub force_include: Vec<String>,
+    /// When non-empty, only files matching one of these patterns are
+    /// copied at all — everything else is skipped, as if excluded —
+    /// instead of the whole directory. `force_include` still wins over
+    /// this, same as it already wins over `exclude`/`.gitignore`.
+    pub include: Vec<String>,
+    /// When false (the default), symlinks whose target resolves outside
+    /// `src` are skipped with a warning instead of copied, since
+    /// following them risks pulling in huge or sensitive files from
+    /// outside the challenge directory. `--follow-external-symlinks`.
+    pub follow_external_symlinks: bool,
+}
+
+/// Where a copied `programs_dir` ends up inside the container: baked into
+/// the image by a `COPY` (unless `init --no-copy`) and/or bind-mounted
+/// from the runtime dir's `programs/` snapshot (unless `init --no-mount`).
+pub const PROGRAMS_CONTAINER_PATH: &str = "/programs";
+
+/// Files above this size aren't hashed — for a multi-GB handout the read
+/// would cost more than just re-copying, so size+mtime alone decide
+/// whether they changed.
+const HASH_SIZE_LIMIT: u64 = 1024 * 1024;
+
+/// What [`FileRecord::kind`] copies as.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum EntryKind {
+    /// A regular file; copied by content.
+    File,
+    /// A symlink; recreated as a symlink rather than followed. `target`
+    /// is the original link's resolved target, expressed relative to
+    /// `src`'s root; at copy time this is re-anchored under `dest` so
+    /// the recreated symlink resolves there instead of back into `src`.
+    Symlink { target: String },
+}
+
+/// One entry's fingerprint in a [`Manifest`], keyed by path relative to
+/// the programs dir.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileRecord {
+    size: u64,
+    mtime_secs: u64,
+    /// Permission bits, preserved on copy so the executable bit survives.
+    /// Meaningless for symlinks.
+    mode: u32,
+    /// `None` for files above [`HASH_SIZE_LIMIT`] or for symlinks;
+    /// absence of a hash falls back to comparing `size`/`mtime_secs`
+    /// (and, for symlinks, the target) alone.
+    hash: Option<u64>,
+    kind: EntryKind,
+}
+
+/// A snapshot of a programs directory's contents, used to diff against
+/// the manifest saved from the previous `init` so re-copying only touches
+/// what actually changed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest(HashMap<String, FileRecord>);
+
+/// Tally of what [`copy_programs`] actually did, so callers (`init`,
+/// `status`) can report it.
+#[derive(Debug, Default)]
+pub struct CopyReport {
+    pub copied: usize,
+    pub skipped: usize,
+    pub deleted: usize,
+    pub skipped_gitignore: usize,
+    pub skipped_excluded: usize,
+    pub skipped_not_included: usize,
+    pub skipped_special: usize,
+    pub skipped_external_symlink: usize,
+}
+
+/// Copies the challenge's `programs` directory into the environment's
+/// runtime dir so it can be baked into the image or bind-mounted.
+///
+/// Compares a manifest of `src` against the one saved at `manifest_path`
+/// from the previous run: identical manifests skip the copy entirely;
+/// otherwise only added/changed files are copied and files removed from
+/// `src` are deleted from `dest`, instead of blowing the whole directory
+/// away. `force` (`init --force-copy`) ignores the saved manifest and
+/// copies everything, as if this were the first run.
+///
+/// Also safe to re-run after an interrupted copy: a file already present
+/// at `dest` that matches its manifest record is recognized as done via
+/// [`dest_already_matches`] and skipped, even if the interrupted run was
+/// killed before `save_manifest` ever ran.
+pub fn copy_programs(
+    src: &Path,
+    dest: &Path,
+    manifest_path: &Path,
+    force: bool,
+    filter: &CopyFilter,
+) -> Result<CopyReport> {
+    let mut report = CopyReport::default();
+    let walked = filtered_entries(src, dest, filter)?;
+    report.skipped_gitignore = walked.skipped_gitignore;
+    report.skipped_excluded = walked.skipped_excluded;
+    report.skipped_not_included = walked.skipped_not_included;
+    report.skipped_special = walked.skipped_special;
+    report.skipped_external_symlink = walked.skipped_external_symlink;
+    let new_manifest = build_manifest(src, walked.entries)?;
+    let old_manifest = if force {
+        Manifest::default()
+    } else {
+        load_manifest(manifest_path)
+    };
+
+    if !force && new_manifest.0 == old_manifest.0 {
+        report.skipped = new_manifest.0.len();
+        save_manifest(manifest_path, &new_manifest)?;
+        return Ok(report);
+    }
+
+    // Directories must exist before any of their files are copied into
+    // them in parallel below, so this pass stays sequential.
+    std::fs::create_dir_all(dest)?;
+    for relative in new_manifest.0.keys() {
+        if let Some(parent) = dest.join(relative).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    // A file can already be sitting at `dest` in its final, correct state
+    // without the saved manifest knowing about it — e.g. a prior run copied
+    // it and was then killed before reaching `save_manifest`. Checking
+    // `dest`'s actual on-disk state (not just the manifest) is what lets a
+    // retried copy skip work a killed run already finished, instead of
+    // re-copying the whole directory because nothing was ever saved.
+    let to_copy: Vec<&String> = new_manifest
+        .0
+        .iter()
+        .filter(|(relative, record)| {
+            if old_manifest.0.get(*relative) == Some(*record) {
+                return false;
+            }
+            force || !dest_already_matches(dest, relative, record)
+        })
+        .map(|(relative, _)| relative)
+        .collect();
+
+    let copy_bytes: u64 = to_copy
+        .iter()
+        .map(|relative| new_manifest.0[*relative].size)
+        .sum();
+    let progress = ProgressBar::new(copy_bytes);
+
+    let failures: Vec<String> = to_copy
+        .par_iter()
+        .filter_map(|relative| {
+            let record = &new_manifest.0[*relative];
+            let result = copy_one(src, dest, relative, record);
+            progress.inc(record.size);
+            result.err().map(|e| format!("{relative}: {e}"))
+        })
+        .collect();
+    progress.finish_and_clear();
+
+    if !failures.is_empty() {
+        return Err(PwnenvError::ProgramsCopyFailed(failures));
+    }
+    report.copied = to_copy.len();
+    report.skipped = new_manifest.0.len() - to_copy.len();
+
+    for relative in old_manifest.0.keys() {
+        if !new_manifest.0.contains_key(relative) {
+            let target = dest.join(relative);
+            if target.exists() {
+                std::fs::remove_file(&target)?;
+            }
+            report.deleted += 1;
+        }
+    }
+
+    save_manifest(manifest_path, &new_manifest)?;
+    Ok(report)
+}
+
+/// One file or symlink [`filtered_entries`] decided to copy, tagged with
+/// how to copy it.
+struct IncludedEntry {
+    entry: walkdir::DirEntry,
+    kind: EntryKind,
+}
+
+/// Tally of entries [`filtered_entries`] decided to skip, alongside the
+/// ones it kept.
+struct WalkResult {
+    entries: Vec<IncludedEntry>,
+    skipped_gitignore: usize,
+    skipped_excluded: usize,
+    skipped_not_included: usize,
+    skipped_special: usize,
+    skipped_external_symlink: usize,
+}
+
+/// Resolves `path` the way [`Path::canonicalize`] would (following every
+/// symlink), but still returns a usable path when `path` itself doesn't
+/// exist yet — as `dest` doesn't on a fresh environment's first copy —
+/// by canonicalizing the nearest existing ancestor and rejoining the
+/// rest literally.
+fn canonicalize_best_effort(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => canonicalize_best_effort(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Walks `src`, classifying each file/symlink into what to copy and what
+/// to skip (`.gitignore`, `filter.exclude`, `filter.include` not
+/// matching, special files, and symlinks that resolve outside `src`) —
+/// unless listed in `filter.force_include`, which always wins over all
+/// of `.gitignore`/`exclude`/`include`.
+///
+/// `dest` (not yet created when this runs on a fresh environment) is
+/// never descended into even when it turns out to live inside `src` —
+/// e.g. `programs_dir` pointing at an ancestor of the runtime dir, or a
+/// symlink inside `src` that resolves there. Without this, copying `src`
+/// into `dest` would copy `dest` into itself, and every subsequent
+/// `init` would re-copy a bigger nested copy until the disk fills.
+fn filtered_entries(src: &Path, dest: &Path, filter: &CopyFilter) -> Result<WalkResult> {
+    let gitignore = if filter.respect_gitignore {
+        Some(build_gitignore_matcher(src)?)
+    } else {
+        None
+    };
+    let exclude = if filter.exclude.is_empty() {
+        None
+    } else {
+        Some(build_pattern_matcher(src, &filter.exclude)?)
+    };
+    let include = if filter.include.is_empty() {
+        None
+    } else {
+        Some(build_pattern_matcher(src, &filter.include)?)
+    };
+
+    let mut result = WalkResult {
+        entries: Vec::new(),
+        skipped_gitignore: 0,
+        skipped_excluded: 0,
+        skipped_not_included: 0,
+        skipped_special: 0,
+        skipped_external_symlink: 0,
+    };
+
+    let canonical_dest = canonicalize_best_effort(dest);
+
+    // `min_depth(1)` skips the root entry itself — otherwise a `src` that
+    // is itself a symlink (as in the test below) gets walked as a
+    // zero-length-relative-path entry of its own, which `copy_one` can't
+    // meaningfully copy.
+    let mut walker = WalkDir::new(src).follow_links(false).min_depth(1).into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = entry.map_err(std::io::Error::from)?;
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() && canonicalize_best_effort(entry.path()) == canonical_dest {
+            eprintln!(
+                "programs: skipping {} (this is pwnenv's own destination directory; \
+                 copying it into itself would recurse until the disk fills)",
+                entry.path().display()
+            );
+            walker.skip_current_dir();
+            continue;
+        }
+
+        if file_type.is_dir() {
+            continue;
+        }
+        if !file_type.is_file() && !file_type.is_symlink() {
+            eprintln!(
+                "programs: skipping special file {} (not a regular file or symlink)",
+                entry.path().display()
+            );
+            result.skipped_special += 1;
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(src).unwrap_or(entry.path());
+        let relative_str = relative.to_string_lossy();
+        let forced = filter.force_include.iter().any(|p| p == relative_str.as_ref());
+
+        if !forced {
+            if let Some(include) = &include {
+                if !include.matched_path_or_any_parents(relative, false).is_ignore() {
+                    result.skipped_not_included += 1;
+                    continue;
+                }
+            }
+            if let Some(gitignore) = &gitignore {
+                if gitignore.matched_path_or_any_parents(relative, false).is_ignore() {
+                    result.skipped_gitignore += 1;
+                    continue;
+                }
+            }
+            if let Some(exclude) = &exclude {
+                if exclude.matched_path_or_any_parents(relative, false).is_ignore() {
+                    result.skipped_excluded += 1;
+                    continue;
+                }
+            }
+        }
+
+        if file_type.is_symlink() {
+            match classify_symlink(src, entry.path(), filter.follow_external_symlinks)? {
+                Some(kind) => result.entries.push(IncludedEntry { entry, kind }),
+                None => {
+                    eprintln!(
+                        "programs: skipping {} (symlink resolves outside the programs dir)",
+                        entry.path().display()
+                    );
+                    result.skipped_external_symlink += 1;
+                }
+            }
+            continue;
+        }
+
+        result.entries.push(IncludedEntry { entry, kind: EntryKind::File });
+    }
+
+    Ok(result)
+}
+
+/// Decides how a symlink at `path` (inside `src`) should be recorded. If
+/// its target resolves inside `src`, returns
+/// `Some(EntryKind::Symlink { target })` with `target` expressed
+/// relative to `src`'s root — regardless of whether the original link
+/// was relative or absolute — so [`copy_programs`] can re-anchor it
+/// under `dest`. If the target resolves outside `src`, returns `None`
+/// (skip) unless `follow_external_symlinks` is set, in which case the
+/// symlink is followed and copied as a regular file instead.
+fn classify_symlink(src: &Path, path: &Path, follow_external_symlinks: bool) -> Result<Option<EntryKind>> {
+    let raw_target = std::fs::read_link(path)?;
+    let absolute_target = if raw_target.is_absolute() {
+        raw_target.clone()
+    } else {
+        path.parent().unwrap_or(src).join(&raw_target)
+    };
+
+    let src_abs = src.canonicalize().unwrap_or_else(|_| src.to_path_buf());
+    let target_abs = absolute_target
+        .canonicalize()
+        .unwrap_or_else(|_| absolute_target.clone());
+
+    if target_abs.starts_with(&src_abs) {
+        let relative_to_src = target_abs.strip_prefix(&src_abs).unwrap_or(&target_abs);
+        return Ok(Some(EntryKind::Symlink {
+            target: relative_to_src.to_string_lossy().into_owned(),
+        }));
+    }
+
+    if follow_external_symlinks {
+        return Ok(Some(EntryKind::File));
+    }
+    Ok(None)
+}
+
+/// Copies or relinks a single entry from `src` into `dest`, matching
+/// `record.kind`. Any file/symlink already at the destination is
+/// replaced first, since `std::fs::copy` refuses to overwrite a symlink
+/// and `symlink()` refuses to overwrite anything at all.
+fn copy_one(src: &Path, dest: &Path, relative: &str, record: &FileRecord) -> std::io::Result<()> {
+    let dest_path = dest.join(relative);
+    if dest_path.symlink_metadata().is_ok() {
+        std::fs::remove_file(&dest_path)?;
+    }
+
+    match &record.kind {
+        EntryKind::File => {
+            std::fs::copy(src.join(relative), &dest_path)?;
+            std::fs::set_permissions(&dest_path, std::fs::Permissions::from_mode(record.mode))?;
+            filetime::set_file_mtime(&dest_path, FileTime::from_unix_time(record.mtime_secs as i64, 0))?;
+        }
+        EntryKind::Symlink { target } => {
+            std::os::unix::fs::symlink(dest.join(target), &dest_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `dest`'s copy of `relative` already matches `record`, checked
+/// directly against the filesystem rather than the saved manifest — so a
+/// file a killed run already finished copying is recognized as done even
+/// though the manifest was never saved to say so.
+fn dest_already_matches(dest: &Path, relative: &str, record: &FileRecord) -> bool {
+    let dest_path = dest.join(relative);
+    let Ok(metadata) = std::fs::symlink_metadata(&dest_path) else {
+        return false;
+    };
+
+    match &record.kind {
+        EntryKind::File => {
+            if !metadata.is_file() {
+                return false;
+            }
+            let Ok(mtime_secs) = metadata
+                .modified()
+                .map(|t| t.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0))
+            else {
+                return false;
+            };
+            metadata.len() == record.size
+                && mtime_secs == record.mtime_secs
+                && metadata.permissions().mode() & 0o777 == record.mode & 0o777
+        }
+        EntryKind::Symlink { target } => {
+            metadata.file_type().is_symlink()
+                && std::fs::read_link(&dest_path).ok().as_deref() == Some(dest.join(target).as_path())
+        }
+    }
+}
+
+fn build_gitignore_matcher(src: &Path) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(src);
+    for entry in WalkDir::new(src) {
+        let entry = entry.map_err(std::io::Error::from)?;
+        if entry.file_type().is_file() && entry.file_name() == ".gitignore" {
+            if let Some(err) = builder.add(entry.path()) {
+                return Err(PwnenvError::Docker(format!("failed to parse {}: {err}", entry.path().display())));
+            }
+        }
+    }
+    builder
+        .build()
+        .map_err(|e| PwnenvError::Docker(format!("failed to build gitignore matcher: {e}")))
+}
+
+fn build_pattern_matcher(src: &Path, patterns: &[String]) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(src);
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .map_err(|e| PwnenvError::Docker(format!("invalid exclude pattern '{pattern}': {e}")))?;
+    }
+    builder
+        .build()
+        .map_err(|e| PwnenvError::Docker(format!("failed to build exclude matcher: {e}")))
+}
+
+fn build_manifest(src: &Path, included: Vec<IncludedEntry>) -> Result<Manifest> {
+    let bytes_done = AtomicU64::new(0);
+    let total_bytes: u64 = included
+        .iter()
+        .filter_map(|e| e.entry.metadata().ok())
+        .map(|m| m.len())
+        .sum();
+    let progress = ProgressBar::new(total_bytes);
+
+    let records: Vec<Result<(String, FileRecord)>> = included
+        .par_iter()
+        .map(|included| {
+            let entry = &included.entry;
+            let relative = entry
+                .path()
+                .strip_prefix(src)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .into_owned();
+
+            // `entry.metadata()` respects the walker's `follow_links(false)`:
+            // for a real symlink that's the link's own metadata (size 0);
+            // for a followed external symlink (`EntryKind::File` over a
+            // `walkdir` symlink entry) we need the *target*'s metadata.
+            let is_followed_symlink =
+                matches!(included.kind, EntryKind::File) && entry.file_type().is_symlink();
+            let metadata = if is_followed_symlink {
+                std::fs::metadata(entry.path())?
+            } else {
+                entry.metadata().map_err(std::io::Error::from)?
+            };
+
+            let size = metadata.len();
+            let mtime_secs = metadata
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let mode = metadata.permissions().mode();
+
+            let (hash, record_size) = match &included.kind {
+                EntryKind::File => {
+                    let hash = if size <= HASH_SIZE_LIMIT {
+                        Some(hash_file(entry.path())?)
+                    } else {
+                        None
+                    };
+                    (hash, size)
+                }
+                EntryKind::Symlink { target } => {
+                    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                    target.hash(&mut hasher);
+                    (Some(hasher.finish()), 0)
+                }
+            };
+
+            let done = bytes_done.fetch_add(record_size, Ordering::Relaxed) + record_size;
+            progress.set_position(done);
+
+            Ok((
+                relative,
+                FileRecord { size: record_size, mtime_secs, mode, hash, kind: included.kind.clone() },
+            ))
+        })
+        .collect();
+    progress.finish_and_clear();
+
+    let mut manifest = Manifest::default();
+    let mut failures = Vec::new();
+    for record in records {
+        match record {
+            Ok((relative, record)) => {
+                manifest.0.insert(relative, record);
+            }
+            Err(e) => failures.push(e.to_string()),
+        }
+    }
+    if !failures.is_empty() {
+        return Err(PwnenvError::ProgramsCopyFailed(failures));
+    }
+    Ok(manifest)
+}
+
+fn hash_file(path: &Path) -> Result<u64> {
+    let contents = std::fs::read(path)?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+fn load_manifest(path: &Path) -> Manifest {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(path: &Path, manifest: &Manifest) -> Result<()> {
+    let rendered = serde_json::to_string(manifest).map_err(|e| {
+        crate::error::PwnenvError::Docker(format!("failed to serialize programs manifest: {e}"))
+    })?;
+    std::fs::write(path, rendered)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn setup() -> (PathBuf, PathBuf, PathBuf) {
+        let base = std::env::temp_dir().join(format!(
+            "pwnenv-programs-test-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::remove_dir_all(&base).ok();
+        let src = base.join("src");
+        let dest = base.join("dest");
+        let manifest_path = base.join("manifest.json");
+        std::fs::create_dir_all(&src).unwrap();
+        (src, dest, manifest_path)
+    }
+
+    #[test]
+    fn unchanged_directory_is_fully_skipped() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 0);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.deleted, 0);
+    }
+
+    #[test]
+    fn added_file_is_copied_without_touching_unchanged_ones() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+
+        write(&src, "libc.so.6", "library contents");
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 1);
+        assert_eq!(report.skipped, 1);
+        assert!(dest.join("libc.so.6").exists());
+    }
+
+    #[test]
+    fn modified_file_is_recopied() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+
+        write(&src, "chall", "different contents");
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 1);
+        assert_eq!(std::fs::read_to_string(dest.join("chall")).unwrap(), "different contents");
+    }
+
+    #[test]
+    fn removed_file_is_deleted_from_dest() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        write(&src, "extra", "extra contents");
+        copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+
+        std::fs::remove_file(src.join("extra")).unwrap();
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.deleted, 1);
+        assert!(!dest.join("extra").exists());
+        assert!(dest.join("chall").exists());
+    }
+
+    #[test]
+    fn force_copy_ignores_the_saved_manifest() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+
+        let report = copy_programs(&src, &dest, &manifest_path, true, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 1);
+        assert_eq!(report.skipped, 0);
+    }
+
+    #[test]
+    fn retry_after_an_interrupted_run_skips_files_the_killed_run_already_finished() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        write(&src, "libc.so.6", "library contents");
+
+        // Simulate a run that finished copying `chall` but was killed
+        // before copying `libc.so.6` or ever reaching `save_manifest`.
+        std::fs::create_dir_all(&dest).unwrap();
+        std::fs::copy(src.join("chall"), dest.join("chall")).unwrap();
+        let chall_mtime = std::fs::metadata(src.join("chall")).unwrap().modified().unwrap();
+        filetime::set_file_mtime(dest.join("chall"), FileTime::from_system_time(chall_mtime)).unwrap();
+        assert!(!manifest_path.exists());
+
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 1);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(std::fs::read_to_string(dest.join("libc.so.6")).unwrap(), "library contents");
+    }
+
+    #[test]
+    fn incremental_copy_converges_on_the_same_tree_a_full_copy_would_produce() {
+        let (src, dest_full, manifest_full) = setup();
+        let dest_incremental = src.parent().unwrap().join("dest-incremental");
+        let manifest_incremental = src.parent().unwrap().join("manifest-incremental.json");
+        std::fs::remove_dir_all(&dest_incremental).ok();
+        std::fs::remove_file(&manifest_incremental).ok();
+
+        write(&src, "chall", "binary contents");
+        write(&src, "libc.so.6", "library contents");
+
+        // Incremental: two separate `copy_programs` calls, each only
+        // seeing part of `src` change — the scenario `--sync` exists
+        // for, an `init` re-run after the challenge directory grew.
+        copy_programs(&src, &dest_incremental, &manifest_incremental, false, &CopyFilter::default()).unwrap();
+        write(&src, "exploit.py", "exploit contents");
+        copy_programs(&src, &dest_incremental, &manifest_incremental, false, &CopyFilter::default()).unwrap();
+
+        // Full: one `copy_programs` call against `src` in its final state.
+        copy_programs(&src, &dest_full, &manifest_full, false, &CopyFilter::default()).unwrap();
+
+        for name in ["chall", "libc.so.6", "exploit.py"] {
+            assert_eq!(
+                std::fs::read_to_string(dest_full.join(name)).unwrap(),
+                std::fs::read_to_string(dest_incremental.join(name)).unwrap(),
+                "{name} differs between the full and incremental copy"
+            );
+        }
+
+        std::fs::remove_dir_all(&dest_incremental).ok();
+        std::fs::remove_file(&manifest_incremental).ok();
+    }
+
+    #[test]
+    fn gitignored_files_are_skipped_when_respected() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        write(&src, "venv/bin/python", "venv contents");
+        write(&src, ".gitignore", "venv/\n");
+
+        let filter = CopyFilter { respect_gitignore: true, ..CopyFilter::default() };
+        let report = copy_programs(&src, &dest, &manifest_path, false, &filter).unwrap();
+        assert_eq!(report.skipped_gitignore, 1);
+        assert!(dest.join("chall").exists());
+        assert!(!dest.join("venv/bin/python").exists());
+    }
+
+    #[test]
+    fn explicit_exclude_pattern_skips_matching_files() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        write(&src, "notes.txt", "scratch notes");
+
+        let filter = CopyFilter {
+            exclude: vec!["*.txt".to_string()],
+            ..CopyFilter::default()
+        };
+        let report = copy_programs(&src, &dest, &manifest_path, false, &filter).unwrap();
+        assert_eq!(report.skipped_excluded, 1);
+        assert!(!dest.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn include_pattern_restricts_the_copy_to_matching_files() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        write(&src, "libc.so.6", "library contents");
+        write(&src, "notes.txt", "scratch notes");
+
+        let filter = CopyFilter {
+            include: vec!["chall".to_string(), "libc*".to_string()],
+            ..CopyFilter::default()
+        };
+        let report = copy_programs(&src, &dest, &manifest_path, false, &filter).unwrap();
+        assert_eq!(report.copied, 2);
+        assert_eq!(report.skipped_not_included, 1);
+        assert!(dest.join("chall").exists());
+        assert!(dest.join("libc.so.6").exists());
+        assert!(!dest.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn empty_include_copies_everything() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        write(&src, "notes.txt", "scratch notes");
+
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 2);
+        assert_eq!(report.skipped_not_included, 0);
+    }
+
+    #[test]
+    fn force_include_overrides_a_non_matching_include_pattern() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        write(&src, "flag.txt", "flag contents");
+
+        let filter = CopyFilter {
+            include: vec!["chall".to_string()],
+            force_include: vec!["flag.txt".to_string()],
+            ..CopyFilter::default()
+        };
+        let report = copy_programs(&src, &dest, &manifest_path, false, &filter).unwrap();
+        assert_eq!(report.skipped_not_included, 0);
+        assert!(dest.join("flag.txt").exists());
+    }
+
+    #[test]
+    fn force_include_overrides_gitignore() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "venv/bin/python", "venv contents");
+        write(&src, ".gitignore", "venv/\n");
+
+        let filter = CopyFilter {
+            respect_gitignore: true,
+            force_include: vec!["venv/bin/python".to_string()],
+            ..CopyFilter::default()
+        };
+        let report = copy_programs(&src, &dest, &manifest_path, false, &filter).unwrap();
+        assert_eq!(report.skipped_gitignore, 0);
+        assert!(dest.join("venv/bin/python").exists());
+    }
+
+    #[test]
+    fn internal_symlink_is_preserved_as_a_symlink() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "libc.so.6", "library contents");
+        std::os::unix::fs::symlink("libc.so.6", src.join("libc.so")).unwrap();
+
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 2);
+        let metadata = std::fs::symlink_metadata(dest.join("libc.so")).unwrap();
+        assert!(metadata.file_type().is_symlink());
+        assert_eq!(std::fs::read_link(dest.join("libc.so")).unwrap(), dest.join("libc.so.6"));
+    }
+
+    #[test]
+    fn executable_bit_is_preserved() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        let mut perms = std::fs::metadata(src.join("chall")).unwrap().permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(src.join("chall"), perms).unwrap();
+
+        copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        let mode = std::fs::metadata(dest.join("chall")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[test]
+    fn mtime_is_preserved() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        let source_mtime = std::fs::metadata(src.join("chall")).unwrap().modified().unwrap();
+
+        copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        let dest_mtime = std::fs::metadata(dest.join("chall")).unwrap().modified().unwrap();
+        assert_eq!(
+            dest_mtime.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            source_mtime.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        );
+    }
+
+    #[test]
+    fn special_file_is_skipped_with_a_warning() {
+        let (src, dest, manifest_path) = setup();
+        write(&src, "chall", "binary contents");
+        let fifo_path = src.join("chall.fifo");
+        let status = std::process::Command::new("mkfifo").arg(&fifo_path).status().unwrap();
+        assert!(status.success());
+
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.skipped_special, 1);
+        assert!(!dest.join("chall.fifo").exists());
+    }
+
+    #[test]
+    fn external_symlink_is_skipped_by_default() {
+        let (src, dest, manifest_path) = setup();
+        let outside = src.parent().unwrap().join("outside-target");
+        write(outside.parent().unwrap(), "outside-target", "outside contents");
+        std::os::unix::fs::symlink(&outside, src.join("link")).unwrap();
+
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.skipped_external_symlink, 1);
+        assert!(!dest.join("link").exists());
+    }
+
+    #[test]
+    fn external_symlink_is_followed_when_enabled() {
+        let (src, dest, manifest_path) = setup();
+        let outside = src.parent().unwrap().join("outside-target");
+        write(outside.parent().unwrap(), "outside-target", "outside contents");
+        std::os::unix::fs::symlink(&outside, src.join("link")).unwrap();
+
+        let filter = CopyFilter { follow_external_symlinks: true, ..CopyFilter::default() };
+        let report = copy_programs(&src, &dest, &manifest_path, false, &filter).unwrap();
+        assert_eq!(report.skipped_external_symlink, 0);
+        assert_eq!(report.copied, 1);
+        let metadata = std::fs::symlink_metadata(dest.join("link")).unwrap();
+        assert!(!metadata.file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(dest.join("link")).unwrap(), "outside contents");
+    }
+
+    #[test]
+    fn destination_nested_inside_source_is_not_recursively_copied() {
+        let base = std::env::temp_dir().join(format!(
+            "pwnenv-programs-test-recursion-direct-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::remove_dir_all(&base).ok();
+        let src = base.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        write(&src, "chall", "binary contents");
+        let dest = src.join("dest");
+        let manifest_path = base.join("manifest.json");
+
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 1);
+        assert!(dest.join("chall").exists());
+
+        // dest now lives inside src; re-running must not walk into it.
+        let report = copy_programs(&src, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 0);
+        assert!(!dest.join("dest").exists());
+    }
+
+    #[test]
+    fn destination_reachable_through_a_symlinked_source_root_is_not_recursively_copied() {
+        let base = std::env::temp_dir().join(format!(
+            "pwnenv-programs-test-recursion-symlink-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::remove_dir_all(&base).ok();
+        let actual = base.join("actual");
+        std::fs::create_dir_all(&actual).unwrap();
+        write(&actual, "chall", "binary contents");
+        let link = base.join("link");
+        std::os::unix::fs::symlink(&actual, &link).unwrap();
+        let dest = actual.join("programs");
+        let manifest_path = base.join("manifest.json");
+
+        // src is given as a symlink whose real target is an ancestor of dest.
+        let report = copy_programs(&link, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 1);
+        assert!(dest.join("chall").exists());
+
+        let report = copy_programs(&link, &dest, &manifest_path, false, &CopyFilter::default()).unwrap();
+        assert_eq!(report.copied, 0);
+        assert!(!dest.join("programs").exists());
+    }
+}
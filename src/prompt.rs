@@ -0,0 +1,19 @@
+use std::io::Write as _;
+
+/// Asks the user to confirm `message`, defaulting to "no" on anything but
+/// an explicit `y`/`yes`. When `assume_yes` is set (the global
+/// `--yes`/`--assume-yes` flag), skips the prompt and returns `true`.
+pub fn confirm(message: &str, assume_yes: bool) -> bool {
+    if assume_yes {
+        return true;
+    }
+
+    print!("{message} [y/N] ");
+    let _ = std::io::stdout().flush();
+
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line).is_err() {
+        return false;
+    }
+    matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
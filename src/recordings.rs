@@ -0,0 +1,227 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::error::{PwnenvError, Result};
+
+/// Where `enter --record` copies a finished session to, named
+/// `<env_name>-<unix-timestamp>.<ext>` so `recordings list`/`recordings
+/// play` work across every environment from one place, instead of a
+/// separate directory per environment that would need its own name
+/// under `recordings/` anyway.
+pub fn recordings_dir() -> PathBuf {
+    crate::runtime::state_dir().join("recordings")
+}
+
+/// Which in-container recorder `enter --record` ended up using, chosen
+/// by [`detect`]. `Asciinema` produces a real asciinema v2 `.cast` file,
+/// playable with `asciinema play` (or uploaded to asciinema.org);
+/// `Script` falls back to util-linux's `script(1)`, present in
+/// essentially every image without installing anything extra, plus a
+/// `.timing` file (via `script -T`) since the typescript alone has
+/// nothing for `scriptreplay(1)` to pace playback with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recorder {
+    Asciinema,
+    Script,
+}
+
+impl Recorder {
+    fn extension(self) -> &'static str {
+        match self {
+            Recorder::Asciinema => "cast",
+            Recorder::Script => "typescript",
+        }
+    }
+
+    /// The `/bin/sh -c`-safe command that records `inner` (already
+    /// itself shell-escaped by the caller) into `out_path` inside the
+    /// container.
+    fn capture_command(self, inner: &str, out_path: &str) -> String {
+        match self {
+            Recorder::Asciinema => format!("asciinema rec -q -c {inner} {out_path}"),
+            Recorder::Script => {
+                format!("script -qc {inner} -T {out_path}.timing {out_path}")
+            }
+        }
+    }
+}
+
+/// Picks `asciinema` if it's on the container's `PATH`, else `script`,
+/// else `None` — `enter --record` degrades to an unrecorded session
+/// with a warning in that last case, rather than failing outright.
+pub fn detect(container: &str) -> Option<Recorder> {
+    if binary_exists(container, "asciinema") {
+        Some(Recorder::Asciinema)
+    } else if binary_exists(container, "script") {
+        Some(Recorder::Script)
+    } else {
+        None
+    }
+}
+
+fn binary_exists(container: &str, binary: &str) -> bool {
+    Command::new("docker")
+        .args(["exec", container, "/bin/sh", "-c"])
+        .arg(format!("command -v {binary}"))
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// One id's worth of metadata, reconstructed entirely from its filename
+/// (`<env_name>-<timestamp>.<ext>`) — there's nothing else worth
+/// persisting per recording, so no separate sidecar/index file to keep
+/// in sync with the directory's actual contents.
+#[derive(Debug, Clone)]
+pub struct RecordingMeta {
+    pub id: String,
+    pub env_name: String,
+    pub timestamp: u64,
+    pub recorder: Recorder,
+    pub path: PathBuf,
+}
+
+/// Starts `command` (the same shell/profile command `enter` would have
+/// run directly) inside `container` under whichever recorder [`detect`]
+/// found, over an interactive `docker exec -it` exactly like a plain
+/// `enter` would, then copies the result out to [`recordings_dir`] once
+/// the session ends. Returns the exit status of the recorded session
+/// itself, plus the recording's id — the copy-out only happens after
+/// the status is known, so a session that never runs (e.g. the shell
+/// binary is missing) doesn't leave a truncated recording behind.
+pub fn record(
+    container: &str,
+    env_name: &str,
+    command: &str,
+    recorder: Recorder,
+    timestamp: u64,
+) -> Result<(std::process::ExitStatus, String)> {
+    let container_path = format!("/tmp/pwnenv-record-{timestamp}.{}", recorder.extension());
+    let inner = shell_quote(command);
+    let capture = recorder.capture_command(&inner, &container_path);
+
+    let status = Command::new("docker")
+        .args(["exec", "-it", container, "/bin/sh", "-c", &capture])
+        .status()
+        .map_err(|e| PwnenvError::Docker(format!("failed to run docker exec: {e}")))?;
+
+    std::fs::create_dir_all(recordings_dir())?;
+    let id = format!("{env_name}-{timestamp}");
+    copy_out(container, &container_path, &id, recorder.extension())?;
+    if recorder == Recorder::Script {
+        copy_out(container, &format!("{container_path}.timing"), &id, "timing")?;
+    }
+
+    Ok((status, id))
+}
+
+fn copy_out(container: &str, container_path: &str, id: &str, ext: &str) -> Result<()> {
+    let dest = recordings_dir().join(format!("{id}.{ext}"));
+    let status = Command::new("docker")
+        .arg("cp")
+        .arg(format!("{container}:{container_path}"))
+        .arg(&dest)
+        .status()
+        .map_err(|e| PwnenvError::Docker(format!("failed to run docker cp: {e}")))?;
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!(
+            "docker cp of the recording out of '{container}' exited with {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Wraps `command` in single quotes for the outer `/bin/sh -c`, escaping
+/// any single quote it already contains the usual POSIX way (close the
+/// quote, emit an escaped one, reopen it).
+pub(crate) fn shell_quote(command: &str) -> String {
+    format!("'{}'", command.replace('\'', "'\\''"))
+}
+
+pub fn list() -> Vec<RecordingMeta> {
+    let Ok(entries) = std::fs::read_dir(recordings_dir()) else {
+        return Vec::new();
+    };
+    let mut recordings: Vec<RecordingMeta> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| parse_meta(&entry.path()))
+        .collect();
+    recordings.sort_by_key(|r| r.timestamp);
+    recordings
+}
+
+pub fn find(id: &str) -> Option<RecordingMeta> {
+    list().into_iter().find(|r| r.id == id)
+}
+
+fn parse_meta(path: &std::path::Path) -> Option<RecordingMeta> {
+    let ext = path.extension()?.to_str()?;
+    let recorder = match ext {
+        "cast" => Recorder::Asciinema,
+        "typescript" => Recorder::Script,
+        _ => return None,
+    };
+    let stem = path.file_stem()?.to_str()?.to_string();
+    let (env_name, timestamp) = stem.rsplit_once('-')?;
+    let timestamp: u64 = timestamp.parse().ok()?;
+    Some(RecordingMeta {
+        id: stem.clone(),
+        env_name: env_name.to_string(),
+        timestamp,
+        recorder,
+        path: path.to_path_buf(),
+    })
+}
+
+/// Plays `recording` host-side: `asciinema play` for a `.cast` file,
+/// `scriptreplay` (against its sibling `.timing` file, written alongside
+/// the typescript by [`record`]) for a `script`-captured one.
+pub fn play(recording: &RecordingMeta) -> Result<std::process::ExitStatus> {
+    let status = match recording.recorder {
+        Recorder::Asciinema => Command::new("asciinema").arg("play").arg(&recording.path).status(),
+        Recorder::Script => Command::new("scriptreplay")
+            .arg("--timing")
+            .arg(recording.path.with_extension("timing"))
+            .arg(&recording.path)
+            .status(),
+    };
+    status.map_err(|e| PwnenvError::Docker(format!("failed to run the recording player: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_quotes_in_the_command_are_escaped() {
+        assert_eq!(shell_quote("echo 'hi'"), "'echo '\\''hi'\\'''");
+    }
+
+    #[test]
+    fn filename_round_trips_through_parse_meta() {
+        let dir = std::env::temp_dir().join("pwnenv-recordings-test-parse");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("chall-1700000000.cast");
+        std::fs::write(&path, "").unwrap();
+
+        let meta = parse_meta(&path).unwrap();
+        assert_eq!(meta.id, "chall-1700000000");
+        assert_eq!(meta.env_name, "chall");
+        assert_eq!(meta.timestamp, 1700000000);
+        assert_eq!(meta.recorder, Recorder::Asciinema);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn unrecognized_extension_is_skipped() {
+        let dir = std::env::temp_dir().join("pwnenv-recordings-test-skip");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("chall-1700000000.timing");
+        std::fs::write(&path, "").unwrap();
+
+        assert!(parse_meta(&path).is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,299 @@
+//! Fetches `pwnenv.yaml`'s `remote_tools` URLs (an https link to a YAML
+//! file, or a git repo) into a cache under [`crate::runtime::state_dir`]
+//! so teams can share tool definitions (a pinned pwndbg setup, an
+//! internal heap-analysis script) without copy-pasting YAML between
+//! configs. [`sync`] is the only thing that touches the network —
+//! [`load_cached`] (what [`crate::config::Config::load`] calls on every
+//! run) only ever reads what's already on disk, so `render`/`build`
+//! work offline once synced. Fetched files are read as data with
+//! `serde_yaml` and never executed, at sync time or otherwise.
+
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ToolConfig;
+use crate::error::{PwnenvError, Result};
+
+/// Where fetched tool definitions (and [`Lock`]) are cached. Keyed by
+/// [`crate::runtime::state_dir`], not any one environment's runtime dir,
+/// since a `remote_tools` URL is typically shared across many challenge
+/// directories.
+pub fn cache_dir() -> PathBuf {
+    crate::runtime::state_dir().join("remote-tools")
+}
+
+fn lock_path() -> PathBuf {
+    cache_dir().join("lock.json")
+}
+
+/// What `sync` pinned a URL to last time, so a re-`sync` only re-fetches
+/// what actually changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LockEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Lock(BTreeMap<String, LockEntry>);
+
+fn load_lock() -> Lock {
+    std::fs::read_to_string(lock_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_lock(lock: &Lock) -> Result<()> {
+    std::fs::create_dir_all(cache_dir())?;
+    let json = serde_json::to_string_pretty(lock)
+        .map_err(|e| PwnenvError::Docker(format!("failed to serialize remote tools lock: {e}")))?;
+    std::fs::write(lock_path(), json)?;
+    Ok(())
+}
+
+/// One `remote_tools` entry, classified by how to fetch it. A git source
+/// is written as `<repo-url>#<path-in-repo>` (path defaults to
+/// `tools.yaml` at the repo root when omitted) and recognized by a
+/// `git@` prefix, a `git+` scheme, or a `.git` repo URL; everything else
+/// is fetched as a plain https YAML file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    Http { url: String },
+    Git { repo: String, path: String },
+}
+
+fn parse(raw: &str) -> Source {
+    let (head, frag_path) = match raw.split_once('#') {
+        Some((head, path)) => (head, Some(path)),
+        None => (raw, None),
+    };
+    let is_git = head.starts_with("git@") || head.starts_with("git+") || head.ends_with(".git");
+    if !is_git {
+        return Source::Http { url: raw.to_string() };
+    }
+    let repo = head.strip_prefix("git+").unwrap_or(head).to_string();
+    let path = frag_path.unwrap_or("tools.yaml").to_string();
+    Source::Git { repo, path }
+}
+
+fn cache_key(raw: &str) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    raw.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn http_cache_path(raw: &str) -> PathBuf {
+    cache_dir().join(format!("{}.yaml", cache_key(raw)))
+}
+
+fn git_clone_dir(raw: &str) -> PathBuf {
+    cache_dir().join(format!("{}-git", cache_key(raw)))
+}
+
+/// What [`sync`] did with one URL.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub fetched: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Fetches every URL in `urls` into [`cache_dir`], recording each one's
+/// ETag (https) or pinned commit (git) in the lock. A URL that fails to
+/// fetch is reported, not fatal — the others still get a chance, and
+/// `load_cached` falls back to whatever's already cached for it.
+pub fn sync(urls: &[String]) -> Result<SyncReport> {
+    std::fs::create_dir_all(cache_dir())?;
+    let mut lock = load_lock();
+    let mut report = SyncReport::default();
+
+    for url in urls {
+        match sync_one(url, &mut lock) {
+            Ok(true) => report.fetched.push(url.clone()),
+            Ok(false) => report.unchanged.push(url.clone()),
+            Err(e) => {
+                eprintln!("remote_tools: failed to sync {url}: {e}");
+                report.failed.push(url.clone());
+            }
+        }
+    }
+
+    save_lock(&lock)?;
+    Ok(report)
+}
+
+/// Returns `true` if `url`'s cache was actually updated, `false` if it
+/// was already up to date.
+fn sync_one(url: &str, lock: &mut Lock) -> Result<bool> {
+    match parse(url) {
+        Source::Http { url } => sync_http(&url, lock),
+        Source::Git { repo, .. } => sync_git(url, &repo, lock),
+    }
+}
+
+fn sync_http(url: &str, lock: &mut Lock) -> Result<bool> {
+    let cache_path = http_cache_path(url);
+    let tmp_path = cache_path.with_extension("yaml.tmp");
+    let header_path = cache_path.with_extension("headers.tmp");
+
+    let mut command = Command::new("curl");
+    command.args(["-fsSL", "-D"]).arg(&header_path).arg("-o").arg(&tmp_path);
+    if let Some(etag) = lock.0.get(url).and_then(|e| e.etag.as_deref()) {
+        if cache_path.exists() {
+            command.args(["-H", &format!("If-None-Match: {etag}")]);
+        }
+    }
+    command.arg(url);
+
+    let status = command
+        .status()
+        .map_err(|e| PwnenvError::Docker(format!("failed to run curl: {e}")))?;
+    if !status.success() {
+        std::fs::remove_file(&tmp_path).ok();
+        std::fs::remove_file(&header_path).ok();
+        return Err(PwnenvError::Docker(format!("curl exited with {status}")));
+    }
+
+    let headers = std::fs::read_to_string(&header_path).unwrap_or_default();
+    std::fs::remove_file(&header_path).ok();
+    let not_modified = headers.lines().next().is_some_and(|status_line| status_line.contains("304"));
+    let etag = headers
+        .lines()
+        .find(|line| line.to_ascii_lowercase().starts_with("etag:"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, value)| value.trim().to_string());
+
+    if not_modified {
+        std::fs::remove_file(&tmp_path).ok();
+        return Ok(false);
+    }
+
+    std::fs::rename(&tmp_path, &cache_path)?;
+    if let Some(etag) = etag {
+        lock.0.insert(url.to_string(), LockEntry { etag: Some(etag), commit: None });
+    }
+    Ok(true)
+}
+
+fn sync_git(raw_url: &str, repo: &str, lock: &mut Lock) -> Result<bool> {
+    let clone_dir = git_clone_dir(raw_url);
+    std::fs::remove_dir_all(&clone_dir).ok();
+
+    let status = Command::new("git")
+        .args(["clone", "--depth", "1", "--quiet", repo])
+        .arg(&clone_dir)
+        .status()
+        .map_err(|e| PwnenvError::Docker(format!("failed to run git: {e}")))?;
+    if !status.success() {
+        return Err(PwnenvError::Docker(format!("git clone of {repo} exited with {status}")));
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&clone_dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .map_err(|e| PwnenvError::Docker(format!("failed to run git: {e}")))?;
+    if !output.status.success() {
+        return Err(PwnenvError::Docker("git rev-parse HEAD failed".to_string()));
+    }
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let changed = lock.0.get(raw_url).and_then(|e| e.commit.as_deref()) != Some(commit.as_str());
+    lock.0.insert(raw_url.to_string(), LockEntry { etag: None, commit: Some(commit) });
+    Ok(changed)
+}
+
+/// Reads whatever [`sync`] already cached for each of `urls`, parsing
+/// every fragment as `{tools: [...]}` and merging their `tools` lists in
+/// order. A URL that's never been synced, or whose cached file no
+/// longer parses, is skipped with a warning rather than failing the
+/// whole config load — the point of caching is that a stale or
+/// unreachable source doesn't take everything else down with it.
+pub fn load_cached(urls: &[String]) -> Vec<ToolConfig> {
+    let mut tools = Vec::new();
+    for url in urls {
+        match load_one(url) {
+            Ok(fetched) => tools.extend(fetched),
+            Err(reason) => eprintln!("warning: remote tool source '{url}' {reason}; skipping it"),
+        }
+    }
+    tools
+}
+
+fn load_one(url: &str) -> std::result::Result<Vec<ToolConfig>, String> {
+    let path = match parse(url) {
+        Source::Http { url } => http_cache_path(&url),
+        Source::Git { path, .. } => git_clone_dir(url).join(&path),
+    };
+    let raw = std::fs::read_to_string(&path).map_err(|_| "has never been synced (run `pwnenv tools sync`)".to_string())?;
+    let fragment: RemoteToolsFragment =
+        serde_yaml::from_str(&raw).map_err(|e| format!("failed to parse its cached copy: {e}"))?;
+    Ok(fragment.tools)
+}
+
+/// The subset of `pwnenv.yaml`'s shape a `remote_tools` source is
+/// expected to provide — same idea as [`crate::config::Config::apply_includes`]'s
+/// `IncludeFragment`, just fetched over the network instead of read
+/// from a sibling file.
+#[derive(Debug, Default, Deserialize)]
+struct RemoteToolsFragment {
+    #[serde(default)]
+    tools: Vec<ToolConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_https_url_is_an_http_source() {
+        assert_eq!(
+            parse("https://example.com/tools.yaml"),
+            Source::Http { url: "https://example.com/tools.yaml".to_string() }
+        );
+    }
+
+    #[test]
+    fn dot_git_suffix_is_a_git_source_with_the_default_path() {
+        assert_eq!(
+            parse("https://example.com/team/tools.git"),
+            Source::Git { repo: "https://example.com/team/tools.git".to_string(), path: "tools.yaml".to_string() }
+        );
+    }
+
+    #[test]
+    fn git_scheme_prefix_is_stripped_and_fragment_picks_the_path() {
+        assert_eq!(
+            parse("git+https://example.com/team/tools.git#pwndbg.yaml"),
+            Source::Git { repo: "https://example.com/team/tools.git".to_string(), path: "pwndbg.yaml".to_string() }
+        );
+    }
+
+    #[test]
+    fn git_ssh_shorthand_is_a_git_source() {
+        assert_eq!(
+            parse("git@github.com:team/tools.git"),
+            Source::Git { repo: "git@github.com:team/tools.git".to_string(), path: "tools.yaml".to_string() }
+        );
+    }
+
+    #[test]
+    fn unsynced_url_is_skipped_with_a_warning_not_an_error() {
+        let state_dir = std::env::temp_dir().join("pwnenv-remote-tools-test-unsynced");
+        std::env::set_var("PWNENV_CONFIG_DIR", &state_dir);
+
+        let tools = load_cached(&["https://example.com/never-synced.yaml".to_string()]);
+        assert!(tools.is_empty());
+
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::fs::remove_dir_all(&state_dir).ok();
+    }
+}
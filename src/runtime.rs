@@ -0,0 +1,413 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::config::Config;
+
+/// The tag every environment's image got before `image_tag` existed,
+/// still the fallback when nothing overrides it.
+pub const DEFAULT_IMAGE_TAG: &str = "pwnenv-env";
+
+/// `pwnenv --config-dir <path>`'s override of [`state_dir`], set once at
+/// startup (see [`set_config_dir_override`]) so every subcommand that
+/// resolves a path through `state_dir()` — `init`/`build`/`up`/`status`/
+/// `list-profiles`/the once-a-day version notice, all of it — picks it up
+/// without needing the path threaded through each call individually.
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Sets [`state_dir`]'s override for the rest of this process. Must be
+/// called at most once, before any code calls `state_dir()` — `main`
+/// does this immediately after parsing `--config-dir`, ahead of
+/// dispatching to any subcommand.
+pub fn set_config_dir_override(dir: PathBuf) {
+    let _ = CONFIG_DIR_OVERRIDE.set(dir);
+}
+
+/// Resolves the tag `build`/`up` give (or expect) an environment's
+/// image: an explicit `--tag` on the invocation wins, then `init
+/// --image-tag`'s override (see [`RuntimeDir::image_tag_override`]),
+/// then `pwnenv.yaml`'s `image_tag`, then [`DEFAULT_IMAGE_TAG`].
+pub fn resolve_image_tag(cli_tag: Option<&str>, runtime: &RuntimeDir, config: &Config) -> String {
+    cli_tag
+        .map(str::to_string)
+        .or_else(|| runtime.image_tag_override())
+        .or_else(|| config.image_tag.clone())
+        .unwrap_or_else(|| DEFAULT_IMAGE_TAG.to_string())
+}
+
+/// Paths into pwnenv's own state directory, rooted at `~/.local/share/pwnenv`.
+/// Each environment gets a subdirectory named after the challenge directory
+/// it was `init`ed from.
+pub struct RuntimeDir {
+    root: PathBuf,
+}
+
+impl RuntimeDir {
+    pub fn new(env_name: &str) -> Self {
+        let root = state_dir().join(env_name);
+        RuntimeDir { root }
+    }
+
+    pub fn root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    /// Directory used as the mount point for `/opt/pwnenv/failed` markers
+    /// written by the guarded tool-install wrappers during a build.
+    pub fn failed_dir(&self) -> PathBuf {
+        self.root.join("failed")
+    }
+
+    pub fn build_log(&self) -> PathBuf {
+        self.root.join("build.log")
+    }
+
+    /// Manifest saved from the last `init`'s programs-dir copy, used to
+    /// skip or delta-copy unchanged files on the next `init`.
+    pub fn programs_manifest_path(&self) -> PathBuf {
+        self.root.join("programs-manifest.json")
+    }
+
+    /// Build-context staging area for `config.bake`'s paths, copied here
+    /// by `init` so the Dockerfile's `COPY` (see [`crate::docker::dockerfile`])
+    /// has something to bake into the image even in mount mode, where
+    /// nothing else from the host ends up in the build context.
+    pub fn bake_dir(&self) -> PathBuf {
+        self.root.join("bake")
+    }
+
+    /// Per-environment override of `config.privileged`, set by `init
+    /// --no-privileged` without touching `pwnenv.yaml`.
+    fn privileged_override_path(&self) -> PathBuf {
+        self.root.join("privileged-override")
+    }
+
+    pub fn set_privileged_override(&self, privileged: bool) -> std::io::Result<()> {
+        std::fs::write(self.privileged_override_path(), privileged.to_string())
+    }
+
+    pub fn privileged_override(&self) -> Option<bool> {
+        std::fs::read_to_string(self.privileged_override_path())
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+    }
+
+    /// Set by `init --from-image`: when present, `build` skips the
+    /// Dockerfile/tool build entirely and `up` writes `image: <ref>`
+    /// instead of building from the environment's own Dockerfile.
+    fn image_override_path(&self) -> PathBuf {
+        self.root.join("from-image")
+    }
+
+    pub fn set_image_override(&self, image_ref: &str) -> std::io::Result<()> {
+        std::fs::write(self.image_override_path(), image_ref)
+    }
+
+    pub fn image_override(&self) -> Option<String> {
+        std::fs::read_to_string(self.image_override_path())
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Set by `init --image-tag`: the tag `build`/`up` use for this
+    /// environment's image without needing `--tag` repeated on every
+    /// later invocation, and without editing `pwnenv.yaml`'s own
+    /// `image_tag` just to try one out.
+    fn image_tag_override_path(&self) -> PathBuf {
+        self.root.join("image-tag-override")
+    }
+
+    pub fn set_image_tag_override(&self, tag: &str) -> std::io::Result<()> {
+        std::fs::write(self.image_tag_override_path(), tag)
+    }
+
+    pub fn image_tag_override(&self) -> Option<String> {
+        std::fs::read_to_string(self.image_tag_override_path())
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Set by `init --gdb-plugin`: overrides `config.gdb_plugin` for
+    /// this environment without editing `pwnenv.yaml`, picked up by
+    /// `build` re-running [`Config::apply_gdb_plugin`](crate::config::Config::apply_gdb_plugin)
+    /// with the override in place.
+    fn gdb_plugin_override_path(&self) -> PathBuf {
+        self.root.join("gdb-plugin-override")
+    }
+
+    pub fn set_gdb_plugin_override(&self, plugin: &str) -> std::io::Result<()> {
+        std::fs::write(self.gdb_plugin_override_path(), plugin)
+    }
+
+    pub fn gdb_plugin_override(&self) -> Option<String> {
+        std::fs::read_to_string(self.gdb_plugin_override_path())
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Extra bind mounts resolved by `init` (see [`crate::mounts`]),
+    /// recorded here so `rebuild`/`status`/`up` reproduce them without
+    /// re-resolving relative host paths against a cwd that may have
+    /// changed since `init` was run.
+    fn mounts_state_path(&self) -> PathBuf {
+        self.root.join("mounts")
+    }
+
+    pub fn set_mounts(&self, mounts: &[crate::mounts::Mount]) -> std::io::Result<()> {
+        std::fs::write(self.mounts_state_path(), crate::mounts::encode(mounts))
+    }
+
+    pub fn mounts(&self) -> Vec<crate::mounts::Mount> {
+        std::fs::read_to_string(self.mounts_state_path())
+            .map(|raw| crate::mounts::decode(&raw))
+            .unwrap_or_default()
+    }
+
+    /// The challenge directory this environment is registered to, set by
+    /// `init` and re-affirmed by `adopt` (see [`crate::commands::adopt`]).
+    /// `adopt` refuses to repoint an existing registration at a different
+    /// directory, so a wiped/stale registration doesn't silently steal a
+    /// name already in use elsewhere.
+    fn host_dir_marker_path(&self) -> PathBuf {
+        self.root.join("host-dir")
+    }
+
+    pub fn set_host_dir(&self, host_dir: &Path) -> std::io::Result<()> {
+        std::fs::write(self.host_dir_marker_path(), host_dir.display().to_string())
+    }
+
+    pub fn host_dir(&self) -> Option<PathBuf> {
+        std::fs::read_to_string(self.host_dir_marker_path())
+            .ok()
+            .map(|s| PathBuf::from(s.trim()))
+            .filter(|p| !p.as_os_str().is_empty())
+    }
+
+    /// Extra `host:container` port mappings added by `open-port` (see
+    /// [`crate::commands::open_port`]), beyond `config.forwarded_port`.
+    /// Recorded here, not in `pwnenv.yaml`, since they're a live,
+    /// ad-hoc addition to a running environment rather than part of its
+    /// checked-in config.
+    fn extra_ports_path(&self) -> PathBuf {
+        self.root.join("extra-ports")
+    }
+
+    pub fn set_extra_ports(&self, ports: &[(u16, u16)]) -> std::io::Result<()> {
+        let encoded = ports.iter().map(|(host, container)| format!("{host}:{container}")).collect::<Vec<_>>().join("\n");
+        std::fs::write(self.extra_ports_path(), encoded)
+    }
+
+    pub fn extra_ports(&self) -> Vec<(u16, u16)> {
+        std::fs::read_to_string(self.extra_ports_path())
+            .map(|raw| {
+                raw.lines()
+                    .filter(|line| !line.is_empty())
+                    .filter_map(|line| {
+                        let (host, container) = line.split_once(':')?;
+                        Some((host.parse().ok()?, container.parse().ok()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Set by `init --offline`: `build` refuses to run `docker build` and
+    /// `up` passes `--pull never`, so both fail fast with a clear message
+    /// instead of an opaque network timeout when there's no internet.
+    fn offline_path(&self) -> PathBuf {
+        self.root.join("offline")
+    }
+
+    pub fn set_offline(&self, offline: bool) -> std::io::Result<()> {
+        std::fs::write(self.offline_path(), offline.to_string())
+    }
+
+    pub fn offline(&self) -> bool {
+        std::fs::read_to_string(self.offline_path())
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(false)
+    }
+
+    /// Per-environment override of how `programs_dir` reaches the
+    /// container, set by `init --no-copy`/`init --no-mount`. `(copy,
+    /// mount)`; both default to `true`.
+    fn programs_delivery_path(&self) -> PathBuf {
+        self.root.join("programs-delivery")
+    }
+
+    pub fn set_programs_delivery(&self, copy: bool, mount: bool) -> std::io::Result<()> {
+        std::fs::write(self.programs_delivery_path(), format!("{copy} {mount}"))
+    }
+
+    pub fn programs_delivery(&self) -> (bool, bool) {
+        std::fs::read_to_string(self.programs_delivery_path())
+            .ok()
+            .and_then(|raw| {
+                let mut parts = raw.trim().split(' ');
+                let copy = parts.next()?.parse().ok()?;
+                let mount = parts.next()?.parse().ok()?;
+                Some((copy, mount))
+            })
+            .unwrap_or((true, true))
+    }
+
+    pub fn ensure_exists(&self) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.root)?;
+        std::fs::create_dir_all(self.failed_dir())
+    }
+
+    /// Marker file recording which layout version this environment's
+    /// runtime dir was last migrated to.
+    fn layout_version_marker(&self) -> PathBuf {
+        self.root.join(".layout-version")
+    }
+}
+
+/// The docker container name pwnenv creates for an environment.
+pub fn container_name(env_name: &str) -> String {
+    format!("pwnenv-{env_name}")
+}
+
+/// Like [`container_name`], but for a specific compose service within
+/// `env_name` (see `enter --service`). The default service is the
+/// environment itself — [`crate::docker::render_compose`] only ever
+/// renders one, named after `env_name` — in which case this is identical
+/// to [`container_name`]; any other (currently hypothetical, until
+/// compose rendering grows support for more than one service) service
+/// gets its own name alongside it instead of colliding.
+pub fn container_name_for_service(env_name: &str, service: &str) -> String {
+    if service == env_name {
+        container_name(env_name)
+    } else {
+        format!("pwnenv-{env_name}-{service}")
+    }
+}
+
+/// Bump this whenever the runtime directory layout changes, and add a
+/// matching step to [`migrate`] so existing installs upgrade in place
+/// instead of breaking.
+const CURRENT_LAYOUT_VERSION: u32 = 1;
+
+fn dirs_home() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// pwnenv's own state directory, shared by every environment (most state
+/// lives per-environment under [`RuntimeDir`], but a handful of markers,
+/// e.g. [`crate::version`]'s once-per-day notice, are global to the
+/// install rather than any one environment). Resolved in order:
+///
+/// 1. `pwnenv --config-dir <path>` (see [`set_config_dir_override`]) — for
+///    running two completely independent setups (e.g. a CTF one and a
+///    hardened malware-triage one) side by side without moving directories.
+/// 2. the `PWNENV_CONFIG_DIR` environment variable.
+/// 3. `$XDG_DATA_HOME/pwnenv`, if `XDG_DATA_HOME` is set.
+/// 4. `~/.local/share/pwnenv`, the long-standing default.
+pub fn state_dir() -> PathBuf {
+    if let Some(dir) = CONFIG_DIR_OVERRIDE.get() {
+        return dir.clone();
+    }
+    if let Some(dir) = std::env::var_os("PWNENV_CONFIG_DIR") {
+        return PathBuf::from(dir);
+    }
+    if let Some(xdg_data_home) = std::env::var_os("XDG_DATA_HOME") {
+        return PathBuf::from(xdg_data_home).join("pwnenv");
+    }
+    dirs_home().join(".local/share/pwnenv")
+}
+
+/// Ensures `env_name`'s runtime dir exists and is on the current layout,
+/// migrating it step by step if it was created by an older pwnenv. Safe to
+/// call on every command invocation: a freshly created or up-to-date
+/// environment is a no-op beyond writing the version marker.
+pub fn setup_minimum_requirements(env_name: &str) -> std::io::Result<RuntimeDir> {
+    let runtime = RuntimeDir::new(env_name);
+    runtime.ensure_exists()?;
+
+    let marker = runtime.layout_version_marker();
+    let mut version: u32 = std::fs::read_to_string(&marker)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0);
+
+    if version == 0 {
+        migrate_v0_to_v1(env_name, &runtime)?;
+        version = 1;
+    }
+
+    std::fs::write(&marker, version.to_string())?;
+    debug_assert_eq!(version, CURRENT_LAYOUT_VERSION);
+    Ok(runtime)
+}
+
+/// Pre-v1 installs wrote the build log next to the environment dir
+/// (`<env_name>.log`) instead of inside it (`<env_name>/build.log`).
+fn migrate_v0_to_v1(env_name: &str, runtime: &RuntimeDir) -> std::io::Result<()> {
+    let old_log = runtime
+        .root()
+        .parent()
+        .map(|parent| parent.join(format!("{env_name}.log")));
+
+    if let Some(old_log) = old_log {
+        if old_log.exists() {
+            eprintln!(
+                "migrate-runtime: moving {} -> {}",
+                old_log.display(),
+                runtime.build_log().display()
+            );
+            std::fs::rename(&old_log, runtime.build_log())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pwnenv_config_dir_env_var_overrides_xdg_data_home() {
+        std::env::set_var("PWNENV_CONFIG_DIR", "/tmp/pwnenv-config-dir-test");
+        std::env::set_var("XDG_DATA_HOME", "/tmp/pwnenv-xdg-test");
+        assert_eq!(state_dir(), PathBuf::from("/tmp/pwnenv-config-dir-test"));
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::env::remove_var("XDG_DATA_HOME");
+    }
+
+    #[test]
+    fn xdg_data_home_is_used_when_config_dir_is_not_set() {
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+        std::env::set_var("XDG_DATA_HOME", "/tmp/pwnenv-xdg-test");
+        assert_eq!(state_dir(), PathBuf::from("/tmp/pwnenv-xdg-test/pwnenv"));
+        std::env::remove_var("XDG_DATA_HOME");
+    }
+
+    #[test]
+    fn container_name_for_service_matches_container_name_for_the_default_service() {
+        assert_eq!(container_name_for_service("chall", "chall"), container_name("chall"));
+    }
+
+    #[test]
+    fn container_name_for_service_is_distinct_for_a_non_default_service() {
+        let default = container_name_for_service("chall", "chall");
+        let other = container_name_for_service("chall", "db");
+        assert_ne!(default, other);
+        assert_eq!(other, "pwnenv-chall-db");
+    }
+
+    #[test]
+    fn extra_ports_round_trip_through_the_runtime_dir() {
+        std::env::set_var("PWNENV_CONFIG_DIR", std::env::temp_dir().join("pwnenv-runtime-test-extra-ports"));
+        let runtime = RuntimeDir::new("extra-ports-test");
+        runtime.ensure_exists().unwrap();
+        runtime.set_extra_ports(&[(8080, 80), (9000, 9000)]).unwrap();
+        assert_eq!(runtime.extra_ports(), vec![(8080, 80), (9000, 9000)]);
+        std::fs::remove_dir_all(runtime.root()).ok();
+        std::env::remove_var("PWNENV_CONFIG_DIR");
+    }
+}
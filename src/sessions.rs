@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PwnenvError, Result};
+use crate::lock::pid_is_alive;
+use crate::runtime::RuntimeDir;
+
+/// One live `enter` session into an environment's container, so
+/// `status`/`ps` can show how many people are attached and `kill` can
+/// warn before yanking the container out from under them (see
+/// [`list_active`], [`Guard`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub pid: u32,
+    pub tty: String,
+    pub started_at: u64,
+}
+
+fn sessions_path(runtime: &RuntimeDir) -> PathBuf {
+    runtime.root().join("sessions.json")
+}
+
+fn load(runtime: &RuntimeDir) -> Vec<Session> {
+    std::fs::read_to_string(sessions_path(runtime))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save(runtime: &RuntimeDir, sessions: &[Session]) -> Result<()> {
+    let json = serde_json::to_string_pretty(sessions)
+        .map_err(|e| PwnenvError::Docker(format!("failed to serialize sessions: {e}")))?;
+    std::fs::write(sessions_path(runtime), json)?;
+    Ok(())
+}
+
+/// `runtime`'s currently live `enter` sessions: everything in
+/// `sessions.json` whose `pid` still exists, with anything else (left
+/// behind by a session that crashed instead of letting its [`Guard`]
+/// drop normally, e.g. a SIGKILL) pruned from the file as a side effect.
+/// Never errors — a missing or unreadable sessions file just means "no
+/// active sessions", same as [`crate::verify::load_results`].
+pub fn list_active(runtime: &RuntimeDir) -> Vec<Session> {
+    let sessions = load(runtime);
+    let live: Vec<Session> = sessions.into_iter().filter(|s| pid_is_alive(s.pid)).collect();
+    save(runtime, &live).ok();
+    live
+}
+
+/// Registers the current process as a live session against `runtime` for
+/// as long as the returned guard stays alive; dropping it (whether
+/// `enter` returns normally or an early `?` unwinds out of it) removes
+/// the entry again. A session that crashes hard enough to skip even the
+/// drop (e.g. a SIGKILL) is cleaned up instead the next time
+/// [`list_active`] prunes dead pids — the same fallback [`crate::lock::BuildLock`]
+/// relies on for a stale build lock.
+pub struct Guard {
+    env_name: String,
+    pid: u32,
+}
+
+impl Guard {
+    pub fn register(runtime: &RuntimeDir) -> Result<Self> {
+        let env_name = runtime
+            .root()
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut sessions: Vec<Session> = load(runtime).into_iter().filter(|s| pid_is_alive(s.pid)).collect();
+        let pid = std::process::id();
+        sessions.push(Session { pid, tty: current_tty(), started_at: now_unix() });
+        save(runtime, &sessions)?;
+        Ok(Guard { env_name, pid })
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let runtime = RuntimeDir::new(&self.env_name);
+        let remaining: Vec<Session> = load(&runtime).into_iter().filter(|s| s.pid != self.pid).collect();
+        save(&runtime, &remaining).ok();
+    }
+}
+
+#[cfg(unix)]
+fn current_tty() -> String {
+    std::fs::read_link("/proc/self/fd/0")
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "-".to_string())
+}
+
+#[cfg(not(unix))]
+fn current_tty() -> String {
+    "-".to_string()
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_runtime(label: &str) -> RuntimeDir {
+        let runtime = RuntimeDir::new(&format!("sessions-test-{label}-{}", std::process::id()));
+        runtime.ensure_exists().unwrap();
+        runtime
+    }
+
+    #[test]
+    fn register_then_drop_round_trips_through_list_active() {
+        let runtime = test_runtime("roundtrip");
+
+        let guard = Guard::register(&runtime).unwrap();
+        let active = list_active(&runtime);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].pid, std::process::id());
+
+        drop(guard);
+        assert!(list_active(&runtime).is_empty());
+
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+
+    #[test]
+    fn list_active_prunes_a_session_left_by_a_dead_pid() {
+        let runtime = test_runtime("prune");
+        let dead = Session { pid: 4294967295, tty: "-".to_string(), started_at: 0 };
+        save(&runtime, &[dead]).unwrap();
+
+        assert!(list_active(&runtime).is_empty());
+        assert!(load(&runtime).is_empty());
+
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+
+    #[test]
+    fn registering_preserves_other_live_and_drops_dead_sessions() {
+        let runtime = test_runtime("coexist");
+        let dead = Session { pid: 4294967295, tty: "-".to_string(), started_at: 0 };
+        save(&runtime, &[dead]).unwrap();
+
+        let guard = Guard::register(&runtime).unwrap();
+        let active = list_active(&runtime);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].pid, std::process::id());
+
+        drop(guard);
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+}
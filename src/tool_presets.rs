@@ -0,0 +1,261 @@
+use crate::config::{Config, ToolConfig};
+
+/// `(ARG name, pinned default)` for each preset's pinned-download build
+/// args, declared the same way [`crate::gdb_plugins::pinned_ref`] pins
+/// plugin install refs: as `build_args` entries a `pwnenv.yaml` that
+/// already sets the same key (or a `build --build-arg`) wins over.
+fn pinned_args(name: &str) -> &'static [(&'static str, &'static str)] {
+    match name {
+        "reversing" => &[
+            ("GHIDRA_VERSION", "11.1.2"),
+            (
+                "GHIDRA_SHA256",
+                "a5b98e4ffb7b0e37f2db0cb2bc4e5f9c9cca54a23f1b85c74f7eb0e396e0a1e5",
+            ),
+        ],
+        _ => &[],
+    }
+}
+
+/// Sets `name`'s pinned-download `build_args` defaults (see
+/// [`pinned_args`]) unless `pwnenv.yaml` already set the same key,
+/// mirroring [`crate::gdb_plugins::apply_default_ref`]. Called by
+/// [`crate::config::Config::apply_include_tools`] for every bundle named
+/// in `include_tools`.
+pub fn apply_default_build_args(config: &mut Config, name: &str) {
+    for (key, default) in pinned_args(name) {
+        config
+            .build_args
+            .entry(key.to_string())
+            .or_insert_with(|| default.to_string());
+    }
+}
+
+/// Named bundles of tools a config can pull in via `include_tools`,
+/// instead of spelling out every install script by hand.
+pub fn lookup(name: &str) -> Option<Vec<ToolConfig>> {
+    match name {
+        "reversing" => Some(vec![
+            ToolConfig {
+                name: "ghidra".to_string(),
+                script: vec![
+                    "RUN apt-get update && apt-get install -y openjdk-17-jdk wget unzip".to_string(),
+                    "RUN wget -O /tmp/ghidra.zip \"https://github.com/NationalSecurityAgency/ghidra/releases/download/Ghidra_${GHIDRA_VERSION}_build/ghidra_${GHIDRA_VERSION}_PUBLIC.zip\"".to_string(),
+                    "RUN echo \"${GHIDRA_SHA256}  /tmp/ghidra.zip\" | sha256sum -c -".to_string(),
+                    "RUN unzip -q /tmp/ghidra.zip -d /opt && mv /opt/ghidra_* /opt/ghidra && rm /tmp/ghidra.zip".to_string(),
+                    "RUN mkdir -p /opt/pwnenv/ghidra-scripts".to_string(),
+                    "RUN <<'EOF'".to_string(),
+                    "cat > /opt/pwnenv/ghidra-scripts/ExportDecompiledC.py <<'SCRIPT'".to_string(),
+                    "# Headless post-script (run via analyzeHeadless -postScript, with the".to_string(),
+                    "# output directory as its argument): decompiles every defined function".to_string(),
+                    "# and writes each one's C to <outdir>/<function>.c.".to_string(),
+                    "import os".to_string(),
+                    "from ghidra.app.decompiler import DecompInterface".to_string(),
+                    "".to_string(),
+                    "out_dir = getScriptArgs()[0]".to_string(),
+                    "if not os.path.isdir(out_dir):".to_string(),
+                    "    os.makedirs(out_dir)".to_string(),
+                    "".to_string(),
+                    "decompiler = DecompInterface()".to_string(),
+                    "decompiler.openProgram(currentProgram)".to_string(),
+                    "for function in currentProgram.getFunctionManager().getFunctions(True):".to_string(),
+                    "    result = decompiler.decompileFunction(function, 60, monitor)".to_string(),
+                    "    if not result.decompileCompleted():".to_string(),
+                    "        continue".to_string(),
+                    "    out_path = os.path.join(out_dir, function.getName() + \".c\")".to_string(),
+                    "    with open(out_path, \"w\") as f:".to_string(),
+                    "        f.write(result.getDecompiledFunction().getC())".to_string(),
+                    "SCRIPT".to_string(),
+                    "EOF".to_string(),
+                ],
+                build_only: false,
+                append: false,
+                artifacts: Vec::new(),
+                verify: vec!["test -x /opt/ghidra/ghidraRun".to_string()],
+                secrets: Vec::new(),
+            },
+            ToolConfig {
+                name: "radare2".to_string(),
+                script: vec!["RUN apt-get update && apt-get install -y radare2".to_string()],
+                build_only: false,
+                append: false,
+                artifacts: Vec::new(),
+                verify: vec!["r2 -v".to_string()],
+                secrets: Vec::new(),
+            },
+            ToolConfig {
+                name: "rizin".to_string(),
+                script: vec!["RUN apt-get update && apt-get install -y rizin".to_string()],
+                build_only: false,
+                append: false,
+                artifacts: Vec::new(),
+                verify: vec!["rizin -v".to_string()],
+                secrets: Vec::new(),
+            },
+        ]),
+        "windows" => Some(vec![ToolConfig {
+            name: "wine".to_string(),
+            script: vec![
+                "RUN dpkg --add-architecture i386".to_string(),
+                "RUN apt-get update && apt-get install -y wine wine32 wine64".to_string(),
+            ],
+            build_only: false,
+            append: false,
+            artifacts: Vec::new(),
+            verify: vec!["wine --version".to_string()],
+            secrets: Vec::new(),
+        }]),
+        "kernel" => Some(vec![ToolConfig {
+            name: "qemu".to_string(),
+            script: vec![
+                "RUN apt-get update && apt-get install -y qemu-system-x86 gdb-multiarch".to_string(),
+            ],
+            build_only: false,
+            append: false,
+            artifacts: Vec::new(),
+            verify: vec!["qemu-system-x86_64 --version".to_string(), "gdb-multiarch --version".to_string()],
+            secrets: Vec::new(),
+        }]),
+        // `pwninit` and `one_gadget` are the two presets this multi-stage
+        // split actually pays off for: both are installed via a
+        // language package manager (`cargo`/`gem`) whose own toolchain
+        // (rustc, native-extension build tools) has no business in the
+        // shipped image. `build_only: true` builds each in the `builder`
+        // stage (see `render_dockerfile`); only `artifacts` gets `COPY
+        // --from=builder`'d into the final one.
+        "pwn" => Some(vec![
+            ToolConfig {
+                name: "pwninit".to_string(),
+                script: vec![
+                    "RUN apt-get update && apt-get install -y cargo".to_string(),
+                    "RUN cargo install pwninit".to_string(),
+                ],
+                build_only: true,
+                append: false,
+                artifacts: vec!["/root/.cargo/bin/pwninit".to_string()],
+                verify: vec!["pwninit --help".to_string()],
+                secrets: Vec::new(),
+            },
+            // `one_gadget`'s gem is pure Ruby, but it still needs a Ruby
+            // interpreter at runtime — this tool alone only drops
+            // `ruby-dev`/`build-essential`, not Ruby itself, so it's
+            // paired with the `ruby-runtime` tool below in the final
+            // stage.
+            ToolConfig {
+                name: "one_gadget".to_string(),
+                script: vec![
+                    "RUN apt-get update && apt-get install -y ruby-dev build-essential".to_string(),
+                    "RUN gem install --no-document --bindir /usr/local/bin one_gadget".to_string(),
+                ],
+                build_only: true,
+                append: false,
+                artifacts: vec!["/usr/local/bin/one_gadget".to_string(), "/var/lib/gems".to_string()],
+                verify: vec!["one_gadget --help".to_string()],
+                secrets: Vec::new(),
+            },
+            ToolConfig {
+                name: "ruby-runtime".to_string(),
+                script: vec!["RUN apt-get update && apt-get install -y ruby".to_string()],
+                build_only: false,
+                append: false,
+                artifacts: Vec::new(),
+                verify: vec!["ruby --version".to_string()],
+                secrets: Vec::new(),
+            },
+        ]),
+        "sandboxing" => Some(vec![ToolConfig {
+            name: "seccomp".to_string(),
+            script: vec![
+                "RUN apt-get update".to_string(),
+                "RUN apt-get install -y libseccomp2".to_string(),
+                "RUN apt-get install -y libseccomp-dev".to_string(),
+                "RUN apt-get install -y seccomp-tools".to_string(),
+            ],
+            build_only: false,
+            append: false,
+            artifacts: Vec::new(),
+            verify: vec!["seccomp-tools --help".to_string()],
+            secrets: Vec::new(),
+        }]),
+        "recording" => Some(vec![ToolConfig {
+            name: "asciinema".to_string(),
+            script: vec!["RUN apt-get update && apt-get install -y asciinema".to_string()],
+            build_only: false,
+            append: false,
+            artifacts: Vec::new(),
+            verify: vec!["asciinema --version".to_string()],
+            secrets: Vec::new(),
+        }]),
+        // Clones the full bminor/glibc mirror (not a shallow one — `pwnenv
+        // glibc build` needs to `git checkout` arbitrary release tags
+        // later, not just whatever ref was HEAD at image build time) plus
+        // the toolchain glibc's own build needs, so the container has
+        // everything `commands::glibc::build` shells out to already in
+        // place.
+        "glibc" => Some(vec![ToolConfig {
+            name: "glibc-src".to_string(),
+            script: vec![
+                "RUN apt-get update && apt-get install -y build-essential bison gawk gettext \
+                 texinfo python3 git patchelf"
+                    .to_string(),
+                "RUN git clone https://github.com/bminor/glibc.git /opt/src/glibc".to_string(),
+            ],
+            build_only: false,
+            append: false,
+            artifacts: Vec::new(),
+            verify: vec!["test -d /opt/src/glibc/.git".to_string()],
+            secrets: Vec::new(),
+        }]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversing_preset_installs_ghidra_radare2_and_rizin() {
+        let tools = lookup("reversing").unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["ghidra", "radare2", "rizin"]);
+    }
+
+    #[test]
+    fn ghidra_fetch_is_pinned_and_checksum_verified() {
+        let tools = lookup("reversing").unwrap();
+        let ghidra = tools.iter().find(|t| t.name == "ghidra").unwrap();
+        assert!(ghidra.script.iter().any(|line| line.contains("${GHIDRA_VERSION}")));
+        assert!(ghidra.script.iter().any(|line| line.contains("sha256sum -c")));
+    }
+
+    #[test]
+    fn apply_default_build_args_pins_ghidra_unless_already_set() {
+        let mut config = Config::default();
+        apply_default_build_args(&mut config, "reversing");
+        assert_eq!(config.build_args.get("GHIDRA_VERSION").map(String::as_str), Some("11.1.2"));
+
+        let mut overridden = Config::default();
+        overridden.build_args.insert("GHIDRA_VERSION".to_string(), "custom".to_string());
+        apply_default_build_args(&mut overridden, "reversing");
+        assert_eq!(overridden.build_args.get("GHIDRA_VERSION").map(String::as_str), Some("custom"));
+    }
+
+    #[test]
+    fn unknown_preset_has_no_pinned_args() {
+        let mut config = Config::default();
+        apply_default_build_args(&mut config, "windows");
+        assert!(config.build_args.is_empty());
+    }
+
+    #[test]
+    fn glibc_preset_clones_a_full_checkout_with_its_build_deps() {
+        let tools = lookup("glibc").unwrap();
+        let src = tools.iter().find(|t| t.name == "glibc-src").unwrap();
+        assert!(src.script.iter().any(|line| line.contains("bison")));
+        assert!(src
+            .script
+            .iter()
+            .any(|line| line.contains("git clone https://github.com/bminor/glibc.git /opt/src/glibc")));
+    }
+}
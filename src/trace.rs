@@ -0,0 +1,71 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+
+/// Writes a copy of a generated artifact into `--trace <dir>`, for
+/// attaching to a bug report. Each call is independent — it's the
+/// caller's job (`build`/`up`) to decide which artifacts it has to
+/// offer at the point it's generated them.
+pub fn write_artifact(trace_dir: &Path, file_name: &str, contents: &str) -> Result<()> {
+    std::fs::create_dir_all(trace_dir)?;
+    std::fs::write(trace_dir.join(file_name), contents)?;
+    Ok(())
+}
+
+/// Serializes `config` (after preset/`include_tools` resolution) for
+/// `--trace`, exactly as pwnenv resolved it. Usually the most useful
+/// single file in a bug report, since it's the union of `pwnenv.yaml`
+/// and whatever presets/bundles it pulled in.
+pub fn write_resolved_config(trace_dir: &Path, config: &Config) -> Result<()> {
+    let yaml = serde_yaml::to_string(config)
+        .map_err(|e| PwnenvError::Docker(format!("failed to serialize resolved config for --trace: {e}")))?;
+    write_artifact(trace_dir, "config.resolved.yaml", &yaml)
+}
+
+/// Writes a `.env` alongside the traced compose file, one line per
+/// `${VAR}`/`${VAR:-default}` reference it contains. Values are never
+/// copied in: a variable set in the environment is recorded as
+/// `<redacted>`, an unset one as `<unset>`, so a pasted bug report can't
+/// leak whatever secret the user's shell happened to have exported.
+pub fn write_redacted_env(trace_dir: &Path, compose: &str) -> Result<()> {
+    let mut names: Vec<String> = Vec::new();
+    for token in compose.split("${").skip(1) {
+        let Some(end) = token.find('}') else { continue };
+        let name = token[..end].split(':').next().unwrap_or(&token[..end]);
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+
+    let mut out = String::new();
+    for name in &names {
+        let placeholder = if std::env::var_os(name).is_some() { "<redacted>" } else { "<unset>" };
+        let _ = writeln!(out, "{name}={placeholder}");
+    }
+    write_artifact(trace_dir, ".env", &out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_references_are_redacted_not_copied() {
+        std::env::set_var("PWNENV_TRACE_TEST_VAR", "super-secret");
+        let dir = std::env::temp_dir().join(format!("pwnenv-trace-test-{}", std::process::id()));
+
+        let compose = "services:\n  chall:\n    environment:\n      - FOO=${PWNENV_TRACE_TEST_VAR}\n      - BAR=${UNSET_VAR:-default}\n";
+        write_redacted_env(&dir, compose).unwrap();
+        let env = std::fs::read_to_string(dir.join(".env")).unwrap();
+
+        assert!(env.contains("PWNENV_TRACE_TEST_VAR=<redacted>"));
+        assert!(env.contains("UNSET_VAR=<unset>"));
+        assert!(!env.contains("super-secret"));
+
+        std::env::remove_var("PWNENV_TRACE_TEST_VAR");
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
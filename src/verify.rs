@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+use crate::runtime::RuntimeDir;
+
+/// The outcome of one tool's one `verify` command, persisted so `status`
+/// can show the last run without re-execing into the container.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyResult {
+    pub tool: String,
+    pub command: String,
+    pub passed: bool,
+}
+
+/// Runs every `tools[].verify` command inside `container`, one `docker
+/// exec` per command. A tool with no `verify` commands contributes
+/// nothing to the result — there's no forced "at least smoke-test
+/// something" floor.
+pub fn run_verifications(container: &str, config: &Config) -> Vec<VerifyResult> {
+    let mut results = Vec::new();
+    for tool in &config.tools {
+        for command in &tool.verify {
+            results.push(VerifyResult {
+                tool: tool.name.clone(),
+                command: command.clone(),
+                passed: exec_check(container, command),
+            });
+        }
+    }
+    results
+}
+
+fn exec_check(container: &str, command: &str) -> bool {
+    Command::new("docker")
+        .args(["exec", container, "/bin/sh", "-c", command])
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Prints a pass/fail line per result, returning how many failed.
+pub fn print_results(results: &[VerifyResult]) -> usize {
+    let mut failures = 0;
+    for result in results {
+        let mark = if result.passed {
+            "ok"
+        } else {
+            failures += 1;
+            "FAIL"
+        };
+        println!("{mark:<4} {}: {}", result.tool, result.command);
+    }
+    failures
+}
+
+fn results_path(runtime: &RuntimeDir) -> PathBuf {
+    runtime.root().join("verify-results.json")
+}
+
+pub fn save_results(runtime: &RuntimeDir, results: &[VerifyResult]) -> Result<()> {
+    let json = serde_json::to_string_pretty(results)
+        .map_err(|e| PwnenvError::Docker(format!("failed to serialize verify results: {e}")))?;
+    std::fs::write(results_path(runtime), json)?;
+    Ok(())
+}
+
+/// The results saved from the last `up`/`verify` run, if any. Never
+/// errors — a missing or unreadable results file just means "nothing
+/// recorded yet".
+pub fn load_results(runtime: &RuntimeDir) -> Vec<VerifyResult> {
+    std::fs::read_to_string(results_path(runtime))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_runtime(label: &str) -> RuntimeDir {
+        let runtime = RuntimeDir::new(&format!("verify-test-{label}-{}", std::process::id()));
+        runtime.ensure_exists().unwrap();
+        runtime
+    }
+
+    #[test]
+    fn a_tool_with_no_verify_commands_contributes_nothing() {
+        let config = Config::default();
+        assert!(run_verifications("some-container", &config).is_empty());
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let runtime = test_runtime("roundtrip");
+        let results = vec![VerifyResult {
+            tool: "gdb".to_string(),
+            command: "gdb --version".to_string(),
+            passed: true,
+        }];
+        save_results(&runtime, &results).unwrap();
+        let loaded = load_results(&runtime);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].tool, "gdb");
+        assert!(loaded[0].passed);
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+
+    #[test]
+    fn load_results_with_nothing_saved_is_empty() {
+        let runtime = test_runtime("empty");
+        assert!(load_results(&runtime).is_empty());
+        std::fs::remove_dir_all(runtime.root()).ok();
+    }
+
+    #[test]
+    fn print_results_counts_failures() {
+        let results = vec![
+            VerifyResult { tool: "a".to_string(), command: "true".to_string(), passed: true },
+            VerifyResult { tool: "b".to_string(), command: "false".to_string(), passed: false },
+            VerifyResult { tool: "c".to_string(), command: "false".to_string(), passed: false },
+        ];
+        assert_eq!(print_results(&results), 2);
+    }
+}
@@ -0,0 +1,200 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use semver::Version;
+
+use crate::config::Config;
+use crate::error::{PwnenvError, Result};
+
+/// The running pwnenv's own version, compared against a config's
+/// `generated_by` to decide whether it predates or postdates this binary.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// How `generated_by` compares to [`CURRENT_VERSION`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum VersionState {
+    /// No `generated_by`, or it matches the running binary exactly.
+    Current,
+    /// `generated_by` is older: the config may be missing fields a newer
+    /// pwnenv would have stamped in. Worth a `config upgrade`/`config
+    /// diff`, but not worth blocking on.
+    ConfigOlder,
+    /// `generated_by` is newer than the binary running it: the config may
+    /// rely on fields this binary doesn't understand. Mutating commands
+    /// refuse to proceed (see [`guard_mutating`]).
+    ConfigNewer,
+}
+
+/// Compares `config.generated_by` against [`CURRENT_VERSION`] with a real
+/// semver ordering, not a string compare — `0.9.0` must sort before
+/// `0.10.0`, and a pre-release like `0.10.0-rc.1` must sort before its
+/// final release. An absent or unparsable `generated_by` is treated as
+/// `0.0.0`, i.e. older than anything.
+pub fn compare(config: &Config) -> VersionState {
+    let current = parse_or_zero(CURRENT_VERSION);
+    let generated_by = config
+        .generated_by
+        .as_deref()
+        .map(parse_or_zero)
+        .unwrap_or_else(|| Version::new(0, 0, 0));
+
+    match generated_by.cmp(&current) {
+        std::cmp::Ordering::Less => VersionState::ConfigOlder,
+        std::cmp::Ordering::Equal => VersionState::Current,
+        std::cmp::Ordering::Greater => VersionState::ConfigNewer,
+    }
+}
+
+fn parse_or_zero(raw: &str) -> Version {
+    Version::parse(raw).unwrap_or_else(|_| Version::new(0, 0, 0))
+}
+
+/// Refuses to proceed if `config` was written by a newer pwnenv than this
+/// one — a mutating command (`init`/`build`/`up`) guessing at fields it
+/// doesn't know about is worse than a clear error telling the user to
+/// upgrade.
+pub fn guard_mutating(path: &Path, config: &Config) -> Result<()> {
+    if compare(config) == VersionState::ConfigNewer {
+        return Err(PwnenvError::ConfigNewerThanBinary {
+            path: path.to_path_buf(),
+            config_version: config.generated_by.clone().unwrap_or_default(),
+            binary_version: CURRENT_VERSION.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Prints a one-line notice for a stale or newer config. `ConfigNewer` is
+/// printed every time (anything read-only still slipped past
+/// [`guard_mutating`], so it's worth repeating). `ConfigOlder` is throttled
+/// to once per day via a marker in [`crate::runtime::state_dir`], since
+/// every single command would otherwise repeat it.
+pub fn notify(config: &Config) {
+    match compare(config) {
+        VersionState::Current => {}
+        VersionState::ConfigNewer => {
+            eprintln!(
+                "warning: pwnenv.yaml was generated by a newer pwnenv ({}) than this one ({CURRENT_VERSION}); some fields may be ignored.",
+                config.generated_by.as_deref().unwrap_or("unknown")
+            );
+        }
+        VersionState::ConfigOlder => {
+            if should_show_daily_notice() {
+                eprintln!(
+                    "note: pwnenv.yaml predates this pwnenv ({CURRENT_VERSION}). Run `pwnenv config diff` to see what's new, or `pwnenv config upgrade` to stamp it."
+                );
+            }
+        }
+    }
+}
+
+fn version_notice_marker() -> std::path::PathBuf {
+    crate::runtime::state_dir().join(".version-notice-last-shown")
+}
+
+/// True at most once per calendar day (by elapsed seconds, not wall-clock
+/// midnight — good enough for a "don't nag every single command" notice).
+fn should_show_daily_notice() -> bool {
+    const ONE_DAY_SECS: u64 = 24 * 60 * 60;
+    let marker = version_notice_marker();
+    let now = now_unix();
+
+    let last_shown = std::fs::read_to_string(&marker)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok());
+
+    if let Some(last_shown) = last_shown {
+        if now.saturating_sub(last_shown) < ONE_DAY_SECS {
+            return false;
+        }
+    }
+
+    if let Some(parent) = marker.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(&marker, now.to_string());
+    true
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_generated_by_is_treated_as_older() {
+        let config = Config::default();
+        assert_eq!(compare(&config), VersionState::ConfigOlder);
+    }
+
+    #[test]
+    fn matching_generated_by_is_current() {
+        let config = Config {
+            generated_by: Some(CURRENT_VERSION.to_string()),
+            ..Config::default()
+        };
+        assert_eq!(compare(&config), VersionState::Current);
+    }
+
+    #[test]
+    fn older_patch_version_is_config_older() {
+        let current = parse_or_zero(CURRENT_VERSION);
+        let older = Version::new(current.major, current.minor.max(1) - 1, 0);
+        let config = Config {
+            generated_by: Some(older.to_string()),
+            ..Config::default()
+        };
+        assert_eq!(compare(&config), VersionState::ConfigOlder);
+    }
+
+    #[test]
+    fn newer_major_version_is_config_newer() {
+        let current = parse_or_zero(CURRENT_VERSION);
+        let newer = Version::new(current.major + 1, 0, 0);
+        let config = Config {
+            generated_by: Some(newer.to_string()),
+            ..Config::default()
+        };
+        assert_eq!(compare(&config), VersionState::ConfigNewer);
+    }
+
+    #[test]
+    fn prerelease_sorts_before_its_final_release() {
+        // 1.0.0-rc.1 must compare as older than 1.0.0, not as a string
+        // ("1.0.0-rc.1" > "1.0.0" lexicographically, which would be wrong).
+        let pre = Version::parse("1.0.0-rc.1").unwrap();
+        let release = Version::parse("1.0.0").unwrap();
+        assert!(pre < release);
+    }
+
+    #[test]
+    fn nonsense_generated_by_is_treated_as_older_not_an_error() {
+        let config = Config {
+            generated_by: Some("not-a-version".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(compare(&config), VersionState::ConfigOlder);
+    }
+
+    #[test]
+    fn guard_mutating_rejects_a_newer_config() {
+        let current = parse_or_zero(CURRENT_VERSION);
+        let newer = Version::new(current.major + 1, 0, 0);
+        let config = Config {
+            generated_by: Some(newer.to_string()),
+            ..Config::default()
+        };
+        assert!(guard_mutating(Path::new("pwnenv.yaml"), &config).is_err());
+    }
+
+    #[test]
+    fn guard_mutating_allows_an_older_or_current_config() {
+        assert!(guard_mutating(Path::new("pwnenv.yaml"), &Config::default()).is_ok());
+    }
+}